@@ -165,22 +165,32 @@ async fn main() -> anyhow::Result<()> {
         .with_mouse(true)
         .with_performance_monitoring(true)
         .with_tick_rate(60.0)
-        .with_frame_rate(60.0);
+        .with_frame_rate(60.0)
+        .with_exit_message(|app| {
+            let metrics = app.get_metrics();
+            [
+                "\n📈 Final Performance Report:".to_string(),
+                format!("  Events Processed: {}", metrics.events_processed),
+                format!("  Actions Processed: {}", metrics.actions_processed),
+                format!("  Average Event Batch: {:.2}", metrics.average_event_batch_size),
+                format!("  Average Action Batch: {:.2}", metrics.average_action_batch_size),
+                format!("  Total Render Time: {:?}", metrics.total_render_time),
+                format!(
+                    "  Total Event Processing Time: {:?}",
+                    metrics.total_event_processing_time
+                ),
+                format!("  Final FPS: {:.1}", metrics.last_fps),
+                format!("  Average Cells Changed: {:.1}", metrics.average_cells_changed),
+                format!("  Max Cells Changed: {}", metrics.max_cells_changed),
+                format!("  Worst Loop Iteration: {:?}", metrics.worst_loop_time),
+            ]
+            .join("\n")
+        });
 
     println!("🚀 Starting optimized counter app with performance monitoring...");
     println!("📊 Try rapid key presses to see the performance metrics!");
 
     app.run().await?;
 
-    let metrics = app.get_metrics();
-    println!("\n📈 Final Performance Report:");
-    println!("  Events Processed: {}", metrics.events_processed);
-    println!("  Actions Processed: {}", metrics.actions_processed);
-    println!("  Average Event Batch: {:.2}", metrics.average_event_batch_size);
-    println!("  Average Action Batch: {:.2}", metrics.average_action_batch_size);
-    println!("  Total Render Time: {:?}", metrics.total_render_time);
-    println!("  Total Event Processing Time: {:?}", metrics.total_event_processing_time);
-    println!("  Final FPS: {:.1}", metrics.last_fps);
-
     Ok(())
 }
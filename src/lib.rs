@@ -45,14 +45,156 @@ pub mod prelude {
         Component, ComponentAccessor,
         app::App,
         components,
-        event::{Action, Event},
+        event::{
+            Action, Event, EventMask, EventPattern, EventSource, Message, NotifyLevel, Priority,
+        },
         kb,
-        keyboard::{KeyBindings, key_event_to_string},
+        keyboard::{
+            DoubleTapBindings, KeyBindingInfo, KeyBindings, KeySeqResult, KeyboardLayout,
+            key_event_to_string, key_from_action,
+        },
         redux::{AppState, Store, StoreConnection, ActionStore, ActionStoreConnection},
-        tui::Tui,
+        tui::{RenderTarget, Tui},
     };
-    pub use weavetui_derive::component;
+    pub use weavetui_derive::{IntoAction, component};
 }
 
-pub use weavetui_core::{Component, ComponentAccessor, app, components, event, kb, keyboard, redux, tui};
-pub use weavetui_derive::component;
+pub use weavetui_core::{Component, ComponentAccessor, app, components, event, kb, keyboard, mouse, redux, rng, tui};
+pub use weavetui_derive::{IntoAction, component};
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use ratatui::{backend::TestBackend, layout::Rect, style::Style, Frame, Terminal};
+    use weavetui_core::{
+        component_manager::{handle_draw, ClipPolicy},
+        event::ActionKind,
+    };
+
+    #[component(container)]
+    struct Panel {}
+
+    #[component]
+    struct Marker {}
+
+    impl Component for Marker {
+        fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+            f.buffer_mut().set_string(area.x, area.y, "child", Style::default());
+        }
+    }
+
+    #[test]
+    fn test_containers_draw_only_their_children() {
+        let mut panel = Panel::default();
+        let mut marker = Marker::default();
+        marker.set_area(Rect::new(2, 0, 5, 1));
+        panel.child_insert("marker", Box::new(marker));
+        panel.set_area(Rect::new(0, 0, 10, 1));
+        assert!(panel.is_container());
+        assert!(!Marker::default().is_container());
+
+        let mut terminal = Terminal::new(TestBackend::new(10, 1)).unwrap();
+        terminal.draw(|f| handle_draw(&mut panel, f, ClipPolicy::Clip)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let row = (0..10).map(|x| buffer[(x, 0)].symbol()).collect::<String>();
+        assert_eq!(row, "  child   ");
+    }
+
+    #[derive(Debug, PartialEq, IntoAction)]
+    enum EditorAction {
+        Save,
+        Open(String),
+    }
+
+    #[test]
+    fn test_into_action_wraps_variants_in_custom_actions() {
+        let save = Action::from(EditorAction::Save);
+        assert!(matches!(save, Action::Custom(_)));
+        assert_eq!(save.as_custom::<EditorAction>(), Some(&EditorAction::Save));
+        assert_eq!(save, Action::from(EditorAction::Save));
+
+        let open = Action::from(EditorAction::Open("notes.md".to_string()));
+        assert_eq!(
+            open.as_custom::<EditorAction>(),
+            Some(&EditorAction::Open("notes.md".to_string()))
+        );
+        assert_ne!(open, save);
+        assert_eq!(open.as_custom::<String>(), None);
+
+        match ActionKind::from(EditorAction::Open("todo.md".to_string())) {
+            ActionKind::Full(action) => assert_eq!(
+                action.as_custom::<EditorAction>(),
+                Some(&EditorAction::Open("todo.md".to_string()))
+            ),
+            ActionKind::Stringified(s) => panic!("expected a full action, got {:?}", s),
+        }
+
+        let keybindings = KeyBindings::new(kb!["<ctrl-s>" => EditorAction::Save]);
+        let action = keybindings.0.values().next().unwrap();
+        assert_eq!(action.as_custom::<EditorAction>(), Some(&EditorAction::Save));
+    }
+
+    #[cfg(feature = "serde")]
+    mod snapshots {
+        use crate::prelude::*;
+        use weavetui_core::{
+            component_manager::{restore_tree, snapshot_tree},
+            serde_json::json,
+        };
+
+        #[component(default, serde)]
+        struct Counter {
+            count: u32,
+            label: String,
+        }
+
+        fn counter(count: u32, label: &str) -> Counter {
+            Counter {
+                count,
+                label: label.to_string(),
+                ..Default::default()
+            }
+        }
+
+        fn tree(counts: [u32; 3], label: &str) -> Counter {
+            let mut child = counter(counts[1], label);
+            child.child_insert("leaf", Box::new(counter(counts[2], label)));
+            let mut root = counter(counts[0], label);
+            root.child_insert("child", Box::new(child));
+            root
+        }
+
+        fn counts(root: &mut Counter) -> (u32, u32, u32, String) {
+            let root_count = root.count;
+            let child = root.child_mut("child").unwrap().downcast_mut::<Counter>().unwrap();
+            let child_count = child.count;
+            let leaf = child.child("leaf").unwrap().downcast_ref::<Counter>().unwrap();
+            (root_count, child_count, leaf.count, leaf.label.clone())
+        }
+
+        #[test]
+        fn test_tree_snapshots_round_trip() {
+            let snapshot = snapshot_tree(&mut tree([1, 2, 3], "old"));
+
+            // Only user fields are captured, children nested under their names
+            assert_eq!(snapshot["state"], json!({ "count": 1, "label": "old" }));
+            let leaf = &snapshot["children"]["child"]["children"]["leaf"];
+            assert_eq!(leaf, &json!({ "state": { "count": 3, "label": "old" }, "children": {} }));
+
+            let mut restored = tree([0, 0, 0], "new");
+            restore_tree(&mut restored, &snapshot);
+            assert_eq!(counts(&mut restored), (1, 2, 3, "old".to_string()));
+
+            // Unknown children, and fields or children missing from the snapshot, are skipped
+            let mut partial = snapshot.clone();
+            partial["children"]["gone"] = json!({ "state": { "count": 9 }, "children": {} });
+            partial["children"]["child"]["children"]["leaf"]["state"] = json!({ "count": 7 });
+            let mut restored = tree([0, 0, 0], "new");
+            restored.child_mut("child").unwrap().child_insert("extra", Box::new(counter(5, "x")));
+            restore_tree(&mut restored, &partial);
+            assert_eq!(counts(&mut restored), (1, 2, 7, "new".to_string()));
+            let extra = restored.child_mut("child").unwrap().child("extra").unwrap();
+            assert_eq!(extra.downcast_ref::<Counter>().unwrap().count, 5);
+        }
+    }
+}
@@ -1,16 +1,39 @@
 //! Application module for `weavetui`.
 
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
-use tokio::sync::mpsc::{self, error::TryRecvError};
-use std::time::{Duration, Instant};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+use tokio::{
+    sync::mpsc::{self, error::TryRecvError},
+    time::MissedTickBehavior,
+};
+use tokio_util::sync::CancellationToken;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    event::{Action, ActionKind, Event},
-    keyboard::KeyBindings,
+    component_manager::ClipPolicy,
+    components::{Corner, Notifications},
+    event::{Action, ActionKind, Event, EventPattern, EventSource, Message, NotifyLevel, Priority},
+    geometry::centered_rect,
+    keyboard::{
+        key_event_to_string, without_lock_state, DoubleTapBindings, KeyBindings, KeySeqResult,
+        KeyboardLayout,
+    },
+    mouse::MouseBindings,
+    redux::{AppState, Store},
+    rng::SharedRng,
     theme::{Theme, ThemeManager},
-    tui::Tui,
-    Component, ComponentHandler,
+    tui::{RenderTarget, Tui},
+    Component, ComponentHandler, EventSourceCell, LocalStateCache,
 };
 
 #[derive(Debug, Clone)]
@@ -22,6 +45,38 @@ pub struct AppConfig {
     pub max_events_per_batch: usize,
     pub max_actions_per_batch: usize,
     pub enable_performance_monitoring: bool,
+    /// Smallest terminal size (width, height) the UI can be drawn in
+    pub min_size: Option<(u16, u16)>,
+    /// How long a pending component key sequence waits for its next key
+    pub key_sequence_timeout: Duration,
+    /// How often to record a copy of the metrics in the metrics history, if at all
+    pub metrics_snapshot_interval: Option<Duration>,
+    /// Most snapshots kept in the metrics history; the oldest are dropped first
+    pub metrics_history_limit: usize,
+    /// What to do when a component draws outside its area
+    pub clip_policy: ClipPolicy,
+    /// Keep input received while paused and handle it on resume instead of dropping it
+    pub replay_paused_input: bool,
+    /// Longest a rendered frame's event, action and render work may take; `None` means
+    /// `1 / frame_rate`
+    pub frame_budget: Option<Duration>,
+    /// Draw inline in a viewport this many rows tall instead of on the alternate screen
+    pub inline_height: Option<u16>,
+    /// What the tick and render timers do after falling behind; skipping avoids a burst of
+    /// catch-up ticks after a slow frame
+    pub missed_tick_behavior: MissedTickBehavior,
+    /// Keep the default keybindings (ctrl-c to quit) when keybindings are replaced
+    pub keep_default_keybindings: bool,
+    /// The stream the UI is drawn on; stderr leaves stdout free for piping
+    pub render_to: RenderTarget,
+    /// Layout the user types on, for matching `<phys:...>` bindings by key position
+    pub keyboard_layout: KeyboardLayout,
+    /// Fewest printable keys read from the terminal at once to take as a paste, if any
+    pub paste_fallback: Option<usize>,
+    /// Use the kitty keyboard protocol where the terminal supports it
+    pub keyboard_enhancement: bool,
+    /// Draw a "No components" message when the app has no components
+    pub empty_placeholder: bool,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -33,6 +88,18 @@ pub struct PerformanceMetrics {
     pub total_render_time: Duration,
     pub total_event_processing_time: Duration,
     pub last_fps: f64,
+    /// Cells that changed between the last two rendered frames
+    pub last_cells_changed: usize,
+    /// Largest number of cells changed in a single frame
+    pub max_cells_changed: usize,
+    /// Average number of cells changed per frame
+    pub average_cells_changed: f64,
+    /// Longest time spent in one run loop iteration, excluding waiting for input
+    pub worst_loop_time: Duration,
+    /// Rendered frames whose work took longer than the frame budget
+    pub frames_over_budget: u64,
+    /// Render requests put off because the previous frame was drawn too recently
+    pub frames_coalesced: u64,
     last_frame_time: Option<Instant>,
     frame_count: u64,
 }
@@ -47,6 +114,21 @@ impl Default for AppConfig {
             max_events_per_batch: 32,
             max_actions_per_batch: 64,
             enable_performance_monitoring: false,
+            min_size: None,
+            key_sequence_timeout: Duration::from_secs(1),
+            metrics_snapshot_interval: None,
+            metrics_history_limit: 60,
+            clip_policy: ClipPolicy::default(),
+            replay_paused_input: false,
+            frame_budget: None,
+            inline_height: None,
+            missed_tick_behavior: MissedTickBehavior::Skip,
+            keep_default_keybindings: false,
+            render_to: RenderTarget::Stdout,
+            keyboard_layout: KeyboardLayout::Qwerty,
+            paste_fallback: None,
+            keyboard_enhancement: false,
+            empty_placeholder: false,
         }
     }
 }
@@ -56,38 +138,215 @@ pub struct App {
     config: AppConfig,
     should_quit: bool,
     keybindings: KeyBindings,
+    /// Bindings declared by components, keyed by tree path in tree order
+    component_keybindings: Vec<(String, KeyBindings)>,
+    /// Bindings components declared as global, applying regardless of focus
+    global_keybindings: KeyBindings,
+    mouse_bindings: MouseBindings,
+    event_bindings: Vec<(EventPattern, Action)>,
     last_tick_key_events: Vec<KeyEvent>,
+    double_taps: DoubleTapBindings,
+    /// Keys buffered for the focused component's `handle_key_sequence`
+    pending_keys: Vec<KeyEvent>,
+    pending_keys_since: Option<Instant>,
     component_handlers: Vec<ComponentHandler>,
     theme_manager: ThemeManager,
     action_tx: mpsc::UnboundedSender<Action>,
     action_rx: mpsc::UnboundedReceiver<Action>,
-    event_batch: Vec<Event>,
+    /// Actions taken off the channel but not yet batched, one queue per `Priority`
+    action_lanes: [VecDeque<Action>; 3],
+    /// Priorities for app actions and message topics, overriding `Action::priority`
+    action_priorities: HashMap<String, Priority>,
+    /// Typed actions that bound app action names resolve to, see `register_action`
+    registered_actions: HashMap<String, ActionFactory>,
+    /// App actions and message topics collapsed when repeated in a batch
+    idempotent_actions: HashSet<String>,
+    event_batch: Vec<(Event, EventSource)>,
+    /// Events sent through an `EventInjector`, with their source
+    injected_tx: mpsc::UnboundedSender<(Event, EventSource)>,
+    injected_rx: mpsc::UnboundedReceiver<(Event, EventSource)>,
+    /// Source of the event being handled, shared with every component
+    event_source: EventSourceCell,
+    /// Random number generator shared with components
+    rng: SharedRng,
+    /// Input recorded since `start_recording`, if recording
+    recording: Option<Vec<Event>>,
+    /// Events queued by `play` that haven't been handled yet
+    replay_pending: usize,
     action_batch: Vec<Action>,
     metrics: PerformanceMetrics,
+    metrics_history: VecDeque<PerformanceMetrics>,
+    last_metrics_snapshot: Option<Instant>,
+    previous_buffer: Option<Buffer>,
+    /// When the run loop last drew a frame, for capping renders at the frame rate
+    last_render: Option<Instant>,
+    input_paused: bool,
+    /// Input received while paused, kept if `replay_paused_input` is set
+    paused_input: Vec<(Event, EventSource)>,
+    paste_broadcast_warned: bool,
+    /// Whether the first frame over budget was already warned about
+    frame_budget_warned: bool,
+    startup_actions: Vec<Action>,
+    local_state: LocalStateCache,
+    /// Cancelled when `run` returns, stopping tasks spawned by components
+    shutdown: CancellationToken,
+    tick_callback: Option<TickCallback>,
+    unbound_key_callback: Option<UnboundKeyCallback>,
+    exit_message: Option<ExitMessage>,
+    io_error_callback: Option<IoErrorCallback>,
+    render_hook: Option<RenderHook>,
+    /// Stores whose coalesced notifications are sent before each frame
+    store_flushes: Vec<StoreFlush>,
+    /// Terminal read errors since the last input event
+    io_errors: u32,
+    /// Why the app quit on its own, printed to stderr once the terminal is restored
+    exit_error: Option<String>,
+    /// Root to focus once components are wired up
+    initial_focus: Option<String>,
+    #[cfg(feature = "watch")]
+    theme_watcher: Option<crate::theme::ThemeWatcher>,
+    /// Whether components have been wired up to the app
+    wired: bool,
+    /// Whether components have been initialized, which happens on the first draw
+    initialized: bool,
+}
+
+/// Handle to a root component added with [`App::add_component_named`]
+#[derive(Debug)]
+pub struct RootHandle<'a> {
+    app: &'a mut App,
+    name: String,
+}
+
+impl RootHandle<'_> {
+    /// Give the component focus, once components are wired up if they aren't yet (chainable)
+    pub fn focus(self) -> Self {
+        if self.app.wired {
+            self.app.focus(&self.name);
+        } else {
+            self.app.initial_focus = Some(self.name.clone());
+        }
+        self
+    }
+
+    /// Get the name the component was added under
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+type TickCallback = Hook<dyn FnMut(&mut App)>;
+type UnboundKeyCallback = Hook<dyn FnMut(&KeyEvent, &[String])>;
+type ExitMessage = Hook<dyn FnOnce(&App) -> String>;
+type IoErrorCallback = Hook<dyn FnMut()>;
+type RenderHook = Hook<dyn FnMut(&mut Buffer)>;
+type StoreFlush = Hook<dyn Fn()>;
+type ActionFactory = Hook<dyn Fn() -> Action>;
+
+/// Terminal read errors in a row after which the app gives up and quits
+const MAX_IO_ERRORS: u32 = 3;
+
+/// Most rounds of events and actions `App::process_pending` handles before giving up
+const MAX_PENDING_ROUNDS: usize = 1000;
+
+/// How long warnings the app raises itself stay on screen
+pub(crate) const WARNING_TTL: Duration = Duration::from_secs(5);
+
+/// Handle for feeding events to a running [`App`], from [`App::injector`]
+#[derive(Debug, Clone)]
+pub struct EventInjector {
+    tx: mpsc::UnboundedSender<(Event, EventSource)>,
+}
+
+impl EventInjector {
+    /// Send an event tagged as `EventSource::Injected`
+    pub fn inject(&self, event: Event) {
+        self.send(event, EventSource::Injected);
+    }
+
+    /// Send an event with the given source, e.g. `EventSource::Replay` when playing back a
+    /// recording
+    pub fn send(&self, event: Event, source: EventSource) {
+        let _ = self.tx.send((event, source));
+    }
+}
+
+/// A boxed callback stored on the app, opaque in debug output
+struct Hook<F: ?Sized>(Box<F>);
+
+impl<F: ?Sized> fmt::Debug for Hook<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<callback>")
+    }
 }
 
 impl Default for App {
     fn default() -> Self {
         let (action_tx, action_rx) = mpsc::unbounded_channel::<Action>();
+        let (injected_tx, injected_rx) = mpsc::unbounded_channel();
         let config = AppConfig::default();
         Self {
             last_tick_key_events: Vec::default(),
+            double_taps: DoubleTapBindings::default(),
+            pending_keys: Vec::new(),
+            pending_keys_since: None,
             keybindings: KeyBindings::default(),
+            component_keybindings: Vec::new(),
+            global_keybindings: KeyBindings::empty(),
+            mouse_bindings: MouseBindings::default(),
+            event_bindings: Vec::new(),
             component_handlers: Vec::new(),
             theme_manager: ThemeManager::default(),
             should_quit: false,
             action_tx,
             action_rx,
+            action_lanes: Default::default(),
+            action_priorities: HashMap::new(),
+            registered_actions: HashMap::new(),
+            idempotent_actions: HashSet::new(),
             event_batch: Vec::with_capacity(config.max_events_per_batch),
+            injected_tx,
+            injected_rx,
+            event_source: EventSourceCell::default(),
+            rng: SharedRng::default(),
+            recording: None,
+            replay_pending: 0,
             action_batch: Vec::with_capacity(config.max_actions_per_batch),
             metrics: PerformanceMetrics::default(),
+            metrics_history: VecDeque::new(),
+            last_metrics_snapshot: None,
+            previous_buffer: None,
+            last_render: None,
+            input_paused: false,
+            paused_input: Vec::new(),
+            paste_broadcast_warned: false,
+            frame_budget_warned: false,
+            startup_actions: Vec::new(),
+            local_state: LocalStateCache::default(),
+            shutdown: CancellationToken::new(),
+            tick_callback: None,
+            unbound_key_callback: None,
+            exit_message: None,
+            io_error_callback: None,
+            render_hook: None,
+            store_flushes: Vec::new(),
+            io_errors: 0,
+            exit_error: None,
+            initial_focus: None,
+            #[cfg(feature = "watch")]
+            theme_watcher: None,
+            wired: false,
+            initialized: false,
             config,
         }
     }
 }
 
 impl App {
-    /// Create an app with custom keybindings and components
+    /// Create an app with custom keybindings and components.
+    ///
+    /// `kb` replaces the default keybindings, so bind a key to quit or call
+    /// [`App::with_default_keybindings`] to keep ctrl-c.
     pub fn new<const N: usize>(kb: [(&str, &str); N], components: Vec<Box<dyn Component>>) -> Self {
         let keybindings = KeyBindings::new(kb);
 
@@ -103,19 +362,195 @@ impl App {
         }
     }
 
-    /// Add components to your app
+    /// Add components to your app.
+    ///
+    /// Root components are drawn in order, so later ones are drawn on top of earlier ones.
     pub fn with_components(mut self, components: Vec<Box<dyn Component>>) -> Self {
         self.component_handlers
             .extend(components.into_iter().map(ComponentHandler::for_));
         self
     }
 
-    /// Set keyboard shortcuts
+    /// Show `Action::Notify` toasts stacked in a corner of the screen.
+    ///
+    /// Adds a [`Notifications`](crate::components::Notifications) root component. It's drawn
+    /// above the roots before it, so call this after adding the other components.
+    pub fn with_notifications(self, corner: Corner) -> Self {
+        self.with_components(vec![Box::new(Notifications::new().with_corner(corner))])
+    }
+
+    /// Add a root component under its own name, returning a handle to set it up further.
+    ///
+    /// The name replaces the type name wherever the component is identified, e.g. for
+    /// [`App::focus`], so several roots of the same type can be told apart:
+    ///
+    /// ```ignore
+    /// app.add_component_named("sidebar", Box::new(Sidebar::default())).focus();
+    /// ```
+    pub fn add_component_named(
+        &mut self,
+        name: &str,
+        mut component: Box<dyn Component>,
+    ) -> RootHandle<'_> {
+        component.get_context_mut().name = Some(name.to_string());
+        let index = self.component_handlers.len();
+        // Appending can't be out of bounds
+        let _ = self.insert_component(index, component);
+
+        RootHandle {
+            app: self,
+            name: name.to_string(),
+        }
+    }
+
+    /// Insert a root component at `index`, shifting the ones after it back.
+    ///
+    /// Draw order follows the order of root components, so a higher index draws on top.
+    /// Fails if `index` is greater than the number of root components.
+    pub fn insert_component(&mut self, index: usize, component: Box<dyn Component>) -> Result<()> {
+        if index > self.component_handlers.len() {
+            anyhow::bail!(
+                "Component index {} is out of bounds for {} root components",
+                index,
+                self.component_handlers.len()
+            );
+        }
+
+        let mut handler = ComponentHandler::for_(component);
+        if self.wired {
+            self.wire_handler(&mut handler);
+        }
+        self.component_handlers.insert(index, handler);
+        if self.wired {
+            self.collect_component_keybindings();
+        }
+
+        Ok(())
+    }
+
+    /// Reorder root components, changing their draw order.
+    ///
+    /// `order[i]` is the current index of the component to move to position `i`, so
+    /// `[2, 0, 1]` moves the last of three components to the bottom. Fails unless `order`
+    /// lists every current index exactly once.
+    pub fn reorder_components(&mut self, order: &[usize]) -> Result<()> {
+        let len = self.component_handlers.len();
+        let mut seen = vec![false; len];
+        let valid = order.len() == len
+            && order
+                .iter()
+                .all(|&index| index < len && !std::mem::replace(&mut seen[index], true));
+        if !valid {
+            anyhow::bail!("Invalid component order {:?} for {} root components", order, len);
+        }
+
+        let mut handlers = self.component_handlers.drain(..).map(Some).collect::<Vec<_>>();
+        self.component_handlers = order
+            .iter()
+            .filter_map(|&index| handlers[index].take())
+            .collect();
+        if self.wired {
+            self.collect_component_keybindings();
+        }
+
+        Ok(())
+    }
+
+    /// Set keyboard shortcuts, replacing the ones set before.
+    ///
+    /// The defaults (ctrl-c to quit) are replaced too, unless kept with
+    /// [`App::with_default_keybindings`]. Use [`App::merge_keybindings`] to add shortcuts to
+    /// the current ones instead.
     pub fn with_keybindings<const N: usize>(
         mut self,
         kb: [(&str, impl Into<ActionKind>); N],
     ) -> Self {
         self.keybindings = KeyBindings::new(kb);
+        if self.config.keep_default_keybindings {
+            self.keybindings = with_defaults(std::mem::take(&mut self.keybindings));
+        }
+        self
+    }
+
+    /// Add keyboard shortcuts to the current ones (chainable).
+    ///
+    /// A key sequence that's already bound is rebound to the new action; everything else,
+    /// including the default ctrl-c to quit, stays.
+    pub fn merge_keybindings<const N: usize>(
+        mut self,
+        kb: [(&str, impl Into<ActionKind>); N],
+    ) -> Self {
+        self.keybindings.extend(KeyBindings::new(kb));
+        self
+    }
+
+    /// Keep the default keybindings (ctrl-c to quit) when keybindings are replaced by
+    /// [`App::new`] or [`App::with_keybindings`] (chainable).
+    ///
+    /// Turning it on adds the defaults back right away, under any key already bound to
+    /// something else, so it can be called before or after setting keybindings.
+    pub fn with_default_keybindings(mut self, keep: bool) -> Self {
+        self.config.keep_default_keybindings = keep;
+        if keep {
+            self.keybindings = with_defaults(std::mem::take(&mut self.keybindings));
+        }
+        self
+    }
+
+    /// Send `action` when `key` is pressed twice within `within`, e.g. `<esc>` twice to quit.
+    ///
+    /// The first press is handled as usual; the second one only fires the double tap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` isn't a single valid key.
+    pub fn with_double_tap(
+        mut self,
+        key: &str,
+        action: impl Into<ActionKind>,
+        within: Duration,
+    ) -> Self {
+        self.double_taps.bind_double(key, action, within);
+        self
+    }
+
+    /// Resolve `name` in this app's bindings to the action built by `factory`, e.g. so
+    /// `"save"` maps to a typed `Action::Custom` instead of `Action::AppAction("save")`
+    /// (chainable).
+    ///
+    /// The name is resolved when a key, mouse or event binding fires, so it covers the
+    /// bindings components return from `keybindings` and those added later too. Other apps
+    /// in the process aren't affected. Registering a name again replaces it.
+    pub fn register_action(mut self, name: &str, factory: impl Fn() -> Action + 'static) -> Self {
+        self.registered_actions
+            .insert(name.to_string(), Hook(Box::new(factory)));
+        self
+    }
+
+    /// Replace a bound app action with the typed action registered for its name, if any
+    fn resolve_registered(&self, action: Action) -> Action {
+        match &action {
+            Action::AppAction(name) => self
+                .registered_actions
+                .get(name)
+                .map_or(action, |factory| (factory.0)()),
+            _ => action,
+        }
+    }
+
+    /// Send actions for terminal events other than keys and mouse input, e.g.
+    /// `(EventPattern::FocusLost, "app:pause")` (chainable).
+    ///
+    /// Every matching binding fires, in the order added, and the event still reaches
+    /// components as usual. Resize thresholds are checked on every resize, not only when the
+    /// size crosses them, so `ResizeBelow` fires again on each resize while the terminal is
+    /// small.
+    pub fn with_event_bindings<const N: usize>(
+        mut self,
+        bindings: [(EventPattern, impl Into<ActionKind>); N],
+    ) -> Self {
+        self.event_bindings
+            .extend(bindings.into_iter().map(|(pattern, cmd)| (pattern, cmd.into().into())));
         self
     }
 
@@ -131,262 +566,2766 @@ impl App {
         self
     }
 
+    /// Set what the tick and render timers do after falling behind, e.g. after a slow
+    /// frame. See [`Tui::missed_tick_behavior`] for the choices.
+    pub fn with_missed_tick_behavior(mut self, behavior: MissedTickBehavior) -> Self {
+        self.config.missed_tick_behavior = behavior;
+        self
+    }
+
+    /// Set how long a frame's work may take before it counts as over budget.
+    ///
+    /// Defaults to `1 / frame_rate`. Only checked with performance monitoring on, where
+    /// overruns are counted in `PerformanceMetrics::frames_over_budget`. The first one also
+    /// raises an in-app warning, shown by [`App::with_notifications`].
+    pub fn with_frame_budget(mut self, budget: Duration) -> Self {
+        self.config.frame_budget = Some(budget);
+        self
+    }
+
     /// Enable mouse support
     pub fn with_mouse(mut self, mouse: bool) -> Self {
         self.config.mouse = mouse;
         self
     }
 
+    /// Map mouse wheel and button events to actions, like keybindings do for keys.
+    ///
+    /// Also enables mouse support.
+    pub fn with_mouse_bindings<const N: usize>(
+        mut self,
+        bindings: [(MouseEventKind, impl Into<ActionKind>); N],
+    ) -> Self {
+        self.mouse_bindings.extend(MouseBindings::new(bindings));
+        self.config.mouse = true;
+        self
+    }
+
+    /// Map mouse events to actions only while the cursor is over the named component.
+    ///
+    /// Scoped bindings take precedence over ones added with [`App::with_mouse_bindings`].
+    /// Also enables mouse support.
+    pub fn with_scoped_mouse_bindings<const N: usize>(
+        mut self,
+        component: &str,
+        bindings: [(MouseEventKind, impl Into<ActionKind>); N],
+    ) -> Self {
+        for (kind, action) in bindings {
+            self.mouse_bindings.add_scoped(kind, component, action);
+        }
+        self.config.mouse = true;
+        self
+    }
+
     /// Enable clipboard paste support
     pub fn with_paste(mut self, paste: bool) -> Self {
         self.config.paste = paste;
         self
     }
 
-    /// Add a theme to your app
-    pub fn add_theme(mut self, theme: Theme) -> Self {
-        if !self.theme_manager.has_active_theme() {
-            self.theme_manager.set_active_theme(&theme.name);
-        }
+    /// Seed the random number generator components get from `ComponentContext::rng`, so
+    /// randomized output is the same on every run, e.g. in snapshot tests.
+    ///
+    /// Without a seed the generator is seeded differently on each run. It's not
+    /// cryptographically secure.
+    pub fn with_seed(self, seed: u64) -> Self {
+        self.rng.reseed(seed);
+        self
+    }
 
-        self.theme_manager.add_theme(theme);
+    /// Use the kitty keyboard protocol on terminals that support it, so keys like ctrl-i
+    /// and tab can be bound separately. See [`Tui::keyboard_enhancement`].
+    pub fn with_keyboard_enhancement(mut self, enhance: bool) -> Self {
+        self.config.keyboard_enhancement = enhance;
         self
     }
 
-    /// Turn on performance monitoring to see how fast your app runs
-    pub fn with_performance_monitoring(mut self, enabled: bool) -> Self {
-        self.config.enable_performance_monitoring = enabled;
+    /// Draw a "No components" message instead of a blank screen while the app has no
+    /// components, e.g. when they're added later from a background task
+    pub fn with_empty_placeholder(mut self, show: bool) -> Self {
+        self.config.empty_placeholder = show;
         self
     }
 
-    /// Get performance stats (events processed, FPS, etc.)
-    pub fn get_metrics(&self) -> PerformanceMetrics {
-        self.metrics.clone()
+    /// Turn bursts of at least `min_chars` printable keys into a paste, for terminals
+    /// without bracketed paste, where pasted text arrives as separate key presses.
+    ///
+    /// Pasted text reaches the app in one go, so its keys are all waiting when input is
+    /// read, while typing trickles in a key or two at a time. A run of `min_chars` or more
+    /// characters, enter (as a newline) and tab, with no modifier but shift, read together
+    /// from the terminal is delivered as an `Event::Paste` instead of keys. It's a guess:
+    /// keys typed while the app is busy pile up and can be taken for a paste, pastes
+    /// shorter than `min_chars` still arrive as keys, and a pasted control character splits
+    /// the paste in two. Keybindings don't fire for keys that end up in a paste.
+    pub fn with_clipboard_paste_fallback(mut self, min_chars: usize) -> Self {
+        self.config.paste_fallback = Some(min_chars.max(2));
+        self
     }
 
-    fn send(&self, action: Action) -> Result<()> {
-        self.action_tx.send(action)?;
-        Ok(())
+    /// Keep key, mouse and paste events received while input is paused and handle them after
+    /// [`App::resume_input`], instead of dropping them.
+    pub fn with_replay_paused_input(mut self, replay: bool) -> Self {
+        self.config.replay_paused_input = replay;
+        self
     }
 
-    fn try_recv(&mut self) -> Result<Action, TryRecvError> {
-        self.action_rx.try_recv()
+    /// Choose how components drawing outside their area are handled.
+    ///
+    /// Defaults to [`ClipPolicy::Clip`] in debug builds and [`ClipPolicy::Off`], which skips
+    /// the per-component buffer copy, in release builds. `Strict` is useful in tests.
+    pub fn with_clip_policy(mut self, policy: ClipPolicy) -> Self {
+        self.config.clip_policy = policy;
+        self
     }
 
-    fn handle_key_event(&mut self, key: KeyEvent) -> Result<()> {
-        if let Some(action) = self.keybindings.get(&[key]) {
-            return self.send(action.clone());
-        }
+    /// Draw in a region `height` rows tall below the cursor, keeping the terminal's scrollback,
+    /// instead of taking over the whole screen.
+    pub fn with_inline(mut self, height: u16) -> Self {
+        self.config.inline_height = Some(height);
+        self
+    }
 
-        self.last_tick_key_events.push(key);
-        if let Some(action) = self.keybindings.get(&self.last_tick_key_events) {
-            self.send(action.clone())?;
-        }
+    /// Draw the UI on `target` instead of stdout.
+    ///
+    /// `RenderTarget::Stderr` keeps stdout out of the terminal handling, so the app's output
+    /// can be piped to another program while the UI shows on the terminal, like `fzf` does.
+    pub fn with_render_target(mut self, target: RenderTarget) -> Self {
+        self.config.render_to = target;
+        self
+    }
 
-        if let KeyCode::Char(c) = key.code {
-            if c.is_alphanumeric() {
-                let mut char_buf = [0u8; 4];
-                let char_str = c.encode_utf8(&mut char_buf);
-                self.send(Action::Key(char_str.to_string()))?;
-            }
+    /// Set the keyboard layout the user types on, so `<phys:...>` bindings match the keys
+    /// in those positions, e.g. `<phys:w>` matches `z` on AZERTY.
+    ///
+    /// Terminals don't say which key was pressed, so the app can't detect the layout. It
+    /// defaults to `Qwerty`, where physical bindings match like plain ones.
+    pub fn with_keyboard_layout(mut self, layout: KeyboardLayout) -> Self {
+        self.config.keyboard_layout = layout;
+        self
+    }
+
+    /// Add a theme to your app
+    pub fn add_theme(mut self, theme: Theme) -> Self {
+        if !self.theme_manager.has_active_theme() {
+            self.theme_manager.set_active_theme(&theme.name);
         }
 
-        Ok(())
+        self.theme_manager.add_theme(theme);
+        self
     }
 
-    fn process_action_batch(&mut self, tui: &mut Tui, initialize: &mut bool) -> Result<()> {
-        let start_time = if self.config.enable_performance_monitoring {
-            Some(Instant::now())
-        } else {
-            None
+    /// Load every `*.toml` theme in a directory, in alphabetical order.
+    ///
+    /// The first theme loaded becomes active if no theme is active yet. Files that fail to
+    /// parse, or a directory that can't be read, are skipped and raised as in-app warnings,
+    /// shown by [`App::with_notifications`].
+    #[cfg(feature = "theme-files")]
+    pub fn with_theme_dir(mut self, path: impl AsRef<std::path::Path>) -> Self {
+        let path = path.as_ref();
+        let mut files = match std::fs::read_dir(path) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|file| file.extension().is_some_and(|ext| ext == "toml"))
+                .collect::<Vec<_>>(),
+            Err(err) => {
+                self.warn(&format!(
+                    "Failed to read theme directory '{}': {}",
+                    path.display(),
+                    err
+                ));
+                return self;
+            }
         };
+        files.sort();
 
-        let batch_size = self.action_batch.len();
-        let mut needs_render = false;
-
-        for action in self.action_batch.drain(..) {
-            match action {
-                Action::Quit => self.should_quit = true,
-                Action::Render => needs_render = true,
-                Action::Tick => {
-                    self.last_tick_key_events.clear();
-                }
-                Action::AppAction(ref m) => {
-                    for handler in self.component_handlers.iter_mut() {
-                        if handler.c.is_active() {
-                            handler.handle_message(m.as_str());
-                        }
-                    }
-                }
-                _ => {}
+        for file in files {
+            match Theme::from_file(&file) {
+                Ok(theme) => self = self.add_theme(theme),
+                Err(err) => self.warn(&format!("Skipping theme: {}", err)),
             }
+        }
+        self
+    }
 
-            for handler in self.component_handlers.iter_mut() {
-                handler.handle_update(&action);
+    /// Load a theme file and reload it whenever it changes on disk, for iterating on a theme.
+    ///
+    /// Reloaded themes are applied to every component and trigger a render. Rapid saves are
+    /// debounced, and a file that fails to parse leaves the previous theme active. Errors are
+    /// raised as in-app warnings, shown by [`App::with_notifications`].
+    #[cfg(feature = "watch")]
+    pub fn watch_theme_file(mut self, path: impl AsRef<std::path::Path>) -> Self {
+        let path = path.as_ref();
+        match Theme::from_file(path) {
+            Ok(theme) => self = self.add_theme(theme),
+            Err(err) => self.warn(&format!("Failed to load theme: {}", err)),
+        }
+
+        match crate::theme::ThemeWatcher::new(path) {
+            Ok(watcher) => self.theme_watcher = Some(watcher),
+            Err(err) => {
+                self.warn(&format!("Failed to watch theme file '{}': {}", path.display(), err))
             }
         }
+        self
+    }
 
-        if needs_render {
-            let render_start = Instant::now();
+    /// Apply the watched theme file if it changed and has settled
+    #[cfg(feature = "watch")]
+    fn reload_watched_theme(&mut self) {
+        const DEBOUNCE: Duration = Duration::from_millis(150);
 
-            tui.draw(|f| {
-                for handler in self.component_handlers.iter_mut() {
-                    let area = f.area();
-                    if !*initialize {
-                        handler.handle_init(area);
-                        *initialize = true;
-                    }
-                    handler.c.set_area(area);
-                    handler.handle_draw(f);
-                }
-            })?;
+        let Some(result) = self.theme_watcher.as_mut().and_then(|w| w.poll(DEBOUNCE)) else {
+            return;
+        };
 
-            if self.config.enable_performance_monitoring {
-                let render_duration = render_start.elapsed();
-                self.metrics.total_render_time += render_duration;
-                self.metrics.frame_count += 1;
-
-                if let Some(last_frame) = self.metrics.last_frame_time {
-                    let frame_duration = render_start.duration_since(last_frame);
-                    if !frame_duration.is_zero() {
-                        self.metrics.last_fps = 1.0 / frame_duration.as_secs_f64();
-                    }
+        match result {
+            Ok(theme) => {
+                self.theme_manager.add_theme(theme);
+                for handler in self.component_handlers.iter_mut() {
+                    handler.handle_theme(&self.theme_manager);
                 }
-                self.metrics.last_frame_time = Some(render_start);
+                let _ = self.send(Action::Render);
             }
+            Err(err) => self.warn(&format!("Keeping the previous theme: {}", err)),
         }
+    }
 
-        if let Some(_start) = start_time {
-            self.metrics.actions_processed += batch_size as u64;
-            self.metrics.average_action_batch_size =
-                (self.metrics.average_action_batch_size * (self.metrics.actions_processed - batch_size as u64) as f64
-                + batch_size as f64) / self.metrics.actions_processed as f64;
-        }
+    /// Show a "terminal too small" message instead of the UI below this size
+    pub fn with_min_size(mut self, width: u16, height: u16) -> Self {
+        self.config.min_size = Some((width, height));
+        self
+    }
 
-        Ok(())
+    /// Set how long a component's pending key sequence waits for its next key (default 1s)
+    pub fn with_key_sequence_timeout(mut self, timeout: Duration) -> Self {
+        self.config.key_sequence_timeout = timeout;
+        self
     }
 
-    fn process_event_batch(&mut self) -> Result<()> {
-        let start_time = if self.config.enable_performance_monitoring {
-            Some(Instant::now())
-        } else {
-            None
-        };
+    /// Dispatch actions when the app starts, e.g. to load data or set an initial selection.
+    ///
+    /// The actions are queued right after components are wired up, so they're handled in the
+    /// first action batch, before the first render. Since `Component::init` runs on the first
+    /// render, components see these actions before `init` is called.
+    pub fn with_startup_actions(mut self, actions: Vec<Action>) -> Self {
+        self.startup_actions.extend(actions);
+        self
+    }
 
-        let batch_size = self.event_batch.len();
+    /// Set the priority of an app action or message topic (chainable).
+    ///
+    /// Higher priority actions are handled before lower ones queued in the same loop
+    /// iteration, e.g. to show an error dialog ahead of a backlog of updates.
+    pub fn with_action_priority(mut self, name: &str, priority: Priority) -> Self {
+        self.action_priorities.insert(name.to_string(), priority);
+        self
+    }
 
-        let events: Vec<Event> = self.event_batch.drain(..).collect();
+    /// Mark an app action or message topic as idempotent (chainable).
+    ///
+    /// Repeats of an idempotent action in one batch are handled once, in place of the last
+    /// one, e.g. when several components each ask for a re-sort. Only mark actions where
+    /// handling them twice has the same effect as once; see [`Action::is_idempotent`] for the
+    /// built-in ones.
+    pub fn with_idempotent_action(mut self, name: &str) -> Self {
+        self.idempotent_actions.insert(name.to_string());
+        self
+    }
 
-        for event in events {
-            match event {
-                Event::Resize(x, y) => self.send(Action::Resize(x, y))?,
-                Event::Render => self.send(Action::Render)?,
-                Event::Tick => self.send(Action::Tick)?,
-                Event::Quit => self.send(Action::Quit)?,
-                Event::Key(key) => self.handle_key_event(key)?,
-                _ => {}
-            }
+    /// Run host-side logic on every tick, without writing a component.
+    ///
+    /// The callback runs once per `Action::Tick` while the action batch is processed, with
+    /// mutable access to the app to dispatch actions, read metrics or change settings.
+    /// It must not call [`App::run`], and replacing the callback from inside itself is ignored.
+    pub fn with_tick_callback(mut self, callback: impl FnMut(&mut App) + 'static) -> Self {
+        self.tick_callback = Some(Hook(Box::new(callback)));
+        self
+    }
 
-            let mut component_actions = Vec::new();
-            for handler in self.component_handlers.iter_mut() {
-                let actions = handler.handle_events(&Some(event.clone()));
-                component_actions.extend(actions);
-            }
+    /// Call `callback` for key presses that nothing is bound to, e.g. to flash a message or
+    /// to log `key_event_to_string(key)` while working on a keymap.
+    ///
+    /// The callback gets the key and the bound key sequences starting with a near match
+    /// (see [`KeyBindings::near_matches`]), to suggest what may have been meant. It isn't
+    /// called for keys that start a bound sequence, for alphanumeric keys, which are still
+    /// sent as `Action::Key`, or for keys taken by a component's key sequence handler. Keys
+    /// components handle in `handle_key_events` can't be told apart, so they're reported too.
+    pub fn on_unbound_key(mut self, callback: impl FnMut(&KeyEvent, &[String]) + 'static) -> Self {
+        self.unbound_key_callback = Some(Hook(Box::new(callback)));
+        self
+    }
 
-            for action in component_actions {
-                self.send(action)?;
-            }
-        }
+    /// Send `store`'s coalesced notifications once per frame, right before the frame is
+    /// rendered.
+    ///
+    /// See [`Store::with_coalesced_notify`]. Stores that notify on every dispatch don't need
+    /// registering.
+    pub fn with_store<S, A>(mut self, store: &Store<S, A>) -> Self
+    where
+        S: AppState,
+        A: Clone + Send + Sync + 'static,
+    {
+        let store = store.clone();
+        self.store_flushes.push(Hook(Box::new(move || store.flush_notifications())));
+        self
+    }
 
-        if let Some(_start) = start_time {
-            let processing_time = _start.elapsed();
-            self.metrics.total_event_processing_time += processing_time;
-            self.metrics.events_processed += batch_size as u64;
-            self.metrics.average_event_batch_size =
-                (self.metrics.average_event_batch_size * (self.metrics.events_processed - batch_size as u64) as f64
-                + batch_size as f64) / self.metrics.events_processed as f64;
-        }
+    /// Run `hook` on each frame's buffer after all components are drawn and before the frame
+    /// is written to the terminal, e.g. to tint the whole screen, overlay a watermark or read
+    /// the screen's text for automation.
+    ///
+    /// The hook runs on every rendered frame, so slow hooks count against the frame budget.
+    /// It isn't run by [`App::render_into`], where the frame belongs to the host.
+    pub fn with_render_hook(mut self, hook: impl FnMut(&mut Buffer) + 'static) -> Self {
+        self.render_hook = Some(Hook(Box::new(hook)));
+        self
+    }
 
-        Ok(())
+    /// Call `callback` each time reading terminal input fails, e.g. to show that input may
+    /// be lost.
+    ///
+    /// Errors are also shown as warnings in the app. After several in a row with no input
+    /// read in between, the app quits as if `Action::Quit` had been sent, printing why to
+    /// stderr once the terminal is restored.
+    pub fn on_io_error(mut self, callback: impl FnMut() + 'static) -> Self {
+        self.io_error_callback = Some(Hook(Box::new(callback)));
+        self
     }
 
-    fn initialize_tui(&mut self) -> Result<Tui> {
-        let mut tui = Tui::new()?
-            .tick_rate(self.config.tick_rate)
-            .frame_rate(self.config.frame_rate)
-            .mouse(self.config.mouse)
-            .paste(self.config.paste);
+    /// Print a message to stdout once [`App::run`] has restored the terminal, e.g. a summary
+    /// of what the user did.
+    ///
+    /// `message` runs after the UI is torn down, so the text stays visible instead of being
+    /// drawn over or lost with the alternate screen. It gets the app to read final state
+    /// from, like components or metrics.
+    pub fn with_exit_message(mut self, message: impl FnOnce(&App) -> String + 'static) -> Self {
+        self.exit_message = Some(Hook(Box::new(message)));
+        self
+    }
 
-        tui.enter()?;
+    /// Dispatch an action to the app, as if a component had sent it
+    pub fn dispatch(&self, action: Action) {
+        let _ = self.action_tx.send(action);
+    }
 
-        for handler in self.component_handlers.iter_mut() {
-            handler.receive_action_handler(self.action_tx.clone());
-            handler.handle_theme(self.theme_manager.clone());
-            handler.handle_custom_keybindings(&mut self.keybindings);
+    /// Get a handle for feeding events to the app while it runs, e.g. from a test script.
+    ///
+    /// Injected events are handled like terminal input, except that components see their
+    /// source in `ComponentContext::event_source`.
+    pub fn injector(&self) -> EventInjector {
+        EventInjector {
+            tx: self.injected_tx.clone(),
         }
+    }
 
-        if !self
-            .keybindings
-            .0
-            .iter()
-            .any(|(_, action)| *action == Action::Quit)
-        {
-            anyhow::bail!("Action::Quit is not bound to any key. Consider binding it for graceful exit (e.g., <ctrl-c>).");
-        }
+    /// Start recording key, mouse and paste events, discarding any recording in progress.
+    ///
+    /// Events played back with [`App::play`], or sent with `EventSource::Replay`, are left
+    /// out, so replaying a session while recording doesn't record it a second time. Input
+    /// dropped while paused isn't recorded either.
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Vec::new());
+    }
 
-        Ok(tui)
+    /// Stop recording and return the recorded events, oldest first
+    pub fn stop_recording(&mut self) -> Vec<Event> {
+        self.recording.take().unwrap_or_default()
     }
 
-    /// Start your app and run until the user quits
-    pub async fn run(&mut self) -> Result<()> {
-        let mut tui = self.initialize_tui()?;
+    /// Whether events are being recorded
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
 
-        let mut initialize = false;
-        loop {
-            while let Some(event) = tui.next().await {
-                self.event_batch.push(event);
+    /// Play back events, e.g. from [`App::stop_recording`], as `EventSource::Replay`.
+    ///
+    /// The events are queued behind any injected ones and handled by the run loop like
+    /// terminal input.
+    pub fn play(&mut self, events: impl IntoIterator<Item = Event>) {
+        let injector = self.injector();
+        for event in events {
+            injector.send(event, EventSource::Replay);
+            self.replay_pending += 1;
+        }
+    }
 
-                // Process batch when full or if we get a critical event
-                if self.event_batch.len() >= self.config.max_events_per_batch
-                    || matches!(self.event_batch.last(), Some(Event::Quit) | Some(Event::Render)) {
-                    break;
+    /// Whether events from [`App::play`] are still queued or a replayed event is being handled
+    pub fn is_replaying(&self) -> bool {
+        self.replay_pending > 0 || self.event_source.get() == EventSource::Replay
+    }
+
+    /// Turn on performance monitoring to see how fast your app runs
+    pub fn with_performance_monitoring(mut self, enabled: bool) -> Self {
+        self.config.enable_performance_monitoring = enabled;
+        self
+    }
+
+    /// Get performance stats (events processed, FPS, etc.)
+    pub fn get_metrics(&self) -> PerformanceMetrics {
+        self.metrics.clone()
+    }
+
+    /// Record a copy of the metrics every `interval`, keeping the last `limit` of them.
+    ///
+    /// Turns on performance monitoring. Snapshots hold the running totals at the time they
+    /// were taken, so per-interval rates are the difference between neighbouring snapshots.
+    pub fn with_metrics_snapshots(mut self, interval: Duration, limit: usize) -> Self {
+        self.config.enable_performance_monitoring = true;
+        self.config.metrics_snapshot_interval = Some(interval);
+        self.config.metrics_history_limit = limit;
+        self
+    }
+
+    /// Get the recorded metrics snapshots, oldest first
+    pub fn metrics_history(&self) -> Vec<PerformanceMetrics> {
+        self.metrics_history.iter().cloned().collect()
+    }
+
+    /// Zero the metrics counters and averages, e.g. to measure a new interval.
+    ///
+    /// The metrics history is kept.
+    pub fn reset_metrics(&mut self) {
+        self.metrics = PerformanceMetrics::default();
+    }
+
+    /// Whether enough time has passed since the last frame to draw another at `now`.
+    ///
+    /// Frames are kept `1 / frame_rate` apart, less a tenth for timer jitter, so bursts of
+    /// render requests are coalesced into the next allowed frame.
+    fn render_due(&mut self, now: Instant) -> bool {
+        let interval = Duration::try_from_secs_f64(0.9 / self.config.frame_rate).ok();
+        if let (Some(last), Some(interval)) = (self.last_render, interval) {
+            if now.duration_since(last) < interval {
+                if self.config.enable_performance_monitoring {
+                    self.metrics.frames_coalesced += 1;
                 }
+                return false;
             }
+        }
 
-            if !self.event_batch.is_empty() {
-                if let Err(err) = self.process_event_batch() {
-                    eprintln!("Error processing event batch: {}", err);
-                }
+        self.last_render = Some(now);
+        true
+    }
+
+    /// Count a rendered frame that took longer than the frame budget, warning about the first
+    fn check_frame_budget(&mut self, frame_time: Duration) {
+        let budget = self
+            .config
+            .frame_budget
+            .or_else(|| Duration::try_from_secs_f64(1.0 / self.config.frame_rate).ok());
+        let Some(budget) = budget else {
+            return;
+        };
+
+        if frame_time > budget {
+            self.metrics.frames_over_budget += 1;
+            if !self.frame_budget_warned {
+                self.frame_budget_warned = true;
+                self.warn(&format!(
+                    "Frame {} took {:?}, over the {:?} frame budget",
+                    self.metrics.frame_count, frame_time, budget
+                ));
+            }
+        }
+    }
+
+    /// Record a metrics snapshot if the snapshot interval has passed since the last one
+    fn snapshot_metrics(&mut self) {
+        let Some(interval) = self.config.metrics_snapshot_interval else {
+            return;
+        };
+
+        let now = Instant::now();
+        let due = self
+            .last_metrics_snapshot
+            .is_none_or(|last| now.duration_since(last) >= interval);
+        if !due {
+            return;
+        }
+
+        self.last_metrics_snapshot = Some(now);
+        self.metrics_history.push_back(self.metrics.clone());
+        while self.metrics_history.len() > self.config.metrics_history_limit {
+            self.metrics_history.pop_front();
+        }
+    }
+
+    /// Focus the first component (roots and children, in order) named `name`.
+    ///
+    /// Every other component loses focus. Returns `false` if no component has that name.
+    pub fn focus(&mut self, name: &str) -> bool {
+        let mut found = false;
+        for handler in self.component_handlers.iter_mut() {
+            handler.handle_focus(name, &mut found);
+        }
+        self.clear_pending_keys();
+        found
+    }
+
+    /// Stop handling key, mouse and paste events, e.g. during an animation that shouldn't be
+    /// interrupted. Ticks and renders keep flowing, so the UI stays live.
+    ///
+    /// Sends `Action::AppAction("input:paused")` so components can show that input is paused.
+    /// Input received while paused is dropped unless replay is enabled with
+    /// [`App::with_replay_paused_input`].
+    pub fn pause_input(&mut self) {
+        if self.input_paused {
+            return;
+        }
+
+        self.input_paused = true;
+        self.clear_pending_keys();
+        let _ = self.send(Action::AppAction("input:paused".to_string()));
+    }
+
+    /// Handle input again after [`App::pause_input`], sending `Action::AppAction("input:resumed")`
+    pub fn resume_input(&mut self) {
+        if !self.input_paused {
+            return;
+        }
+
+        self.input_paused = false;
+        let queued = std::mem::take(&mut self.paused_input);
+        self.event_batch.splice(0..0, queued);
+        let _ = self.send(Action::AppAction("input:resumed".to_string()));
+    }
+
+    /// Whether input is paused with [`App::pause_input`]
+    pub fn is_input_paused(&self) -> bool {
+        self.input_paused
+    }
+
+    /// Move focus to the next component in tab order, wrapping around at the end.
+    ///
+    /// Only active components whose `accepts_focus` returns `true` are visited, ordered by
+    /// `focus_order` and then tree order. Components are told apart by name, so give
+    /// repeated components distinct names. Returns `false` if no component accepts focus.
+    pub fn focus_next(&mut self) -> bool {
+        self.step_focus(1)
+    }
+
+    /// Move focus to the previous component in tab order, wrapping around at the start.
+    ///
+    /// See [`App::focus_next`].
+    pub fn focus_prev(&mut self) -> bool {
+        self.step_focus(-1)
+    }
+
+    fn step_focus(&mut self, step: isize) -> bool {
+        let mut focusable = Vec::new();
+        for handler in self.component_handlers.iter_mut() {
+            handler.handle_focusable(&mut focusable);
+        }
+        // Explicitly ordered components first; the sort is stable, keeping tree order
+        focusable.sort_by_key(|(order, _)| order.map_or((1, 0), |order| (0, order)));
+        if focusable.is_empty() {
+            return false;
+        }
+
+        let mut path = Vec::new();
+        let focused = self
+            .component_handlers
+            .iter_mut()
+            .any(|handler| handler.handle_focus_path(&mut path));
+        let current = focused
+            .then(|| focusable.iter().position(|(_, name)| Some(name) == path.last()))
+            .flatten();
+
+        let len = focusable.len() as isize;
+        let next = match current {
+            Some(index) => (index as isize + step).rem_euclid(len),
+            None if step > 0 => 0,
+            None => len - 1,
+        };
+        let name = focusable[next as usize].1.clone();
+        self.focus(&name)
+    }
+
+    /// Run a closure on the first root component of type `T`.
+    ///
+    /// Gives typed access to a component from outside the run loop, e.g. to assert on its
+    /// state in tests. Returns `None` if no root component is a `T`.
+    pub fn with_component<T: Component, R>(&mut self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        self.component_handlers
+            .iter_mut()
+            .find(|handler| handler.c.is::<T>())
+            .and_then(|handler| handler.with(f))
+    }
+
+    /// Remove focus from every component
+    pub fn clear_focus(&mut self) {
+        self.focus("");
+    }
+
+    /// Capture the state of every component tree, keyed by root component name.
+    ///
+    /// Only components declared with `#[component(serde)]` contribute state; see
+    /// [`component_manager::snapshot_tree`](crate::component_manager::snapshot_tree).
+    #[cfg(feature = "serde")]
+    pub fn snapshot(&mut self) -> serde_json::Value {
+        self.component_handlers
+            .iter_mut()
+            .map(|handler| (handler.name(), handler.handle_snapshot()))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+
+    /// Restore state captured by [`App::snapshot`], e.g. when resuming a session
+    #[cfg(feature = "serde")]
+    pub fn restore(&mut self, value: &serde_json::Value) {
+        for handler in self.component_handlers.iter_mut() {
+            if let Some(tree) = value.get(handler.name()) {
+                handler.handle_restore(tree);
+            }
+        }
+    }
+
+    fn send(&self, action: Action) -> Result<()> {
+        self.action_tx.send(action)?;
+        Ok(())
+    }
+
+    /// Raise a warning as an `Action::Notify`, shown by [`App::with_notifications`]; writing
+    /// it to the terminal would garble the screen while the app is running
+    fn warn(&self, text: &str) {
+        let _ = self.send(Action::Notify {
+            level: NotifyLevel::Warning,
+            text: text.to_string(),
+            ttl: WARNING_TTL,
+        });
+    }
+
+    fn try_recv(&mut self) -> Result<Action, TryRecvError> {
+        self.action_rx.try_recv()
+    }
+
+    /// Find the action bound to a key sequence.
+    ///
+    /// Global component bindings win, then the focused component's, then its ancestors', then
+    /// the app's. Without focus, component bindings override the app's, later components
+    /// winning. When nothing is bound to the keys as typed, they're looked up again as
+    /// physical keys on the configured keyboard layout.
+    fn find_binding(&mut self, keys: &[KeyEvent]) -> Option<Action> {
+        let layout = self.config.keyboard_layout;
+        let physical = keys
+            .iter()
+            .map(|key| layout.physical(key).unwrap_or(*key))
+            .collect::<Vec<_>>();
+        let action = self
+            .find_binding_with(|kb| kb.get(keys))
+            .or_else(|| self.find_binding_with(|kb| kb.get_physical(&physical)))?;
+        Some(self.resolve_registered(action))
+    }
+
+    /// Find the action `lookup` gets from the keybindings, in the order of [`App::find_binding`]
+    fn find_binding_with(
+        &mut self,
+        lookup: impl Fn(&KeyBindings) -> Option<&Action>,
+    ) -> Option<Action> {
+        if let Some(action) = lookup(&self.global_keybindings) {
+            return Some(action.clone());
+        }
+
+        let mut paths = Vec::new();
+        for (index, handler) in self.component_handlers.iter_mut().enumerate() {
+            if handler.handle_focus_tree_path(&index.to_string(), &mut paths) {
+                break;
+            }
+        }
+
+        let component_binding = |path: &str| {
+            self.component_keybindings
+                .iter()
+                .find(|(component, _)| component == path)
+                .and_then(|(_, kb)| lookup(kb))
+        };
+
+        let action = if paths.is_empty() {
+            self.component_keybindings
+                .iter()
+                .rev()
+                .find_map(|(_, kb)| lookup(kb))
+        } else {
+            paths.iter().rev().find_map(|path| component_binding(path))
+        };
+
+        action.or_else(|| lookup(&self.keybindings)).cloned()
+    }
+
+    /// Offer a key to the focused component's sequence handler, returning whether it took the key
+    fn handle_key_sequence(&mut self, key: KeyEvent) -> bool {
+        self.expire_pending_keys();
+        let had_pending = !self.pending_keys.is_empty();
+        self.pending_keys.push(key);
+
+        let mut result = self.offer_pending_keys();
+        if result == Some(KeySeqResult::Rejected) && had_pending {
+            // The key that broke the sequence may start a new one
+            self.pending_keys = vec![key];
+            result = self.offer_pending_keys();
+        }
+
+        match result {
+            Some(KeySeqResult::Pending) => {
+                self.pending_keys_since = Some(Instant::now());
+                true
+            }
+            Some(KeySeqResult::Consumed) => {
+                self.clear_pending_keys();
+                true
+            }
+            Some(KeySeqResult::Rejected) | None => {
+                self.clear_pending_keys();
+                false
+            }
+        }
+    }
+
+    fn offer_pending_keys(&mut self) -> Option<KeySeqResult> {
+        self.component_handlers
+            .iter_mut()
+            .find_map(|handler| handler.handle_key_sequence(&self.pending_keys))
+    }
+
+    /// Drop pending keys once the sequence timeout has passed since the last one
+    fn expire_pending_keys(&mut self) {
+        if self
+            .pending_keys_since
+            .is_some_and(|since| since.elapsed() >= self.config.key_sequence_timeout)
+        {
+            self.clear_pending_keys();
+        }
+    }
+
+    fn clear_pending_keys(&mut self) {
+        self.pending_keys.clear();
+        self.pending_keys_since = None;
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<()> {
+        if !self.double_taps.is_empty() {
+            if let Some(action) = self.double_taps.press(key, Instant::now()) {
+                return self.send(self.resolve_registered(action));
+            }
+        }
+
+        if let Some(action) = self.find_binding(&[key]) {
+            return self.send(action);
+        }
+
+        self.last_tick_key_events.push(key);
+        let sequence = self.last_tick_key_events.clone();
+        let mut handled = false;
+        if let Some(action) = self.find_binding(&sequence) {
+            self.send(action)?;
+            handled = true;
+        }
+
+        if let KeyCode::Char(c) = key.code {
+            if c.is_alphanumeric() {
+                self.send(Action::Key(key_event_to_string(&key)))?;
+                handled = true;
+            }
+        }
+
+        if !handled && self.unbound_key_callback.is_some() && !self.starts_binding(&sequence) {
+            let suggestions = self.all_keybindings().near_matches(&key);
+            if let Some(Hook(callback)) = self.unbound_key_callback.as_mut() {
+                callback(&key, &suggestions);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Report a failed terminal read, quitting once too many happen in a row
+    fn handle_io_error(&mut self) -> Result<()> {
+        self.io_errors += 1;
+        self.warn(&format!("Failed to read terminal input ({} in a row)", self.io_errors));
+        if let Some(Hook(callback)) = self.io_error_callback.as_mut() {
+            callback();
+        }
+        if self.io_errors == MAX_IO_ERRORS {
+            self.exit_error =
+                Some(format!("Giving up after {} terminal read errors", MAX_IO_ERRORS));
+            self.send(Action::Quit)?;
+        }
+        Ok(())
+    }
+
+    /// Evaluate the message set with `with_exit_message`, if any
+    fn take_exit_message(&mut self) -> Option<String> {
+        let Hook(message) = self.exit_message.take()?;
+        Some(message(self))
+    }
+
+    /// Gather the app, global and component bindings into one set
+    fn all_keybindings(&self) -> KeyBindings {
+        let mut all = self.keybindings.clone();
+        all.extend(self.global_keybindings.clone());
+        for (_, kb) in &self.component_keybindings {
+            all.extend(kb.clone());
+        }
+        all
+    }
+
+    /// Whether a bound key sequence starts with `keys`, either one or its last key
+    fn starts_binding(&self, keys: &[KeyEvent]) -> bool {
+        let last = &keys[keys.len() - 1..];
+        self.all_keybindings()
+            .0
+            .keys()
+            .any(|bound| bound.starts_with(keys) || bound.starts_with(last))
+    }
+
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Result<()> {
+        if self.mouse_bindings.is_empty() {
+            return Ok(());
+        }
+
+        let mut hovered = Vec::new();
+        for handler in self.component_handlers.iter_mut() {
+            handler.handle_hit_test(mouse.column, mouse.row, &mut hovered);
+        }
+
+        if let Some(action) = self.mouse_bindings.get(mouse.kind, &hovered) {
+            self.send(self.resolve_registered(action.clone()))?;
+        }
+
+        Ok(())
+    }
+
+    fn priority_of(&self, action: &Action) -> Priority {
+        action_name(action)
+            .and_then(|name| self.action_priorities.get(name))
+            .copied()
+            .unwrap_or_else(|| action.priority())
+    }
+
+    fn is_idempotent(&self, action: &Action) -> bool {
+        action.is_idempotent()
+            || action_name(action).is_some_and(|name| self.idempotent_actions.contains(name))
+    }
+
+    /// Drop repeats of idempotent actions from a batch, keeping the last of each so the
+    /// newest value wins, e.g. the final size of several resizes
+    fn dedup_action_batch(&self, batch: &mut Vec<Action>) {
+        let mut seen = Vec::new();
+        batch.reverse();
+        batch.retain(|action| {
+            if !self.is_idempotent(action) {
+                return true;
+            }
+            if seen.contains(action) {
+                return false;
+            }
+            seen.push(action.clone());
+            true
+        });
+        batch.reverse();
+    }
+
+    /// Batch queued events, terminal ones first, up to `max_events_per_batch`.
+    ///
+    /// Whatever doesn't fit stays queued for the next iteration, so a flood of input can't
+    /// keep queued actions or rendering waiting.
+    fn fill_event_batch(&mut self, mut next_terminal_event: impl FnMut() -> Option<Event>) {
+        while self.event_batch.len() < self.config.max_events_per_batch {
+            match next_terminal_event() {
+                Some(event) => self.event_batch.push((event, EventSource::Terminal)),
+                None => break,
             }
+        }
+        while self.event_batch.len() < self.config.max_events_per_batch {
+            match self.injected_rx.try_recv() {
+                Ok(injected) => self.event_batch.push(injected),
+                Err(_) => break,
+            }
+        }
+    }
+
+    /// Batch queued actions, most urgent first.
+    ///
+    /// Everything on the channel is sorted into priority lanes, then the batch is filled from
+    /// the highest lane down. A critical action ends the batch so it's handled right away.
+    fn fill_action_batch(&mut self) {
+        while let Ok(action) = self.try_recv() {
+            let lane = self.priority_of(&action) as usize;
+            self.action_lanes[lane].push_back(action);
+        }
+
+        while self.action_batch.len() < self.config.max_actions_per_batch {
+            let Some(lane) = (0..self.action_lanes.len())
+                .rev()
+                .find(|&lane| !self.action_lanes[lane].is_empty())
+            else {
+                break;
+            };
 
-            while let Ok(action) = self.try_recv() {
+            if let Some(action) = self.action_lanes[lane].pop_front() {
                 self.action_batch.push(action);
+            }
+            if lane == Priority::Critical as usize {
+                break;
+            }
+        }
+    }
+
+    /// Apply the batched actions, returning whether a render was requested
+    fn process_action_batch(&mut self) -> Result<bool> {
+        let start_time = if self.config.enable_performance_monitoring {
+            Some(Instant::now())
+        } else {
+            None
+        };
+
+        // Taken out of `self` so the tick callback can borrow the app mutably
+        let mut batch = std::mem::take(&mut self.action_batch);
+        self.dedup_action_batch(&mut batch);
 
-                if self.action_batch.len() >= self.config.max_actions_per_batch
-                    || matches!(self.action_batch.last(), Some(Action::Quit) | Some(Action::Render)) {
-                    break;
+        let batch_size = batch.len();
+        let mut needs_render = false;
+        for action in batch.drain(..) {
+            match action {
+                Action::Quit => self.should_quit = true,
+                Action::Render => needs_render = true,
+                Action::Tick => {
+                    self.last_tick_key_events.clear();
+                    self.expire_pending_keys();
+                    if let Some(Hook(mut callback)) = self.tick_callback.take() {
+                        callback(self);
+                        self.tick_callback = Some(Hook(callback));
+                    }
+                }
+                Action::AppAction(ref m) => {
+                    let message = Message::new(m);
+                    for handler in self.component_handlers.iter_mut() {
+                        if handler.c.is_active() {
+                            handler.handle_message(&message);
+                        }
+                    }
+                }
+                Action::Message(ref message) => {
+                    for handler in self.component_handlers.iter_mut() {
+                        if handler.c.is_active() {
+                            handler.handle_message(message);
+                        }
+                    }
                 }
+                _ => {}
             }
 
-            if !self.action_batch.is_empty() {
-                if let Err(err) = self.process_action_batch(&mut tui, &mut initialize) {
-                    eprintln!("Error processing action batch: {}", err);
-                }
+            let mut follow_ups = Vec::new();
+            for handler in self.component_handlers.iter_mut() {
+                follow_ups.extend(handler.handle_update(&action));
             }
+            for follow_up in follow_ups {
+                self.send(follow_up)?;
+            }
+        }
+        self.action_batch = batch;
 
-            if self.should_quit {
-                if let Err(err) = tui.stop() {
-                    eprintln!("Error stopping TUI: {}", err);
+        if let Some(_start) = start_time {
+            self.metrics.actions_processed += batch_size as u64;
+            self.metrics.average_action_batch_size =
+                (self.metrics.average_action_batch_size * (self.metrics.actions_processed - batch_size as u64) as f64
+                + batch_size as f64) / self.metrics.actions_processed as f64;
+        }
+
+        Ok(needs_render)
+    }
+
+    /// Draw the app into part of a frame owned by a host application (embedded mode).
+    ///
+    /// Components are laid out in `area` instead of the whole frame, so the app can sit inside
+    /// a larger ratatui layout. The host owns the terminal: raw mode, the alternate screen and
+    /// reading input are up to it, and [`App::run`] isn't used. Components are wired up on the
+    /// first call, and embedded apps don't need a key bound to `Action::Quit`.
+    pub fn render_into(&mut self, f: &mut Frame<'_>, area: Rect) {
+        if !self.wired {
+            // Only fails if the action channel is closed, which can't happen while `self` owns it
+            let _ = self.wire_components();
+        }
+        self.draw_components(f, area);
+    }
+
+    /// Handle everything queued, then render once, without the async event loop, e.g. to
+    /// drive the app step by step in tests.
+    ///
+    /// Events waiting in `tui` or sent through [`App::injector`] are handled, then queued
+    /// actions, including the ones sent while handling them, round after round until both
+    /// the event and the action batches are empty. Components are wired up on the first
+    /// call, but the terminal isn't entered, so call [`Tui::enter`] first if the frame should
+    /// be shown. Fails if work is still queued after 1000 rounds, e.g. when components keep
+    /// sending each other actions. Quitting only sets the flag [`App::run`] checks; the frame
+    /// is still rendered.
+    pub fn process_pending(&mut self, tui: &mut Tui) -> Result<()> {
+        self.process_queued(|| tui.try_next())?;
+        self.render(tui)
+    }
+
+    /// Handle queued events and actions until none are left, pulling terminal events from
+    /// `next_terminal_event`
+    fn process_queued(
+        &mut self,
+        mut next_terminal_event: impl FnMut() -> Option<Event>,
+    ) -> Result<()> {
+        self.wire_components()?;
+
+        for _ in 0..MAX_PENDING_ROUNDS {
+            while let Some(event) = next_terminal_event() {
+                self.event_batch.push((event, EventSource::Terminal));
+            }
+            while let Ok(injected) = self.injected_rx.try_recv() {
+                self.event_batch.push(injected);
+            }
+            if !self.event_batch.is_empty() {
+                self.process_event_batch()?;
+            }
+
+            self.fill_action_batch();
+            if self.action_batch.is_empty() {
+                if self.injected_rx.is_empty() {
+                    return Ok(());
                 }
-                break;
+                continue;
             }
+            self.process_action_batch()?;
         }
+        anyhow::bail!("Work was still queued after {} rounds", MAX_PENDING_ROUNDS)
+    }
 
-        if let Err(err) = tui.exit() {
-            eprintln!("Error exiting TUI: {}", err);
+    /// Send the coalesced notifications of stores registered with [`App::with_store`]
+    fn flush_stores(&self) {
+        for Hook(flush) in &self.store_flushes {
+            flush();
         }
+    }
 
-        Ok(())
+    /// Draw the components over the whole frame, then run the render hook
+    fn draw_frame(&mut self, f: &mut Frame<'_>) {
+        self.draw_components(f, f.area());
+        if let Some(Hook(hook)) = self.render_hook.as_mut() {
+            hook(f.buffer_mut());
+        }
     }
 
+    /// Draw every component, initializing them on the first render
+    fn draw_components(&mut self, f: &mut Frame<'_>, area: Rect) {
+        // `Component::init` relies on components being wired before the first draw
+        debug_assert!(self.wired, "components must be wired before they're drawn");
+
+        if let Some(min_size) = self.config.min_size {
+            if area.width < min_size.0 || area.height < min_size.1 {
+                draw_too_small(f, area, min_size);
+                return;
+            }
+        }
+
+        if self.component_handlers.is_empty() && self.config.empty_placeholder {
+            let message = "No components";
+            f.render_widget(
+                Paragraph::new(message),
+                centered_rect(message.len() as u16, 1, area),
+            );
+        }
+
+        for handler in self.component_handlers.iter_mut() {
+            if !self.initialized {
+                handler.handle_init(area);
+            }
+            handler.c.set_area(area);
+            handler.handle_draw(f, self.config.clip_policy, &self.theme_manager);
+        }
+        self.initialized = true;
+    }
+
+    fn render(&mut self, tui: &mut Tui) -> Result<()> {
+        // Outside the draw, so subscribers aren't notified while the terminal is being drawn
+        self.flush_stores();
+        let render_start = Instant::now();
+
+        let frame = tui.draw(|f| self.draw_frame(f))?;
+
+        if self.config.enable_performance_monitoring {
+            let render_duration = render_start.elapsed();
+            self.metrics.total_render_time += render_duration;
+            self.metrics.frame_count += 1;
+
+            if let Some(last_frame) = self.metrics.last_frame_time {
+                let frame_duration = render_start.duration_since(last_frame);
+                if !frame_duration.is_zero() {
+                    self.metrics.last_fps = 1.0 / frame_duration.as_secs_f64();
+                }
+            }
+            self.metrics.last_frame_time = Some(render_start);
+            self.record_cells_changed(frame.buffer);
+        }
+
+        Ok(())
+    }
+
+    /// Count the cells that differ from the previous frame into the metrics of the frame just
+    /// counted in `frame_count`
+    fn record_cells_changed(&mut self, buffer: &Buffer) {
+        // A full redraw counts every cell; otherwise only the cells that differ
+        let cells_changed = match &self.previous_buffer {
+            Some(previous) if previous.area == buffer.area => previous.diff(buffer).len(),
+            _ => buffer.content.len(),
+        };
+        self.previous_buffer = Some(buffer.clone());
+
+        self.metrics.last_cells_changed = cells_changed;
+        self.metrics.max_cells_changed = self.metrics.max_cells_changed.max(cells_changed);
+        self.metrics.average_cells_changed = (self.metrics.average_cells_changed
+            * (self.metrics.frame_count - 1) as f64
+            + cells_changed as f64)
+            / self.metrics.frame_count as f64;
+    }
+
+    fn process_event_batch(&mut self) -> Result<()> {
+        let start_time = if self.config.enable_performance_monitoring {
+            Some(Instant::now())
+        } else {
+            None
+        };
+
+        let batch_size = self.event_batch.len();
+
+        let events: Vec<(Event, EventSource)> = self.event_batch.drain(..).collect();
+        let events = match self.config.paste_fallback {
+            Some(min_chars) => coalesce_key_bursts(events, min_chars),
+            None => events,
+        };
+
+        for (event, source) in events {
+            let event = match event {
+                Event::Key(key) => Event::Key(without_lock_state(key)),
+                event => event,
+            };
+            let is_input = matches!(event, Event::Key(_) | Event::Mouse(_) | Event::Paste(_));
+            if self.input_paused && is_input {
+                if self.config.replay_paused_input {
+                    self.paused_input.push((event, source));
+                } else if source == EventSource::Replay {
+                    // Dropped, so it won't be counted when handled
+                    self.replay_pending = self.replay_pending.saturating_sub(1);
+                }
+                continue;
+            }
+            self.event_source.set(source);
+            if source == EventSource::Replay {
+                self.replay_pending = self.replay_pending.saturating_sub(1);
+            } else if let Some(recording) = self.recording.as_mut().filter(|_| is_input) {
+                recording.push(event.clone());
+            }
+            if is_input {
+                self.io_errors = 0;
+            }
+            for (pattern, action) in &self.event_bindings {
+                if pattern.matches(&event) {
+                    self.send(self.resolve_registered(action.clone()))?;
+                }
+            }
+
+            match event {
+                Event::Resize(x, y) => self.send(Action::Resize(x, y))?,
+                Event::Render => self.send(Action::Render)?,
+                Event::Tick { .. } => self.send(Action::Tick)?,
+                Event::Quit => self.send(Action::Quit)?,
+                // Keys taken by a component's key sequence skip the keymap and broadcast
+                Event::Key(key) if self.handle_key_sequence(key) => continue,
+                Event::Key(key) => self.handle_key_event(key)?,
+                Event::Mouse(mouse) => self.handle_mouse_event(mouse)?,
+                Event::Error => self.handle_io_error()?,
+                _ => {}
+            }
+
+            let mut component_actions = Vec::new();
+            match &event {
+                Event::Paste(text) if self.component_handlers.iter_mut().any(|h| h.has_focus()) => {
+                    for handler in self.component_handlers.iter_mut() {
+                        component_actions.extend(handler.handle_paste(text));
+                    }
+                }
+                _ => {
+                    if matches!(event, Event::Paste(_)) && !self.paste_broadcast_warned {
+                        self.paste_broadcast_warned = true;
+                        self.warn(
+                            "No component has focus, broadcasting paste to all components. \
+                             This is deprecated; focus a component that accepts pastes instead.",
+                        );
+                    }
+
+                    for handler in self.component_handlers.iter_mut() {
+                        let actions = handler.handle_events(&Some(event.clone()));
+                        component_actions.extend(actions);
+                    }
+                }
+            }
+
+            for action in component_actions {
+                self.send(action)?;
+            }
+        }
+        self.event_source.set(EventSource::Terminal);
+
+        if let Some(_start) = start_time {
+            let processing_time = _start.elapsed();
+            self.metrics.total_event_processing_time += processing_time;
+            self.metrics.events_processed += batch_size as u64;
+            self.metrics.average_event_batch_size =
+                (self.metrics.average_event_batch_size * (self.metrics.events_processed - batch_size as u64) as f64
+                + batch_size as f64) / self.metrics.events_processed as f64;
+        }
+
+        Ok(())
+    }
+
+    fn initialize_tui(&mut self) -> Result<Tui> {
+        let tui = match self.config.inline_height {
+            Some(height) => Tui::inline(height)?,
+            None => Tui::new()?,
+        };
+        let tui = tui.render_to(self.config.render_to)?;
+        let mut tui = tui
+            .tick_rate(self.config.tick_rate)
+            .frame_rate(self.config.frame_rate)
+            .missed_tick_behavior(self.config.missed_tick_behavior)
+            .mouse(self.config.mouse)
+            .paste(self.config.paste)
+            .keyboard_enhancement(self.config.keyboard_enhancement);
+
+        self.wire_components()?;
+
+        if cfg!(debug_assertions) && self.component_handlers.is_empty() {
+            eprintln!(
+                "Warning: The app has no components, so nothing will be drawn. \
+                 Add some with App::with_components."
+            );
+        }
+
+        if ![&self.keybindings, &self.global_keybindings]
+            .into_iter()
+            .chain(self.component_keybindings.iter().map(|(_, kb)| kb))
+            .any(|kb| kb.actions().any(|action| *action == Action::Quit))
+            && !self.double_taps.actions().any(|action| *action == Action::Quit)
+        {
+            anyhow::bail!("Action::Quit is not bound to any key. Consider binding it for graceful exit (e.g., <ctrl-c>).");
+        }
+
+        let size = tui.size()?;
+        for handler in self.component_handlers.iter_mut() {
+            handler.handle_start(size);
+        }
+
+        tui.enter()?;
+
+        Ok(tui)
+    }
+
+    /// Connect a root component to the app's action channel, theme, local state and shutdown,
+    /// and mount its children
+    fn wire_handler(&self, handler: &mut ComponentHandler) {
+        handler.receive_action_handler(self.action_tx.clone());
+        handler.handle_theme(&self.theme_manager);
+        handler.handle_local_state(&self.local_state);
+        handler.handle_shutdown(&self.shutdown);
+        handler.handle_event_source(&self.event_source);
+        handler.handle_rng(&self.rng);
+        handler.handle_mount();
+    }
+
+    /// Rebuild the component keybindings, which depend on the order of root components
+    fn collect_component_keybindings(&mut self) {
+        self.component_keybindings.clear();
+        self.global_keybindings = KeyBindings::empty();
+        for (index, handler) in self.component_handlers.iter_mut().enumerate() {
+            handler.handle_custom_keybindings(&index.to_string(), &mut self.component_keybindings);
+            handler.handle_global_keybindings(&mut self.global_keybindings);
+        }
+    }
+
+    /// Connect components to the app, collect keybindings and queue the startup actions
+    fn wire_components(&mut self) -> Result<()> {
+        if self.wired {
+            return Ok(());
+        }
+
+        let mut handlers = std::mem::take(&mut self.component_handlers);
+        for handler in handlers.iter_mut() {
+            self.wire_handler(handler);
+        }
+        self.component_handlers = handlers;
+        self.collect_component_keybindings();
+        self.wired = true;
+
+        if let Some(name) = self.initial_focus.take() {
+            if !self.focus(&name) {
+                self.warn(&format!("Cannot focus '{}', no component has that name", name));
+            }
+        }
+
+        for action in std::mem::take(&mut self.startup_actions) {
+            self.send(action)?;
+        }
+
+        Ok(())
+    }
+
+    /// Start your app and run until the user quits
+    pub async fn run(&mut self) -> Result<()> {
+        let mut tui = self.initialize_tui()?;
+
+        let mut needs_render = false;
+        loop {
+            // Only wait for input when no actions are queued, so leftover actions aren't starved
+            if self.action_rx.is_empty() && self.action_lanes.iter().all(VecDeque::is_empty) {
+                tokio::select! {
+                    event = tui.next() => match event {
+                        Some(event) => self.event_batch.push((event, EventSource::Terminal)),
+                        // The event channel is closed for good; waiting on it again would spin
+                        None => self.should_quit = true,
+                    },
+                    Some(injected) = self.injected_rx.recv() => self.event_batch.push(injected),
+                }
+            }
+
+            let iteration_start = Instant::now();
+
+            // Take a bounded share of events, then of actions, then render once
+            self.fill_event_batch(|| tui.try_next());
+
+            if !self.event_batch.is_empty() {
+                if let Err(err) = self.process_event_batch() {
+                    eprintln!("Error processing event batch: {}", err);
+                }
+            }
+
+            #[cfg(feature = "watch")]
+            self.reload_watched_theme();
+
+            self.fill_action_batch();
+
+            if !self.action_batch.is_empty() {
+                match self.process_action_batch() {
+                    Ok(render_requested) => needs_render |= render_requested,
+                    Err(err) => eprintln!("Error processing action batch: {}", err),
+                }
+            }
+
+            // A render put off here stays pending until a later iteration is due, at the
+            // latest when the terminal's next render event arrives
+            let rendered = needs_render && self.render_due(Instant::now());
+            if rendered {
+                needs_render = false;
+                if let Err(err) = self.render(&mut tui) {
+                    eprintln!("Error rendering: {}", err);
+                }
+            }
+
+            if self.config.enable_performance_monitoring {
+                let loop_time = iteration_start.elapsed();
+                self.metrics.worst_loop_time = self.metrics.worst_loop_time.max(loop_time);
+                if rendered {
+                    self.check_frame_budget(loop_time);
+                }
+                self.snapshot_metrics();
+            }
+
+            if self.should_quit {
+                if let Err(err) = tui.stop() {
+                    eprintln!("Error stopping TUI: {}", err);
+                }
+                break;
+            }
+        }
+
+        if let Err(err) = tui.exit() {
+            eprintln!("Error exiting TUI: {}", err);
+        }
+        self.shutdown.cancel();
+
+        if let Some(err) = self.exit_error.take() {
+            eprintln!("Error: {}", err);
+        }
+
+        if let Some(message) = self.take_exit_message() {
+            println!("{}", message);
+        }
+
+        Ok(())
+    }
+
+}
+
+/// Replace runs of at least `min_chars` printable terminal keys with a paste of their text
+fn coalesce_key_bursts(
+    events: Vec<(Event, EventSource)>,
+    min_chars: usize,
+) -> Vec<(Event, EventSource)> {
+    let printable = |event: &Event, source: EventSource| {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        if source != EventSource::Terminal || !(key.modifiers - KeyModifiers::SHIFT).is_empty() {
+            return None;
+        }
+        match key.code {
+            KeyCode::Char(c) => Some(c),
+            KeyCode::Enter => Some('\n'),
+            KeyCode::Tab => Some('\t'),
+            _ => None,
+        }
+    };
+
+    let mut coalesced = Vec::with_capacity(events.len());
+    let mut run: Vec<(Event, EventSource)> = Vec::new();
+    let mut text = String::new();
+    let flush = |run: &mut Vec<_>, text: &mut String, out: &mut Vec<_>| {
+        if run.len() >= min_chars {
+            out.push((Event::Paste(std::mem::take(text)), EventSource::Terminal));
+            run.clear();
+        } else {
+            out.append(run);
+            text.clear();
+        }
+    };
+    for (event, source) in events {
+        match printable(&event, source) {
+            Some(c) => {
+                text.push(c);
+                run.push((event, source));
+            }
+            None => {
+                flush(&mut run, &mut text, &mut coalesced);
+                coalesced.push((event, source));
+            }
+        }
+    }
+    flush(&mut run, &mut text, &mut coalesced);
+    coalesced
+}
+
+/// Draw the "terminal too small" message in place of the UI
+fn draw_too_small(f: &mut Frame<'_>, area: Rect, (min_width, min_height): (u16, u16)) {
+    let message = format!(
+        "Terminal too small (need {}x{}, current {}x{})",
+        min_width, min_height, area.width, area.height
+    );
+    let lines = (message.len() as u16).div_ceil(area.width.max(1));
+
+    f.render_widget(
+        Paragraph::new(message)
+            .centered()
+            .wrap(Wrap { trim: true }),
+        centered_rect(area.width, lines, area),
+    );
+}
+
+/// Add the default keybindings to `keybindings`, without overriding its own
+fn with_defaults(keybindings: KeyBindings) -> KeyBindings {
+    let mut merged = KeyBindings::default();
+    merged.extend(keybindings);
+    merged
+}
+
+/// Get the name apps configure an action by: the app action itself or the message topic
+fn action_name(action: &Action) -> Option<&String> {
+    match action {
+        Action::AppAction(name) => Some(name),
+        Action::Message(message) => Some(&message.topic),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComponentAccessor, ComponentContext};
+
+    #[test]
+    fn test_startup_actions_are_queued_before_quit() {
+        let mut app = App::new([("<q>", "quit")], vec![]).with_startup_actions(vec![
+            Action::AppAction("load".to_string()),
+            Action::Quit,
+        ]);
+        app.wire_components().unwrap();
+
+        let mut actions = Vec::new();
+        while let Ok(action) = app.try_recv() {
+            actions.push(action);
+        }
+
+        assert_eq!(
+            actions,
+            vec![Action::AppAction("load".to_string()), Action::Quit]
+        );
+    }
+
+    /// Declare a test component binding one key to one action
+    macro_rules! bound_component {
+        ($name:ident, $key:literal => $action:literal) => {
+            #[derive(Debug, Default)]
+            struct $name {
+                pub _ctx: ComponentContext,
+            }
+
+            impl_component_accessor!($name);
+
+            impl Component for $name {
+                fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+                fn keybindings(&self) -> KeyBindings {
+                    KeyBindings::new([($key, $action)])
+                }
+            }
+        };
+    }
+
+    bound_component!(Sidebar, "<j>" => "sidebar:down");
+    bound_component!(Editor, "<j>" => "editor:down");
+    bound_component!(Status, "<s>" => "status:toggle");
+    bound_component!(Clipboard, "<ctrl-c>" => "copy");
+
+    #[test]
+    fn test_keybindings_are_scoped_to_focus() {
+        let mut editor = Editor::default();
+        editor.child_insert("status", Box::new(Status::default()));
+        let mut app = App::new(
+            [("<q>", "quit"), ("<k>", "app:up")],
+            vec![Box::new(Sidebar::default()), Box::new(editor)],
+        );
+        app.wire_components().unwrap();
+
+        let key = |c: char| [KeyEvent::from(KeyCode::Char(c))];
+        let action = |name: &str| Some(Action::AppAction(name.to_string()));
+
+        // Without focus every component's bindings apply, later components winning
+        assert_eq!(app.find_binding(&key('j')), action("editor:down"));
+
+        app.focus("Sidebar");
+        assert_eq!(app.find_binding(&key('j')), action("sidebar:down"));
+        assert_eq!(app.find_binding(&key('s')), None);
+        assert_eq!(app.find_binding(&key('k')), action("app:up"));
+
+        // A focused child still gets its ancestors' bindings
+        app.focus("Status");
+        assert_eq!(app.find_binding(&key('s')), action("status:toggle"));
+        assert_eq!(app.find_binding(&key('j')), action("editor:down"));
+    }
+
+    #[test]
+    fn test_instances_of_one_type_keep_their_own_bindings() {
+        #[derive(Debug, Default)]
+        struct Pane {
+            action: &'static str,
+            pub _ctx: ComponentContext,
+        }
+
+        impl_component_accessor!(Pane);
+
+        impl Component for Pane {
+            fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+            fn keybindings(&self) -> KeyBindings {
+                KeyBindings::new([("<j>", self.action)])
+            }
+        }
+
+        let pane = |action| Box::new(Pane { action, ..Pane::default() });
+        let mut inner = pane("inner:down");
+        inner.child_insert("nested", pane("nested:down"));
+        let mut app = App::new([("<q>", "quit")], vec![pane("left:down"), inner]);
+        app.wire_components().unwrap();
+
+        let j = [KeyEvent::from(KeyCode::Char('j'))];
+        let action = |name: &str| Some(Action::AppAction(name.to_string()));
+
+        app.component_handlers[0].c.set_focused(true);
+        assert_eq!(app.find_binding(&j), action("left:down"));
+
+        app.component_handlers[0].c.set_focused(false);
+        app.component_handlers[1].c.set_focused(true);
+        assert_eq!(app.find_binding(&j), action("inner:down"));
+
+        app.component_handlers[1].c.set_focused(false);
+        app.component_handlers[1].c.child_mut("nested").unwrap().set_focused(true);
+        assert_eq!(app.find_binding(&j), action("nested:down"));
+    }
+
+    #[derive(Debug, Default)]
+    struct Help {
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Help);
+
+    impl Component for Help {
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+        fn global_keybindings(&self) -> KeyBindings {
+            KeyBindings::new([("<?>", "help:open"), ("<j>", "help:down")])
+        }
+    }
+
+    #[test]
+    fn test_global_keybindings_apply_regardless_of_focus() {
+        let mut editor = Editor::default();
+        editor.child_insert("help", Box::new(Help::default()));
+        let mut app = App::new(
+            [("<q>", "quit"), ("<?>", "app:help")],
+            vec![Box::new(Sidebar::default()), Box::new(editor)],
+        );
+        app.wire_components().unwrap();
+
+        let key = |c: char| [KeyEvent::from(KeyCode::Char(c))];
+        let action = |name: &str| Some(Action::AppAction(name.to_string()));
+
+        app.focus("Sidebar");
+        assert_eq!(app.find_binding(&key('?')), action("help:open"));
+        assert_eq!(app.find_binding(&key('j')), action("help:down"));
+        assert_eq!(app.find_binding(&key('q')), Some(Action::Quit));
+    }
+
+    #[test]
+    fn test_with_component_finds_first_root_of_type() {
+        let mut app = App::default().with_components(vec![
+            Box::new(Sidebar::default()),
+            Box::new(Editor::default()),
+        ]);
+
+        assert_eq!(
+            app.with_component(|editor: &mut Editor| editor.name()),
+            Some("Editor".to_string())
+        );
+        app.with_component(|sidebar: &mut Sidebar| sidebar.set_active(false));
+        assert_eq!(app.with_component(|sidebar: &mut Sidebar| sidebar.is_active()), Some(false));
+        assert_eq!(app.with_component(|_: &mut Status| ()), None);
+    }
+
+    #[test]
+    fn test_exit_message_sees_the_final_app() {
+        let mut app = App::new([("<q>", "quit")], vec![Box::new(Sidebar::default())])
+            .with_exit_message(|app| {
+                let roots = app.component_handlers.len();
+                format!("Closed {} component(s), quit: {}", roots, app.should_quit)
+            });
+        app.should_quit = true;
+
+        let message = app.take_exit_message();
+        assert_eq!(message.as_deref(), Some("Closed 1 component(s), quit: true"));
+        assert_eq!(app.take_exit_message(), None);
+    }
+
+    #[test]
+    fn test_tick_callback_runs_once_per_tick() {
+        use std::{cell::Cell, rc::Rc};
+
+        let ticks = Rc::new(Cell::new(0));
+        let counter = ticks.clone();
+        let mut app = App::new([("<q>", "quit")], vec![]).with_tick_callback(move |app| {
+            counter.set(counter.get() + 1);
+            app.dispatch(Action::AppAction("refresh".to_string()));
+        });
+
+        app.action_batch = vec![Action::Tick, Action::Render, Action::Tick];
+        assert!(app.process_action_batch().unwrap());
+        assert_eq!(ticks.get(), 2);
+
+        assert_eq!(app.try_recv().ok(), Some(Action::AppAction("refresh".to_string())));
+    }
+
+    /// Understands vim-style `dd` and `<count>j`
+    #[derive(Debug, Default)]
+    struct Vim {
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Vim);
+
+    impl Component for Vim {
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+        fn handle_key_sequence(&mut self, keys: &[KeyEvent]) -> KeySeqResult {
+            let typed = keys
+                .iter()
+                .filter_map(|key| match key.code {
+                    KeyCode::Char(c) => Some(c),
+                    _ => None,
+                })
+                .collect::<String>();
+            let command = typed.trim_start_matches(|c: char| c.is_ascii_digit());
+            let count = &typed[..typed.len() - command.len()];
+
+            match command {
+                "" if !count.is_empty() => KeySeqResult::Pending,
+                "d" => KeySeqResult::Pending,
+                "dd" => {
+                    self.send("delete");
+                    KeySeqResult::Consumed
+                }
+                "j" => {
+                    self.send_message("down", if count.is_empty() { "1" } else { count });
+                    KeySeqResult::Consumed
+                }
+                _ => KeySeqResult::Rejected,
+            }
+        }
+    }
+
+    #[test]
+    fn test_key_sequences_go_to_the_focused_component() {
+        let mut app = App::new([("<q>", "quit")], vec![Box::new(Vim::default())]);
+        app.wire_components().unwrap();
+
+        let press = |app: &mut App, keys: &str| {
+            keys.chars()
+                .map(|c| app.handle_key_sequence(KeyEvent::from(KeyCode::Char(c))))
+                .collect::<Vec<_>>()
+        };
+        let received = |app: &mut App| {
+            let Ok(Action::Message(message)) = app.try_recv() else {
+                panic!("expected a message");
+            };
+            assert_eq!(message.source, "Vim");
+            (message.topic, message.payload)
+        };
+
+        // Sequences are only offered to a focused component
+        assert_eq!(press(&mut app, "d"), vec![false]);
+
+        app.focus("Vim");
+        assert_eq!(press(&mut app, "3j"), vec![true, true]);
+        assert_eq!(received(&mut app), ("down".to_string(), "3".to_string()));
+        assert_eq!(press(&mut app, "dd"), vec![true, true]);
+        assert_eq!(app.try_recv().ok(), Some(Action::AppAction("delete".to_string())));
+
+        // A rejected key restarts the sequence, then falls through if still rejected
+        assert_eq!(press(&mut app, "dj"), vec![true, true]);
+        assert_eq!(received(&mut app), ("down".to_string(), "1".to_string()));
+        assert_eq!(press(&mut app, "dx"), vec![true, false]);
+        assert!(app.pending_keys.is_empty());
+
+        // Pending keys are dropped once the timeout passes
+        app.config.key_sequence_timeout = Duration::ZERO;
+        assert_eq!(press(&mut app, "5j"), vec![true, true]);
+        assert_eq!(received(&mut app), ("down".to_string(), "1".to_string()));
+    }
+
+    #[derive(Debug, Default)]
+    struct Label {
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Label);
+
+    impl Component for Label {
+        fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+            f.buffer_mut().set_string(area.x, area.y, "hi", ratatui::style::Style::default());
+        }
+    }
+
+    #[test]
+    fn test_empty_app_draws_a_placeholder() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut terminal = Terminal::new(TestBackend::new(20, 3)).unwrap();
+        let mut app = App::default();
+        terminal.draw(|f| app.render_into(f, f.area())).unwrap();
+        assert_eq!(terminal.backend().buffer()[(4, 1)].symbol(), " ");
+
+        let mut app = App::default().with_empty_placeholder(true);
+        terminal.draw(|f| app.render_into(f, f.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+        let row = (0..20).map(|x| buffer[(x, 1)].symbol()).collect::<String>();
+        assert_eq!(row.trim(), "No components");
+    }
+
+    #[test]
+    fn test_small_terminals_show_the_minimum_size() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut app = App::default()
+            .with_components(vec![Box::new(Label::default())])
+            .with_min_size(20, 3);
+        let mut draw = |width| {
+            let mut terminal = Terminal::new(TestBackend::new(width, 3)).unwrap();
+            terminal.draw(|f| app.render_into(f, f.area())).unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            (0..3)
+                .map(|y| (0..width).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let small = draw(19);
+        assert!(small.contains("Terminal too small"), "{}", small);
+        // The message wraps to fit, showing the needed and current sizes
+        assert!(small.contains("need 20x3") && small.contains("19x3)"), "{}", small);
+        assert!(!small.starts_with("hi"), "{}", small);
+
+        // The UI comes back once the terminal is large enough
+        let large = draw(20);
+        assert!(large.starts_with("hi") && !large.contains("too small"), "{}", large);
+    }
+
+    #[test]
+    fn test_render_into_draws_in_the_given_area() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        // Embedded apps don't need a quit binding
+        let mut app = App::default().with_components(vec![Box::new(Label::default())]);
+        let mut terminal = Terminal::new(TestBackend::new(10, 4)).unwrap();
+        let area = Rect::new(3, 2, 5, 2);
+
+        terminal.draw(|f| app.render_into(f, area)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer[(3, 2)].symbol(), "h");
+        assert_eq!(buffer[(4, 2)].symbol(), "i");
+        assert_eq!(buffer[(0, 0)].symbol(), " ");
+        assert!(app.wired && app.initialized);
+    }
+
+    #[derive(Debug, Default)]
+    struct Echo {
+        pongs: usize,
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Echo);
+
+    impl Component for Echo {
+        fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+            let text = self.pongs.to_string();
+            f.buffer_mut().set_string(area.x, area.y, text, ratatui::style::Style::default());
+        }
+
+        fn on_message(&mut self, message: &Message) {
+            match message.topic.as_str() {
+                "ping" => self.send_action(Action::AppAction("pong".to_string())),
+                "pong" => self.pongs += 1,
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_queued_work_is_processed_until_idle() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut app = App::new([("<p>", "ping")], vec![Box::new(Echo::default())]);
+        app.injector().inject(Event::Key(KeyEvent::from(KeyCode::Char('p'))));
+        let mut terminal_events = vec![Event::Key(KeyEvent::from(KeyCode::Char('p')))];
+        app.process_queued(|| terminal_events.pop()).unwrap();
+
+        assert_eq!(app.with_component(|echo: &mut Echo| echo.pongs), Some(2));
+        assert!(app.try_recv().is_err());
+
+        let mut terminal = Terminal::new(TestBackend::new(2, 1)).unwrap();
+        terminal.draw(|f| app.render_into(f, f.area())).unwrap();
+        assert_eq!(terminal.backend().buffer()[(0, 0)].symbol(), "2");
+    }
+
+    #[test]
+    fn test_action_batch_is_filled_by_priority() {
+        let app_action = |name: &str| Action::AppAction(name.to_string());
+        let mut app = App::new([("<q>", "quit")], vec![])
+            .with_action_priority("error", Priority::High);
+
+        for action in [app_action("a"), app_action("error"), app_action("b")] {
+            app.dispatch(action);
+        }
+        app.fill_action_batch();
+        assert_eq!(
+            app.action_batch,
+            vec![app_action("error"), app_action("a"), app_action("b")]
+        );
+
+        // Quit jumps the queue and is batched on its own
+        app.action_batch.clear();
+        for action in [app_action("a"), Action::Quit, app_action("b")] {
+            app.dispatch(action);
+        }
+        app.fill_action_batch();
+        assert_eq!(app.action_batch, vec![Action::Quit]);
+
+        app.action_batch.clear();
+        app.fill_action_batch();
+        assert_eq!(app.action_batch, vec![app_action("a"), app_action("b")]);
+    }
+
+    #[test]
+    fn test_event_and_action_batches_are_bounded() {
+        let mut app = App::new([("<q>", "quit")], vec![]);
+        app.config.max_events_per_batch = 3;
+        app.config.max_actions_per_batch = 2;
+
+        // Terminal events come first, and what doesn't fit waits for the next batch
+        let mut terminal = (0..4).map(|_| Event::Render);
+        app.injector().inject(Event::FocusGained);
+        app.fill_event_batch(|| terminal.next());
+        assert_eq!(app.event_batch.len(), 3);
+        assert_eq!(terminal.len(), 1);
+        assert!(!app.injected_rx.is_empty());
+
+        app.event_batch.clear();
+        app.fill_event_batch(|| terminal.next());
+        assert_eq!(app.event_batch.len(), 2);
+        assert!(matches!(app.event_batch[1], (Event::FocusGained, EventSource::Injected)));
+
+        // A flood of actions is split over batches the same way
+        for name in ["a", "b", "c"] {
+            app.dispatch(Action::AppAction(name.to_string()));
+        }
+        app.fill_action_batch();
+        assert_eq!(app.action_batch.len(), 2);
+        app.action_batch.clear();
+        app.fill_action_batch();
+        assert_eq!(app.action_batch, vec![Action::AppAction("c".to_string())]);
+    }
+
+    #[test]
+    fn test_insert_and_reorder_root_components() {
+        let mut app = App::new([("<q>", "quit")], vec![Box::new(Sidebar::default())]);
+        app.wire_components().unwrap();
+
+        assert!(app.insert_component(2, Box::new(Status::default())).is_err());
+        app.insert_component(0, Box::new(Editor::default())).unwrap();
+        app.insert_component(2, Box::new(Status::default())).unwrap();
+
+        let names = |app: &App| {
+            app.component_handlers
+                .iter()
+                .map(|handler| handler.c.name())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&app), ["Editor", "Sidebar", "Status"]);
+
+        let key = |c: char| [KeyEvent::from(KeyCode::Char(c))];
+        let action = |name: &str| Some(Action::AppAction(name.to_string()));
+
+        // Components inserted after wiring contribute keybindings right away
+        assert_eq!(app.find_binding(&key('s')), action("status:toggle"));
+
+        // Without focus the last root's bindings win, so reordering changes precedence
+        assert_eq!(app.find_binding(&key('j')), action("sidebar:down"));
+        app.reorder_components(&[1, 2, 0]).unwrap();
+        assert_eq!(names(&app), ["Sidebar", "Status", "Editor"]);
+        assert_eq!(app.find_binding(&key('j')), action("editor:down"));
+
+        assert!(app.reorder_components(&[0, 1]).is_err());
+        assert!(app.reorder_components(&[0, 0, 1]).is_err());
+        assert!(app.reorder_components(&[0, 1, 3]).is_err());
+        assert_eq!(names(&app), ["Sidebar", "Status", "Editor"]);
+    }
+
+    #[test]
+    fn test_named_roots_can_be_focused_inline() {
+        let mut app = App::new([("<q>", "quit")], vec![]);
+        app.add_component_named("left", Box::new(Sidebar::default()));
+        app.add_component_named("right", Box::new(Sidebar::default())).focus();
+
+        // Focus is applied once components are wired up
+        assert!(!app.component_handlers[1].has_focus());
+        app.wire_components().unwrap();
+        assert!(!app.component_handlers[0].has_focus());
+        assert!(app.component_handlers[1].has_focus());
+
+        let handle = app.add_component_named("status", Box::new(Status::default())).focus();
+        assert_eq!(handle.name(), "status");
+        assert!(app.component_handlers[2].has_focus());
+        assert!(!app.component_handlers[1].has_focus());
+        assert_eq!(app.component_handlers[2].c.name(), "status");
+
+        // A root that's gone by the time components are wired up is reported in the app
+        let mut app = App::new([("<q>", "quit")], vec![]);
+        app.initial_focus = Some("gone".to_string());
+        app.wire_components().unwrap();
+        let warning = app.try_recv().unwrap();
+        assert!(matches!(warning, Action::Notify { level: NotifyLevel::Warning, .. }));
+    }
+
+    #[test]
+    fn test_metrics_history_is_bounded_and_survives_reset() {
+        let mut app = App::default().with_metrics_snapshots(Duration::ZERO, 2);
+
+        for processed in 1..=3 {
+            app.metrics.actions_processed = processed;
+            app.snapshot_metrics();
+        }
+        let history = app.metrics_history();
+        assert_eq!(
+            history.iter().map(|m| m.actions_processed).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+
+        app.reset_metrics();
+        assert_eq!(app.get_metrics().actions_processed, 0);
+        assert_eq!(app.metrics_history().len(), 2);
+    }
+
+    #[derive(Debug, Default)]
+    struct Loader {
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Loader);
+
+    impl Component for Loader {
+        fn init(&mut self, _area: Rect) {
+            self.send_action(Action::AppAction("load".to_string()));
+        }
+
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+    }
+
+    #[test]
+    fn test_actions_sent_from_init_are_delivered() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut app = App::default().with_components(vec![Box::new(Loader::default())]);
+        let mut terminal = Terminal::new(TestBackend::new(4, 2)).unwrap();
+
+        terminal.draw(|f| app.render_into(f, f.area())).unwrap();
+        assert_eq!(app.try_recv().ok(), Some(Action::AppAction("load".to_string())));
+
+        // `init` only runs once
+        terminal.draw(|f| app.render_into(f, f.area())).unwrap();
+        assert!(app.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_registered_stores_are_flushed_each_frame() {
+        #[derive(Clone, Debug)]
+        struct Count(u32);
+        impl AppState for Count {}
+
+        let store = Store::new(Count(0), |count, _: &()| Count(count.0 + 1))
+            .with_coalesced_notify(true);
+        let mut rx = store.subscribe();
+        let mut app = App::default().with_store(&store);
+        app.wire_components().unwrap();
+
+        store.dispatch_all(&[()]);
+        store.dispatch(&());
+        assert!(rx.try_recv().is_err());
+        app.flush_stores();
+        assert_eq!(rx.try_recv().unwrap().0, 2);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_render_hook_sees_the_drawn_frame() {
+        use ratatui::{backend::TestBackend, style::Color, Terminal};
+
+        let mut app = App::default()
+            .with_components(vec![Box::new(Label::default())])
+            .with_render_hook(|buffer| {
+                let area = buffer.area;
+                buffer.set_style(area, ratatui::style::Style::default().bg(Color::Blue));
+                buffer.set_string(3, 1, "w", ratatui::style::Style::default());
+            });
+        app.wire_components().unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(4, 2)).unwrap();
+        terminal.draw(|f| app.draw_frame(f)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer[(0, 0)].symbol(), "h");
+        assert_eq!(buffer[(0, 0)].bg, Color::Blue);
+        assert_eq!(buffer[(3, 1)].symbol(), "w");
+    }
+
+    #[test]
+    fn test_frames_over_budget_are_counted() {
+        let mut app = App::default().with_frame_rate(20.0);
+
+        // The default budget follows the frame rate: 50ms at 20fps
+        app.check_frame_budget(Duration::from_millis(40));
+        app.check_frame_budget(Duration::from_millis(60));
+        assert_eq!(app.get_metrics().frames_over_budget, 1);
+
+        let mut app = app.with_frame_budget(Duration::from_millis(100));
+        app.check_frame_budget(Duration::from_millis(60));
+        assert_eq!(app.get_metrics().frames_over_budget, 1);
+    }
+
+    #[cfg(feature = "theme-files")]
+    #[test]
+    fn test_theme_dir_errors_are_warned_in_app() {
+        let dir = std::env::temp_dir().join(format!("weavetui-app-themes-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("broken.toml"), "[colors\n").unwrap();
+        std::fs::write(dir.join("light.toml"), "[colors]\nprimary = \"red\"\n").unwrap();
+        let warnings = |app: &mut App| {
+            std::iter::from_fn(|| app.try_recv().ok())
+                .filter_map(|action| match action {
+                    Action::Notify { level: NotifyLevel::Warning, text, .. } => Some(text),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut app = App::default().with_theme_dir(&dir);
+        let skipped = warnings(&mut app);
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].starts_with("Skipping theme"), "{}", skipped[0]);
+        assert!(app.theme_manager.has_active_theme());
+
+        let mut app = App::default().with_theme_dir(dir.join("missing"));
+        let unreadable = warnings(&mut app);
+        assert_eq!(unreadable.len(), 1);
+        assert!(unreadable[0].starts_with("Failed to read theme directory"), "{}", unreadable[0]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn test_theme_reload_errors_are_warned_in_app() {
+        let dir = std::env::temp_dir().join(format!("weavetui-app-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("live.toml");
+        std::fs::write(&file, "[colors]\nprimary = \"red\"\n").unwrap();
+
+        let mut app = App::default().watch_theme_file(&file);
+        std::fs::write(&file, "[colors\n").unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let warning = loop {
+            app.reload_watched_theme();
+            if let Ok(Action::Notify { level, text, .. }) = app.try_recv() {
+                break (level, text);
+            }
+            assert!(Instant::now() < deadline, "no warning after breaking the theme");
+            std::thread::sleep(Duration::from_millis(10));
+        };
+
+        assert_eq!(warning.0, NotifyLevel::Warning);
+        assert!(warning.1.starts_with("Keeping the previous theme"), "{}", warning.1);
+        assert!(app.theme_manager.has_active_theme());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_changed_cells_are_counted_per_frame() {
+        let mut app = App::default().with_performance_monitoring(true);
+        let mut frame = |buffer: &Buffer| {
+            app.metrics.frame_count += 1;
+            app.record_cells_changed(buffer);
+            let metrics = app.get_metrics();
+            (metrics.last_cells_changed, metrics.max_cells_changed, metrics.average_cells_changed)
+        };
+
+        // The first frame, and any after a resize, redraw every cell
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 2));
+        assert_eq!(frame(&buffer), (8, 8, 8.0));
+        buffer.set_string(0, 0, "ab", ratatui::style::Style::default());
+        assert_eq!(frame(&buffer), (2, 8, 5.0));
+        assert_eq!(frame(&buffer), (0, 8, 10.0 / 3.0));
+        assert_eq!(frame(&Buffer::empty(Rect::new(0, 0, 5, 2))), (10, 10, 5.0));
+    }
+
+    #[test]
+    fn test_only_the_first_frame_over_budget_warns() {
+        let mut app = App::default().with_frame_budget(Duration::from_millis(10));
+        for _ in 0..3 {
+            app.check_frame_budget(Duration::from_millis(20));
+        }
+        assert_eq!(app.get_metrics().frames_over_budget, 3);
+
+        let warnings = std::iter::from_fn(|| app.try_recv().ok())
+            .filter(|action| matches!(action, Action::Notify { level: NotifyLevel::Warning, .. }))
+            .count();
+        assert_eq!(warnings, 1);
+    }
+
+    #[derive(Debug, Default)]
+    struct Field {
+        order: Option<i32>,
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Field);
+
+    impl Component for Field {
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+        fn accepts_focus(&self) -> bool {
+            true
+        }
+
+        fn focus_order(&self) -> Option<i32> {
+            self.order
+        }
+    }
+
+    #[test]
+    fn test_focus_traversal_skips_components_not_accepting_focus() {
+        let field = |order| Box::new(Field { order, ..Default::default() });
+        let mut app = App::default();
+        app.add_component_named("name", field(None));
+        app.add_component_named("title", Box::new(Label::default()));
+        app.add_component_named("email", field(None));
+        app.add_component_named("search", field(Some(0)));
+        let mut hidden = field(None);
+        hidden.set_active(false);
+        app.add_component_named("hidden", hidden);
+        app.wire_components().unwrap();
+
+        let focused = |app: &mut App| {
+            let mut path = Vec::new();
+            app.component_handlers
+                .iter_mut()
+                .any(|handler| handler.handle_focus_path(&mut path));
+            path.pop().unwrap_or_default()
+        };
+
+        let mut order = Vec::new();
+        for _ in 0..4 {
+            assert!(app.focus_next());
+            order.push(focused(&mut app));
+        }
+        assert_eq!(order, ["search", "name", "email", "search"]);
+
+        assert!(app.focus_prev());
+        assert!(app.focus_prev());
+        assert_eq!(focused(&mut app), "name");
+
+        assert!(!App::default().focus_next());
+    }
+
+    #[test]
+    fn test_renders_are_capped_at_the_frame_rate() {
+        let mut app = App::default()
+            .with_frame_rate(10.0)
+            .with_performance_monitoring(true);
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert!(app.render_due(at(0)));
+        assert!(!app.render_due(at(20)));
+        assert!(!app.render_due(at(50)));
+        assert!(app.render_due(at(100)));
+        assert_eq!(app.get_metrics().frames_coalesced, 2);
+    }
+
+    #[test]
+    fn test_paused_input_is_dropped_or_replayed() {
+        let quit_key = || Event::Key(KeyEvent::from(KeyCode::Char('q')));
+        let app_action = |name: &str| Some(Action::AppAction(name.to_string()));
+
+        for replay in [false, true] {
+            let mut app = App::new([("<q>", "quit")], vec![]).with_replay_paused_input(replay);
+            app.wire_components().unwrap();
+
+            app.pause_input();
+            assert!(app.is_input_paused());
+            assert_eq!(app.try_recv().ok(), app_action("input:paused"));
+
+            let tick = Event::Tick { count: 1, elapsed: Duration::ZERO };
+            app.event_batch = vec![
+                (quit_key(), EventSource::Terminal),
+                (tick, EventSource::Terminal),
+            ];
+            app.process_event_batch().unwrap();
+            assert_eq!(app.try_recv().ok(), Some(Action::Tick));
+            assert!(app.try_recv().is_err());
+
+            app.resume_input();
+            assert_eq!(app.try_recv().ok(), app_action("input:resumed"));
+            app.process_event_batch().unwrap();
+            let replayed = app.try_recv().ok();
+            assert_eq!(replayed, replay.then_some(Action::Quit), "replay: {replay}");
+        }
+    }
+
+    #[test]
+    fn test_physical_bindings_follow_the_keyboard_layout() {
+        let key = |c: char| [KeyEvent::from(KeyCode::Char(c))];
+        let bindings = [("<phys:w>", "player:up"), ("<z>", "app:undo")];
+        let action = |name: &str| Some(Action::AppAction(name.to_string()));
+
+        let mut app = App::new(bindings, vec![]);
+        assert_eq!(app.find_binding(&key('w')), action("player:up"));
+
+        let mut app = App::new(bindings, vec![]).with_keyboard_layout(KeyboardLayout::Azerty);
+        assert_eq!(app.find_binding(&key('w')), None);
+        // Bindings by character win over the physical key typing the same character
+        assert_eq!(app.find_binding(&key('z')), action("app:undo"));
+        app.keybindings.0.retain(|keys, _| keys != &key('z'));
+        assert_eq!(app.find_binding(&key('z')), action("player:up"));
+    }
+
+    #[test]
+    fn test_registered_actions_resolve_in_keymaps() {
+        #[derive(Debug, PartialEq)]
+        struct Save;
+
+        let mut app = App::new([("<s>", "test:save"), ("<q>", "quit")], vec![])
+            .register_action("test:save", || Action::custom(Save));
+        let key = |c: char| [KeyEvent::from(KeyCode::Char(c))];
+        let saved = app.find_binding(&key('s'));
+        assert!(saved.as_ref().and_then(Action::as_custom::<Save>).is_some());
+        assert_eq!(app.find_binding(&key('q')), Some(Action::Quit));
+
+        // Bindings added later resolve the name too, but only in this app
+        app.keybindings
+            .extend(KeyBindings::new(crate::kb!["<ctrl-s>" => "test:save", "<x>" => "test:other"]));
+        let ctrl_s = [KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL)];
+        let saved = app.find_binding(&ctrl_s);
+        assert!(saved.as_ref().and_then(Action::as_custom::<Save>).is_some());
+        let other = Some(Action::AppAction("test:other".to_string()));
+        assert_eq!(app.find_binding(&key('x')), other);
+
+        let mut other_app = App::new([("<s>", "test:save")], vec![]);
+        let unresolved = Some(Action::AppAction("test:save".to_string()));
+        assert_eq!(other_app.find_binding(&key('s')), unresolved);
+    }
+
+    #[test]
+    fn test_event_bindings_send_actions() {
+        let mut app = App::default().with_event_bindings([
+            (EventPattern::FocusLost, "app:pause"),
+            (EventPattern::ResizeBelow(80, 24), "app:compact"),
+        ]);
+        app.wire_components().unwrap();
+
+        app.event_batch = vec![
+            (Event::FocusLost, EventSource::Terminal),
+            (Event::FocusGained, EventSource::Terminal),
+            (Event::Resize(100, 30), EventSource::Terminal),
+            (Event::Resize(100, 20), EventSource::Terminal),
+        ];
+        app.process_event_batch().unwrap();
+
+        let mut actions = Vec::new();
+        while let Ok(action) = app.try_recv() {
+            actions.push(action);
+        }
+        let app_action = |name: &str| Action::AppAction(name.to_string());
+        assert_eq!(
+            actions,
+            [
+                app_action("app:pause"),
+                Action::Resize(100, 30),
+                app_action("app:compact"),
+                Action::Resize(100, 20),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unfocused_paste_warns_once_in_the_app() {
+        let mut app = App::default().with_components(vec![Box::new(Label::default())]);
+        app.wire_components().unwrap();
+        let warnings = |app: &mut App| {
+            std::iter::from_fn(|| app.try_recv().ok())
+                .filter(|action| {
+                    matches!(action, Action::Notify { level: NotifyLevel::Warning, .. })
+                })
+                .count()
+        };
+
+        for _ in 0..2 {
+            let paste = Event::Paste("hi".to_string());
+            app.event_batch.push((paste, EventSource::Terminal));
+            app.process_event_batch().unwrap();
+        }
+        assert_eq!(warnings(&mut app), 1);
+    }
+
+    #[test]
+    fn test_keys_match_bindings_with_num_lock_on() {
+        use crossterm::event::{KeyEventKind, KeyEventState};
+
+        let mut app = App::new([("<ctrl-c>", "quit"), ("<enter>", "submit")], vec![]);
+        let with_state = |code, modifiers, state| {
+            let kind = KeyEventKind::Press;
+            let key = KeyEvent::new_with_kind_and_state(code, modifiers, kind, state);
+            (Event::Key(key), EventSource::Terminal)
+        };
+        app.event_batch.push(with_state(
+            KeyCode::Char('c'),
+            KeyModifiers::CONTROL,
+            KeyEventState::NUM_LOCK,
+        ));
+        app.event_batch.push(with_state(
+            KeyCode::Enter,
+            KeyModifiers::NONE,
+            KeyEventState::KEYPAD | KeyEventState::NUM_LOCK,
+        ));
+        app.process_event_batch().unwrap();
+
+        assert_eq!(app.try_recv().ok(), Some(Action::Quit));
+        assert_eq!(app.try_recv().ok(), Some(Action::AppAction("submit".to_string())));
+    }
+
+    #[test]
+    fn test_key_bursts_become_pastes() {
+        let key = |code| (Event::Key(KeyEvent::from(code)), EventSource::Terminal);
+        let chars = |text: &str| text.chars().map(|c| key(KeyCode::Char(c))).collect::<Vec<_>>();
+
+        let mut events = chars("hi x");
+        events.push(key(KeyCode::Enter));
+        events.extend(chars("y"));
+        events.push(key(KeyCode::Esc));
+        events.extend(chars("ok"));
+        let coalesced = coalesce_key_bursts(events, 4);
+        // `Event` isn't `PartialEq`, so compare how the events print
+        let debug = |events: &[(Event, EventSource)]| format!("{:?}", events);
+
+        let mut expected = vec![(Event::Paste("hi x\ny".to_string()), EventSource::Terminal)];
+        expected.push(key(KeyCode::Esc));
+        expected.extend(chars("ok"));
+        assert_eq!(debug(&coalesced), debug(&expected));
+
+        // Keys with ctrl or from other sources are never part of a paste
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        let mut events = chars("ab");
+        events.push((Event::Key(ctrl_c), EventSource::Terminal));
+        events.push((Event::Key(KeyEvent::from(KeyCode::Char('d'))), EventSource::Injected));
+        assert_eq!(debug(&coalesce_key_bursts(events.clone(), 2)[1..]), debug(&events[2..]));
+    }
+
+    #[test]
+    fn test_seeded_apps_give_components_the_same_numbers() {
+        let draw_numbers = || {
+            let mut parent = Label::default();
+            parent.child_insert("child", Box::new(Label::default()));
+            let mut app = App::default().with_components(vec![Box::new(parent)]).with_seed(7);
+            app.wire_components().unwrap();
+            app.with_component(|parent: &mut Label| {
+                let first = parent.get_context().rng().next_u64();
+                let child = parent.child("child").unwrap();
+                (first, child.get_context().rng().next_u64())
+            })
+        };
+
+        let (first, second) = draw_numbers().unwrap();
+        assert_ne!(first, second);
+        assert_eq!(draw_numbers(), Some((first, second)));
+    }
+
+    #[test]
+    fn test_repeated_io_errors_quit() {
+        use std::{cell::Cell, rc::Rc};
+
+        let errors = Rc::new(Cell::new(0));
+        let seen = errors.clone();
+        let mut app = App::new([("<q>", "quit")], vec![])
+            .on_io_error(move || seen.set(seen.get() + 1));
+        app.wire_components().unwrap();
+
+        // Input read between errors resets the count
+        let key = Event::Key(KeyEvent::from(KeyCode::Char('x')));
+        app.event_batch = vec![
+            (Event::Error, EventSource::Terminal),
+            (Event::Error, EventSource::Terminal),
+            (key, EventSource::Terminal),
+            (Event::Error, EventSource::Terminal),
+        ];
+        app.process_event_batch().unwrap();
+        assert_eq!(errors.get(), 3);
+        while let Ok(action) = app.try_recv() {
+            assert_ne!(action, Action::Quit);
+        }
+
+        app.event_batch = vec![(Event::Error, EventSource::Terminal); 2];
+        app.process_event_batch().unwrap();
+        assert_eq!(errors.get(), 5);
+        let actions = std::iter::from_fn(|| app.try_recv().ok()).collect::<Vec<_>>();
+        let warned = |action: &Action, count: u32| {
+            matches!(action, Action::Notify { level: NotifyLevel::Warning, text, .. }
+                if *text == format!("Failed to read terminal input ({} in a row)", count))
+        };
+        assert_eq!(actions.len(), 3);
+        assert!(warned(&actions[0], 2) && warned(&actions[1], 3));
+        assert_eq!(actions[2], Action::Quit);
+        assert_eq!(
+            app.exit_error.as_deref(),
+            Some("Giving up after 3 terminal read errors")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_spawned_tasks_are_cancelled_on_shutdown() {
+        let mut app = App::new([("<q>", "quit")], vec![Box::new(Label::default())]);
+        app.wire_components().unwrap();
+
+        let done = app
+            .with_component(|label: &mut Label| label.get_context().spawn(async { 42 }))
+            .unwrap();
+        assert_eq!(done.await.unwrap(), Some(42));
+
+        let pending = app
+            .with_component(|label: &mut Label| {
+                label.get_context().spawn(std::future::pending::<()>())
+            })
+            .unwrap();
+        app.shutdown.cancel();
+        assert_eq!(pending.await.unwrap(), None);
+    }
+
+    #[derive(Debug, Default)]
+    struct Counter {
+        topics: Vec<String>,
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Counter);
+
+    impl Component for Counter {
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+        fn on_message(&mut self, message: &Message) {
+            self.topics.push(message.topic.clone());
+        }
+    }
+
+    #[test]
+    fn test_idempotent_duplicates_are_handled_once() {
+        let mut app = App::new([("<q>", "quit")], vec![Box::new(Counter::default())])
+            .with_idempotent_action("resort");
+        app.wire_components().unwrap();
+
+        for name in ["resort", "count", "resort", "count", "resort"] {
+            app.dispatch(Action::AppAction(name.to_string()));
+        }
+        app.fill_action_batch();
+        app.process_action_batch().unwrap();
+
+        let topics = app.with_component(|counter: &mut Counter| counter.topics.clone());
+        assert_eq!(topics.unwrap(), ["count", "count", "resort"]);
+
+        let mut batch = vec![Action::Render, Action::Tick, Action::Render, Action::Tick];
+        app.dedup_action_batch(&mut batch);
+        assert_eq!(batch, [Action::Tick, Action::Render, Action::Tick]);
+
+        // The last size is the one that sticks
+        let mut batch = vec![
+            Action::Resize(80, 24),
+            Action::Resize(100, 30),
+            Action::Resize(80, 24),
+        ];
+        app.dedup_action_batch(&mut batch);
+        assert_eq!(batch, [Action::Resize(100, 30), Action::Resize(80, 24)]);
+    }
+
+    #[derive(Debug, Default)]
+    struct Starter {
+        size: Option<(u16, u16)>,
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Starter);
+
+    impl Component for Starter {
+        fn on_start(&mut self, size: (u16, u16)) {
+            self.size = Some(size);
+        }
+
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+    }
+
+    #[test]
+    fn test_on_start_reaches_children() {
+        let mut parent = Starter::default();
+        parent.child_insert("child", Box::new(Starter::default()));
+
+        let mut handler = ComponentHandler::for_(Box::new(parent));
+        handler.handle_start((80, 24));
+
+        let child = handler.c.get_children().get_mut("child").unwrap();
+        assert_eq!(child.downcast_ref::<Starter>().unwrap().size, Some((80, 24)));
+        assert_eq!(handler.with(|parent: &mut Starter| parent.size), Some(Some((80, 24))));
+    }
+
+    #[derive(Debug, Default)]
+    struct Witness {
+        sources: Vec<EventSource>,
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Witness);
+
+    impl Component for Witness {
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+        fn handle_key_events(&mut self, _key: KeyEvent) -> Option<Action> {
+            self.sources.push(self.get_context().event_source.get());
+            None
+        }
+    }
+
+    #[test]
+    fn test_components_see_where_events_came_from() {
+        let mut parent = Witness::default();
+        parent.child_insert("child", Box::new(Witness::default()));
+        let mut app = App::new([("<q>", "quit")], vec![Box::new(parent)]);
+        app.wire_components().unwrap();
+
+        let key = || Event::Key(KeyEvent::from(KeyCode::Char('x')));
+        let injector = app.injector();
+        injector.inject(key());
+        injector.send(key(), EventSource::Replay);
+        app.event_batch.push((key(), EventSource::Terminal));
+        while let Ok(injected) = app.injected_rx.try_recv() {
+            app.event_batch.push(injected);
+        }
+        app.process_event_batch().unwrap();
+
+        let expected = [EventSource::Terminal, EventSource::Injected, EventSource::Replay];
+        let sources = app.with_component(|parent: &mut Witness| {
+            let own = parent.sources.clone();
+            let child = parent.child("child").unwrap();
+            (own, child.downcast_ref::<Witness>().unwrap().sources.clone())
+        });
+        assert_eq!(sources, Some((expected.to_vec(), expected.to_vec())));
+        assert_eq!(app.event_source.get(), EventSource::Terminal);
+    }
+
+    #[test]
+    fn test_replayed_events_are_not_recorded_again() {
+        let mut app = App::new([("<q>", "quit")], vec![Box::new(Witness::default())]);
+        app.wire_components().unwrap();
+        let key = |c: char| Event::Key(KeyEvent::from(KeyCode::Char(c)));
+        let drain_injected = |app: &mut App| {
+            while let Ok(injected) = app.injected_rx.try_recv() {
+                app.event_batch.push(injected);
+            }
+        };
+
+        app.start_recording();
+        app.event_batch.push((key('a'), EventSource::Terminal));
+        let tick = Event::Tick { count: 1, elapsed: Duration::ZERO };
+        app.event_batch.push((tick, EventSource::Terminal));
+        app.event_batch.push((key('b'), EventSource::Terminal));
+        app.process_event_batch().unwrap();
+        let recorded = app.stop_recording();
+        assert_eq!(recorded.len(), 2);
+
+        app.start_recording();
+        app.play(recorded);
+        assert!(app.is_replaying());
+        drain_injected(&mut app);
+        app.process_event_batch().unwrap();
+        assert!(!app.is_replaying());
+        assert!(app.stop_recording().is_empty());
+
+        let sources = app.with_component(|witness: &mut Witness| witness.sources.clone());
+        let (terminal, replay) = (EventSource::Terminal, EventSource::Replay);
+        assert_eq!(sources, Some(vec![terminal, terminal, replay, replay]));
+    }
+
+    #[test]
+    fn test_replay_finishes_when_paused_input_is_dropped_or_kept() {
+        let key = |c: char| Event::Key(KeyEvent::from(KeyCode::Char(c)));
+        let play_paused = |replay_paused_input| {
+            let mut app = App::new([("<q>", "quit")], vec![Box::new(Witness::default())])
+                .with_replay_paused_input(replay_paused_input);
+            app.wire_components().unwrap();
+            app.pause_input();
+            app.play([key('a'), key('b')]);
+            while let Ok(injected) = app.injected_rx.try_recv() {
+                app.event_batch.push(injected);
+            }
+            app.process_event_batch().unwrap();
+            app
+        };
+
+        let app = play_paused(false);
+        assert!(!app.is_replaying());
+
+        // Kept events are still pending until they're handled on resume
+        let mut app = play_paused(true);
+        assert!(app.is_replaying());
+        app.resume_input();
+        app.process_event_batch().unwrap();
+        assert!(!app.is_replaying());
+    }
+
+    #[test]
+    fn test_unbound_keys_are_reported_with_suggestions() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let reported = Rc::new(RefCell::new(Vec::new()));
+        let log = reported.clone();
+        let mut app = App::new(
+            [("<q>", "quit"), ("<ctrl-up>", "app:top"), ("<g><g>", "app:start")],
+            vec![Box::new(Sidebar::default())],
+        )
+        .on_unbound_key(move |key, suggestions| {
+            log.borrow_mut().push((key_event_to_string(key), suggestions.to_vec()));
+        });
+        app.wire_components().unwrap();
+
+        for code in [KeyCode::Up, KeyCode::Char('x'), KeyCode::Char('g'), KeyCode::Esc] {
+            app.handle_key_event(KeyEvent::from(code)).unwrap();
+            app.last_tick_key_events.clear();
+        }
+
+        let expected = [
+            ("up".to_string(), vec!["<ctrl-up>".to_string()]),
+            ("esc".to_string(), vec![]),
+        ];
+        assert_eq!(*reported.borrow(), expected);
+    }
+
+    #[test]
+    fn test_keybindings_merge_or_replace() {
+        let ctrl_c = [KeyEvent::new(KeyCode::Char('c'), crossterm::event::KeyModifiers::CONTROL)];
+        let q = [KeyEvent::from(KeyCode::Char('q'))];
+
+        let app = App::default().with_keybindings([("<q>", "quit")]);
+        assert_eq!(app.keybindings.get(&ctrl_c), None);
+
+        let app = App::default().merge_keybindings([("<q>", "quit")]);
+        assert_eq!(app.keybindings.get(&ctrl_c), Some(&Action::Quit));
+        assert_eq!(app.keybindings.get(&q), Some(&Action::Quit));
+
+        // Keeping the defaults works before or after replacing, without overriding rebinds
+        for app in [
+            App::default()
+                .with_default_keybindings(true)
+                .with_keybindings([("<ctrl-c>", "copy")]),
+            App::new([("<ctrl-c>", "copy")], vec![]).with_default_keybindings(true),
+        ] {
+            let copy = Action::AppAction("copy".to_string());
+            assert_eq!(app.keybindings.get(&ctrl_c), Some(&copy));
+        }
+        let app = App::new([("<q>", "quit")], vec![]).with_default_keybindings(true);
+        assert_eq!(app.keybindings.get(&ctrl_c), Some(&Action::Quit));
+    }
+
+    #[test]
+    fn test_wiring_mounts_children_of_roots() {
+        let mut root = Label::default();
+        root.child_insert("child", Box::new(Label::default()));
+        assert!(!root.child_mut("child").unwrap().get_context().mounted);
+
+        let mut app = App::default().with_components(vec![Box::new(root)]);
+        app.wire_components().unwrap();
+        let root = app.component_handlers[0].c.as_mut();
+        assert!(root.get_context().mounted);
+        assert!(root.child_mut("child").unwrap().get_context().mounted);
+    }
+
+    #[test]
+    fn test_ctrl_c_can_be_rebound() {
+        let ctrl_c = [KeyEvent::new(KeyCode::Char('c'), crossterm::event::KeyModifiers::CONTROL)];
+        let copy = Some(Action::AppAction("copy".to_string()));
+
+        let mut app = App::default()
+            .with_keybindings([("<ctrl-c>", "copy")])
+            .with_components(vec![Box::new(Label::default())]);
+        app.wire_components().unwrap();
+        assert_eq!(app.find_binding(&ctrl_c), copy);
+
+        // The app keeps its default ctrl-c binding, but the focused component's wins
+        let mut clipboard = Clipboard::default();
+        clipboard.set_focused(true);
+        let mut app = App::default().with_components(vec![Box::new(clipboard)]);
+        app.wire_components().unwrap();
+        assert_eq!(app.find_binding(&ctrl_c), copy);
+    }
 }
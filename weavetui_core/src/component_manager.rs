@@ -1,62 +1,337 @@
 //! Component management utilities.
 
-use ratatui::{layout::Rect, Frame};
+use crossterm::event::KeyEvent;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Clear, Paragraph, Wrap},
+    Frame,
+};
 use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
 
 use crate::{
-    event::{Action, Event},
-    keyboard::KeyBindings,
-    theme::ThemeManager,
-    Component,
+    app::WARNING_TTL,
+    event::{Action, Event, EventMask, Message, NotifyLevel},
+    keyboard::{KeyBindings, KeySeqResult},
+    mouse::LocalMouseEvent,
+    rng::SharedRng,
+    theme::{ColorRole, ThemeManager},
+    Component, EventSourceCell, LocalStateCache,
 };
 
-/// Draw a component and its children recursively
-pub fn handle_draw<T: Component + ?Sized>(c: &mut T, f: &mut Frame<'_>) {
+/// What `handle_draw` does when a component draws outside its area.
+///
+/// Checking copies the frame buffer for every component drawn, so the default is `Clip` in
+/// debug builds and `Off` in release builds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipPolicy {
+    /// Don't check, so a component can overwrite its siblings
+    Off,
+    /// Undo any cells drawn outside the area; debug builds warn about the first offender
+    Clip,
+    /// Panic with the name of the offending component
+    Strict,
+}
+
+impl Default for ClipPolicy {
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            ClipPolicy::Clip
+        } else {
+            ClipPolicy::Off
+        }
+    }
+}
+
+/// How the content under a modal component is covered before the modal is drawn,
+/// see `Component::backdrop`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackdropStyle {
+    /// Dim the cells, keeping their content readable
+    Dim,
+    /// Replace every cell's symbol with the given character, dimmed, e.g. `'░'`
+    Blur(char),
+}
+
+impl BackdropStyle {
+    /// Cover the part of `area` inside the buffer
+    pub fn apply(&self, buffer: &mut Buffer, area: Rect) {
+        let area = area.intersection(buffer.area);
+        for position in area.positions() {
+            let Some(cell) = buffer.cell_mut(position) else {
+                continue;
+            };
+            if let BackdropStyle::Blur(symbol) = self {
+                cell.set_char(*symbol);
+            }
+            cell.modifier.insert(Modifier::DIM);
+        }
+    }
+}
+
+/// Draw a component and its children recursively.
+///
+/// Children are drawn in the area they were given with `set_area`, usually by the parent's
+/// `draw` after splitting its own area. A child without an area isn't drawn, rather than
+/// being stacked over the whole parent; debug builds warn about the first one found.
+///
+/// Each component is held to its own area according to `policy`. A component with a
+/// backdrop first has it applied to its area, covering whatever was drawn there before.
+/// Components not initialized yet, like children added after the first draw, get `init`
+/// with their area right before they're first drawn.
+///
+/// Components are drawn with the theme `c` was given, which is cheap to copy; use
+/// [`handle_draw_themed`] to pass one.
+pub fn handle_draw<T: Component + ?Sized>(c: &mut T, f: &mut Frame<'_>, policy: ClipPolicy) {
+    let theme = c.get_theme_manager().clone();
+    handle_draw_themed(c, f, policy, &theme);
+}
+
+/// Draw a component and its children like [`handle_draw`], passing `theme` to each
+/// component's `try_draw`
+pub fn handle_draw_themed<T: Component + ?Sized>(
+    c: &mut T,
+    f: &mut Frame<'_>,
+    policy: ClipPolicy,
+    theme: &ThemeManager,
+) {
     if let Some(area) = c.area() {
         if c.is_active() {
-            c.draw(f, area);
+            init_once(c, area);
+            if let Some(backdrop) = c.backdrop() {
+                backdrop.apply(f.buffer_mut(), area);
+            }
+            if c.cache_render() {
+                draw_cached(c, f, area, policy, theme);
+            } else {
+                draw_clipped(c, f, area, policy, theme);
+            }
+            c.get_context_mut().drawn_area = Some(area);
 
-            for child in c.get_children().values_mut() {
+            let mut unpositioned = None;
+            for (name, child) in c.get_children().iter_mut() {
                 if child.area().is_none() {
-                    child.set_area(area);
+                    unpositioned.get_or_insert_with(|| name.clone());
+                    continue;
                 }
-                handle_draw(child.as_mut(), f);
+                handle_draw_themed(child.as_mut(), f, policy, theme);
+            }
+            if let Some(name) = unpositioned {
+                warn_unpositioned_child(c, &name);
             }
         }
     }
 }
 
-/// Update a component and its children with an action
-pub fn update<T: Component + ?Sized>(c: &mut T, action: &Action) {
+/// Copy the component's cached cells into `area`, or draw it and cache what it drew
+fn draw_cached<T: Component + ?Sized>(
+    c: &mut T,
+    f: &mut Frame<'_>,
+    area: Rect,
+    policy: ClipPolicy,
+    theme: &ThemeManager,
+) {
+    let area = area.intersection(f.area());
+    if let Some(cache) = c.get_context().render_cache.as_ref().filter(|cache| cache.area == area) {
+        f.buffer_mut().merge(cache);
+        return;
+    }
+
+    // Errors are drawn but not cached, so drawing is tried again on the next frame
+    if !draw_clipped(c, f, area, policy, theme) {
+        return;
+    }
+    let mut cache = Buffer::empty(area);
+    for position in area.positions() {
+        cache[position] = f.buffer_mut()[position].clone();
+    }
+    c.get_context_mut().render_cache = Some(cache);
+}
+
+/// Call the component's `try_draw`, then handle cells it changed outside `area`.
+///
+/// Returns `false` if drawing failed and the error was drawn instead.
+fn draw_clipped<T: Component + ?Sized>(
+    c: &mut T,
+    f: &mut Frame<'_>,
+    area: Rect,
+    policy: ClipPolicy,
+    theme: &ThemeManager,
+) -> bool {
+    if policy == ClipPolicy::Off {
+        return try_draw(c, f, area, theme);
+    }
+
+    let before = f.buffer_mut().clone();
+    let drawn = try_draw(c, f, area, theme);
+
+    let buffer = f.buffer_mut();
+    let bounds = buffer.area;
+    let mut overflowed = false;
+    for (i, (cell, old)) in buffer.content.iter_mut().zip(&before.content).enumerate() {
+        let position = Position::new(
+            bounds.x + (i % bounds.width as usize) as u16,
+            bounds.y + (i / bounds.width as usize) as u16,
+        );
+        if cell != old && !area.contains(position) {
+            overflowed = true;
+            *cell = old.clone();
+        }
+    }
+
+    if overflowed {
+        if policy == ClipPolicy::Strict {
+            panic!("Component '{}' drew outside its area {:?}", c.name(), area);
+        }
+        warn_overflowing_component(c, area);
+    }
+    drawn
+}
+
+/// Draw the component, showing the error in its area if drawing fails
+fn try_draw<T: Component + ?Sized>(
+    c: &mut T,
+    f: &mut Frame<'_>,
+    area: Rect,
+    theme: &ThemeManager,
+) -> bool {
+    let Err(err) = c.try_draw(f, area, theme) else {
+        return true;
+    };
+
+    // The component's area may reach past the frame, which widgets don't clip to
+    let area = area.intersection(f.area());
+    let style = Style::default().fg(theme.get_current_role(ColorRole::Error));
+    let message = Paragraph::new(format!("{:#}", err))
+        .style(style)
+        .wrap(Wrap { trim: true })
+        .block(Block::bordered().title("render error").border_style(style));
+    f.render_widget(Clear, area);
+    f.render_widget(message, area);
+    false
+}
+
+/// Show a warning in the app through the component's action handler, since printing
+/// during a draw would write over the frame
+fn warn<T: Component + ?Sized>(c: &T, text: String) {
+    c.send_action(Action::Notify {
+        level: NotifyLevel::Warning,
+        text,
+        ttl: WARNING_TTL,
+    });
+}
+
+fn warn_overflowing_component<T: Component + ?Sized>(c: &T, area: Rect) {
+    static WARNED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+    if cfg!(debug_assertions) && !WARNED.swap(true, std::sync::atomic::Ordering::Relaxed) {
+        warn(
+            c,
+            format!(
+                "Component '{}' drew outside its area {:?}; the extra cells were discarded.",
+                c.name(),
+                area
+            ),
+        );
+    }
+}
+
+fn warn_unpositioned_child<T: Component + ?Sized>(parent: &T, child: &str) {
+    static WARNED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+    if cfg!(debug_assertions) && !WARNED.swap(true, std::sync::atomic::Ordering::Relaxed) {
+        warn(
+            parent,
+            format!(
+                "Child '{}' of '{}' has no area and won't be drawn. Call `set_area` on it in \
+                 the parent's `draw`.",
+                child,
+                parent.name()
+            ),
+        );
+    }
+}
+
+/// Update a component and its children with an action, collecting their follow-up actions
+pub fn update<T: Component + ?Sized>(c: &mut T, action: &Action) -> Vec<Action> {
+    let mut actions = vec![];
+
     if c.is_active() {
-        c.update(action);
+        actions.extend(c.update_with_actions(action));
 
         for child in c.get_children().values_mut() {
-            update(child.as_mut(), action);
+            actions.extend(update(child.as_mut(), action));
         }
     }
+
+    actions
+}
+
+/// When a component handles a message relative to its children
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DispatchOrder {
+    /// Before its children
+    #[default]
+    TopDown,
+    /// After its children
+    BottomUp,
 }
 
-/// Handle a string message for a component and its children
-pub fn handle_message<T: Component + ?Sized>(c: &mut T, message: &str) {
+/// Handle a message for a component and its children, in the order the component asks for
+pub fn handle_message<T: Component + ?Sized>(c: &mut T, message: &Message) {
     if c.is_active() {
-        c.on_event(message);
+        let order = c.message_order();
+        if order == DispatchOrder::TopDown {
+            c.on_message(message);
+        }
 
         for child in c.get_children().values_mut() {
             handle_message(child.as_mut(), message);
         }
+
+        if order == DispatchOrder::BottomUp {
+            c.on_message(message);
+        }
     }
 }
 
-/// Initialize a component and its children
+/// Initialize a component and its children, skipping those already initialized
 pub fn init<T: Component + ?Sized>(c: &mut T, area: Rect) {
-    c.init(area);
+    init_once(c, area);
 
     for child in c.get_children().values_mut() {
         init(child.as_mut(), area);
     }
 }
 
+/// Call `init` unless it already ran since the component was created or reinitialized
+fn init_once<T: Component + ?Sized>(c: &mut T, area: Rect) {
+    if !c.get_context().initialized {
+        c.get_context_mut().initialized = true;
+        c.init(area);
+    }
+}
+
+/// Mark a component and its children uninitialized, so `init` runs again on the next draw
+pub fn reinit<T: Component + ?Sized>(c: &mut T) {
+    c.get_context_mut().initialized = false;
+
+    for child in c.get_children().values_mut() {
+        reinit(child.as_mut());
+    }
+}
+
+/// Call `on_start` for a component and its children
+pub fn start<T: Component + ?Sized>(c: &mut T, size: (u16, u16)) {
+    c.on_start(size);
+
+    for child in c.get_children().values_mut() {
+        start(child.as_mut(), size);
+    }
+}
+
 /// Set action handler for a component and its children
 pub fn receive_action_handler<T: Component + ?Sized>(c: &mut T, tx: UnboundedSender<Action>) {
     c.register_action_handler(tx.clone());
@@ -66,15 +341,37 @@ pub fn receive_action_handler<T: Component + ?Sized>(c: &mut T, tx: UnboundedSen
     }
 }
 
+/// Collect the names of active components whose area contains a position, outermost first
+pub fn hit_test<T: Component + ?Sized>(c: &mut T, column: u16, row: u16, hits: &mut Vec<String>) {
+    if !c.is_active() {
+        return;
+    }
+
+    if c.area().is_some_and(|area| area.contains(Position::new(column, row))) {
+        hits.push(c.name());
+    }
+
+    for child in c.get_children().values_mut() {
+        hit_test(child.as_mut(), column, row, hits);
+    }
+}
+
 /// Handle events for a component and collect resulting actions
 pub fn handle_event_for<T: Component + ?Sized>(c: &mut T, event: &Option<Event>) -> Vec<Action> {
     if c.is_active() {
         let mut actions = vec![];
 
+        let subscribed = event
+            .as_ref()
+            .is_none_or(|event| c.subscribed_events().contains(EventMask::of(event)));
         let action = match event {
+            _ if !subscribed => None,
             Some(Event::Key(key_event)) => c.handle_key_events(*key_event),
-            Some(Event::Mouse(mouse_event)) => c.handle_mouse_events(*mouse_event),
-            Some(Event::Tick) => c.handle_tick_event(),
+            Some(Event::Mouse(mouse_event)) => {
+                let area = c.area().unwrap_or_default();
+                c.handle_local_mouse_events(LocalMouseEvent::new(*mouse_event, area))
+            }
+            Some(Event::Tick { count, elapsed }) => c.handle_tick_event(*count, *elapsed),
             Some(Event::Render) => c.handle_frame_event(),
             Some(Event::Paste(s)) => c.handle_paste_event(s),
             _ => None,
@@ -95,21 +392,1033 @@ pub fn handle_event_for<T: Component + ?Sized>(c: &mut T, event: &Option<Event>)
     }
 }
 
-/// Collect keybindings from a component and its children
-pub fn custom_keybindings<T: Component + ?Sized>(c: &mut T, kb: &mut KeyBindings) {
-    let other_kb = c.keybindings();
-    kb.extend(other_kb);
+/// Deliver pasted text to focused components that accept pastes
+pub fn deliver_paste<T: Component + ?Sized>(c: &mut T, text: &str) -> Vec<Action> {
+    let mut actions = vec![];
+
+    if c.is_active() {
+        let subscribed = c.subscribed_events().contains(EventMask::PASTE);
+        if c.is_focused() && c.accepts_paste() && subscribed {
+            actions.extend(c.handle_paste_event(text));
+        }
+
+        for child in c.get_children().values_mut() {
+            actions.extend(deliver_paste(child.as_mut(), text));
+        }
+    }
+
+    actions
+}
+
+/// Offer a key sequence to the focused component, returning `None` if nothing active has focus
+pub fn handle_key_sequence<T: Component + ?Sized>(
+    c: &mut T,
+    keys: &[KeyEvent],
+) -> Option<KeySeqResult> {
+    if !c.is_active() {
+        return None;
+    }
+
+    if c.is_focused() {
+        return Some(c.handle_key_sequence(keys));
+    }
+
+    c.get_children()
+        .values_mut()
+        .find_map(|child| handle_key_sequence(child.as_mut(), keys))
+}
+
+/// Check if a component or any of its children has focus
+pub fn has_focus<T: Component + ?Sized>(c: &mut T) -> bool {
+    c.is_focused() || c.get_children().values_mut().any(|child| has_focus(child.as_mut()))
+}
+
+/// Focus the first component named `name` that hasn't been `found` yet and unfocus the rest
+pub fn focus_by_name<T: Component + ?Sized>(c: &mut T, name: &str, found: &mut bool) {
+    let hit = !*found && c.name() == name;
+    *found |= hit;
+    c.set_focused(hit);
 
     for child in c.get_children().values_mut() {
-        custom_keybindings(child.as_mut(), kb);
+        focus_by_name(child.as_mut(), name, found);
     }
 }
 
+/// Collect the focus order and name of active components accepting focus, in tree order
+pub fn focusable<T: Component + ?Sized>(c: &mut T, focusable: &mut Vec<(Option<i32>, String)>) {
+    if !c.is_active() {
+        return;
+    }
+
+    if c.accepts_focus() {
+        focusable.push((c.focus_order(), c.name()));
+    }
+
+    for child in c.get_children().values_mut() {
+        self::focusable(child.as_mut(), focusable);
+    }
+}
+
+/// Collect keybindings from a component and its children, keyed by their tree path.
+///
+/// A path is the component's `path` followed by the names its descendants are registered
+/// under, joined with `/`, so two instances of the same type keep their own bindings.
+pub fn custom_keybindings<T: Component + ?Sized>(
+    c: &mut T,
+    path: &str,
+    kb: &mut Vec<(String, KeyBindings)>,
+) {
+    kb.push((path.to_string(), c.keybindings()));
+
+    for (name, child) in c.get_children().iter_mut() {
+        custom_keybindings(child.as_mut(), &format!("{}/{}", path, name), kb);
+    }
+}
+
+/// Collect the tree paths, as used by [`custom_keybindings`], of the focused component and
+/// its ancestors, outermost first. Returns whether a focused component was found.
+pub fn focus_tree_path<T: Component + ?Sized>(
+    c: &mut T,
+    path: &str,
+    paths: &mut Vec<String>,
+) -> bool {
+    paths.push(path.to_string());
+    if c.is_focused() {
+        return true;
+    }
+
+    for (name, child) in c.get_children().iter_mut() {
+        if focus_tree_path(child.as_mut(), &format!("{}/{}", path, name), paths) {
+            return true;
+        }
+    }
+
+    paths.pop();
+    false
+}
+
+/// Collect the global keybindings of a component and its children, later ones winning
+pub fn global_keybindings<T: Component + ?Sized>(c: &mut T, kb: &mut KeyBindings) {
+    kb.extend(c.global_keybindings());
+    for child in c.get_children().values_mut() {
+        global_keybindings(child.as_mut(), kb);
+    }
+}
+
+/// Collect the names from a component down to its focused descendant.
+///
+/// Returns `false`, leaving `path` as it was, if nothing in the tree has focus.
+pub fn focus_path<T: Component + ?Sized>(c: &mut T, path: &mut Vec<String>) -> bool {
+    path.push(c.name());
+    if c.is_focused() {
+        return true;
+    }
+
+    for child in c.get_children().values_mut() {
+        if focus_path(child.as_mut(), path) {
+            return true;
+        }
+    }
+
+    path.pop();
+    false
+}
+
 /// Set theme for a component and its children
 pub fn handle_theme<T: Component + ?Sized>(c: &mut T, th: &ThemeManager) {
     c.set_theme_manager(th.clone());
+    c.mark_dirty();
 
     for child in c.get_children().values_mut() {
         handle_theme(child.as_mut(), th);
     }
 }
+
+/// Share a local state cache with a component and its children, loading any state saved under their keys
+pub fn attach_local_state<T: Component + ?Sized>(c: &mut T, cache: &LocalStateCache) {
+    c.get_context_mut().local_state = cache.clone();
+
+    if let Some(state) = c.local_state_key().and_then(|key| cache.take(&key)) {
+        c.load_local_state(state);
+    }
+
+    for child in c.get_children().values_mut() {
+        attach_local_state(child.as_mut(), cache);
+    }
+}
+
+/// Link a component and its children to the app's shutdown token, see `ComponentContext::spawn`
+pub fn attach_shutdown<T: Component + ?Sized>(c: &mut T, shutdown: &CancellationToken) {
+    c.get_context_mut().shutdown = shutdown.clone();
+
+    for child in c.get_children().values_mut() {
+        attach_shutdown(child.as_mut(), shutdown);
+    }
+}
+
+/// Share the app's event source cell with a component and its children
+pub fn attach_event_source<T: Component + ?Sized>(c: &mut T, source: &EventSourceCell) {
+    c.get_context_mut().event_source = source.clone();
+
+    for child in c.get_children().values_mut() {
+        attach_event_source(child.as_mut(), source);
+    }
+}
+
+/// Share the app's random number generator with a component and its children
+pub fn attach_rng<T: Component + ?Sized>(c: &mut T, rng: &SharedRng) {
+    c.get_context_mut().shared_rng = rng.clone();
+
+    for child in c.get_children().values_mut() {
+        attach_rng(child.as_mut(), rng);
+    }
+}
+
+/// Save the local state of a component and its children into a cache
+pub fn save_local_state<T: Component + ?Sized>(c: &mut T, cache: &LocalStateCache) {
+    if let Some(key) = c.local_state_key() {
+        if let Some(state) = c.save_local_state() {
+            cache.insert(key, state);
+        }
+    }
+
+    for child in c.get_children().values_mut() {
+        save_local_state(child.as_mut(), cache);
+    }
+}
+
+/// Mark a component and its children mounted, calling `on_mount` for those that weren't
+pub fn mount<T: Component + ?Sized>(c: &mut T) {
+    if !c.get_context().mounted {
+        c.get_context_mut().mounted = true;
+        c.on_mount();
+    }
+
+    for child in c.get_children().values_mut() {
+        mount(child.as_mut());
+    }
+}
+
+/// Mark a root component mounted without calling its `on_mount`, then mount its children
+pub fn mount_root<T: Component + ?Sized>(c: &mut T) {
+    c.get_context_mut().mounted = true;
+
+    for child in c.get_children().values_mut() {
+        mount(child.as_mut());
+    }
+}
+
+/// Mark a component and its children unmounted, calling `on_unmount` for those that were
+/// mounted
+pub fn unmount<T: Component + ?Sized>(c: &mut T) {
+    if c.get_context().mounted {
+        c.get_context_mut().mounted = false;
+        c.on_unmount();
+    }
+
+    for child in c.get_children().values_mut() {
+        unmount(child.as_mut());
+    }
+}
+
+/// Bring a component's children in line with `desired`, keyed by name.
+///
+/// Lets a parent rebuild its list of children, e.g. from data on every update, without
+/// resetting them. A child whose name is in `desired` and whose type matches is kept as is,
+/// state included, and the new instance is dropped; update it through
+/// [`Component::child_mut`] if it needs new data. Other desired children are inserted with
+/// [`Component::child_insert`], wired up like the parent, replacing an existing child of a
+/// different type. Children missing from `desired` are removed with
+/// [`Component::child_remove`], which fires their unmount hooks.
+pub fn reconcile_children<T: Component + ?Sized>(
+    parent: &mut T,
+    desired: Vec<(String, Box<dyn Component>)>,
+) {
+    let stale = parent
+        .get_children()
+        .keys()
+        .filter(|name| !desired.iter().any(|(desired, _)| desired == *name))
+        .cloned()
+        .collect::<Vec<_>>();
+    for name in stale {
+        parent.child_remove(&name);
+    }
+
+    for (name, child) in desired {
+        let same_type = parent
+            .get_children()
+            .get(&name)
+            .is_some_and(|existing| existing.as_any().type_id() == child.as_any().type_id());
+        if !same_type {
+            parent.child_insert(&name, child);
+        }
+    }
+}
+
+/// Capture the state of a component and its children.
+///
+/// The result is an object holding the component's own `state` (or `null`) and its
+/// `children`, keyed by the name they were registered under.
+#[cfg(feature = "serde")]
+pub fn snapshot_tree<T: Component + ?Sized>(c: &mut T) -> serde_json::Value {
+    let state = c.snapshot().unwrap_or_default();
+    let children = c
+        .get_children()
+        .iter_mut()
+        .map(|(name, child)| (name.clone(), snapshot_tree(child.as_mut())))
+        .collect::<serde_json::Map<_, _>>();
+
+    serde_json::json!({ "state": state, "children": children })
+}
+
+/// Restore state captured by [`snapshot_tree`] into a component and its children.
+///
+/// Children missing from the snapshot, or snapshots for children that no longer exist,
+/// are skipped.
+#[cfg(feature = "serde")]
+pub fn restore_tree<T: Component + ?Sized>(c: &mut T, value: &serde_json::Value) {
+    if let Some(state) = value.get("state").filter(|state| !state.is_null()) {
+        c.restore(state);
+    }
+
+    for (name, child) in c.get_children().iter_mut() {
+        if let Some(child_value) = value.get("children").and_then(|children| children.get(name)) {
+            restore_tree(child.as_mut(), child_value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComponentAccessor, ComponentContext};
+    use ratatui::style::Color;
+    use std::time::Duration;
+
+    #[derive(Debug, Default)]
+    struct Input {
+        accepts: bool,
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Input);
+
+    impl Component for Input {
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+        fn accepts_paste(&self) -> bool {
+            self.accepts
+        }
+
+        fn handle_paste_event(&mut self, message: &str) -> Option<Action> {
+            Some(Action::AppAction(message.to_string()))
+        }
+    }
+
+    #[test]
+    fn test_paste_goes_to_focused_accepting_component() {
+        let mut root = Input::default();
+        root.get_children().insert(
+            "field".to_string(),
+            Box::new(Input {
+                accepts: true,
+                ..Default::default()
+            }),
+        );
+
+        assert!(!has_focus(&mut root));
+        assert!(deliver_paste(&mut root, "text").is_empty());
+
+        let mut found = false;
+        focus_by_name(&mut root, "Input", &mut found);
+        assert!(found && root.is_focused());
+        // The root has focus but doesn't accept pastes
+        assert!(deliver_paste(&mut root, "text").is_empty());
+
+        root.set_focused(false);
+        root.get_children().get_mut("field").unwrap().set_focused(true);
+        assert_eq!(
+            deliver_paste(&mut root, "text"),
+            vec![Action::AppAction("text".to_string())]
+        );
+    }
+
+    #[derive(Debug, Default)]
+    struct List {
+        id: String,
+        offset: usize,
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(List);
+
+    impl Component for List {
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+        fn local_state_key(&self) -> Option<String> {
+            Some(self.id.clone())
+        }
+
+        fn save_local_state(&self) -> Option<Box<dyn std::any::Any + Send>> {
+            Some(Box::new(self.offset))
+        }
+
+        fn load_local_state(&mut self, state: Box<dyn std::any::Any + Send>) {
+            if let Ok(offset) = state.downcast::<usize>() {
+                self.offset = *offset;
+            }
+        }
+    }
+
+    fn list(id: &str, offset: usize) -> Box<dyn Component> {
+        Box::new(List {
+            id: id.to_string(),
+            offset,
+            ..Default::default()
+        })
+    }
+
+    fn offset(parent: &mut Input, name: &str) -> usize {
+        parent.child(name).unwrap().downcast_ref::<List>().unwrap().offset
+    }
+
+    #[test]
+    fn test_local_state_survives_child_recreation() {
+        let mut parent = Input::default();
+        parent.child_insert("files", list("files", 5));
+        parent.child_insert("logs", list("logs", 2));
+
+        // Recreating a child restores the state saved under its key
+        parent.child_insert("files", list("files", 0));
+        assert_eq!(offset(&mut parent, "files"), 5);
+
+        // State is only handed to a component with the same key
+        parent.child_remove("logs");
+        parent.child_insert("logs", list("other", 0));
+        assert_eq!(offset(&mut parent, "logs"), 0);
+        assert!(parent.get_context().local_state.contains("logs"));
+    }
+
+    #[derive(Debug, Default)]
+    struct Listener {
+        received: Vec<Message>,
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Listener);
+
+    impl Component for Listener {
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+        fn on_message(&mut self, message: &Message) {
+            self.received.push(message.clone());
+        }
+    }
+
+    #[test]
+    fn test_send_stamps_source_and_reaches_on_message() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut sender = Input::default();
+        sender.register_action_handler(tx);
+        sender.send_message("toggle", "sidebar");
+
+        let Ok(Action::Message(message)) = rx.try_recv() else {
+            panic!("expected a message action");
+        };
+        assert_eq!(
+            message,
+            Message::new("toggle").with_payload("sidebar").with_source("Input")
+        );
+
+        let mut listener = Listener::default();
+        handle_message(&mut listener, &message);
+        assert_eq!(listener.received, vec![message]);
+    }
+
+    #[derive(Debug, Default)]
+    struct Saver {
+        id: &'static str,
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Saver);
+
+    impl Component for Saver {
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+        fn update_with_actions(&mut self, action: &Action) -> Vec<Action> {
+            match action {
+                Action::AppAction(name) if name == "save" => {
+                    vec![Action::AppAction(format!("{}:saved", self.id))]
+                }
+                _ => vec![],
+            }
+        }
+    }
+
+    #[test]
+    fn test_update_collects_follow_ups_parent_first() {
+        let mut parent = Saver {
+            id: "parent",
+            ..Default::default()
+        };
+        for id in ["b", "a"] {
+            parent.child_insert(id, Box::new(Saver { id, ..Default::default() }));
+        }
+
+        // No action handler is registered; follow-ups are returned instead
+        let follow_ups = update(&mut parent, &Action::AppAction("save".to_string()));
+        let names = follow_ups
+            .iter()
+            .map(|action| match action {
+                Action::AppAction(name) => name.as_str(),
+                _ => "",
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["parent:saved", "a:saved", "b:saved"]);
+
+        parent.set_active(false);
+        assert!(update(&mut parent, &Action::AppAction("save".to_string())).is_empty());
+    }
+
+    #[derive(Debug, Default)]
+    struct Marker {
+        symbol: &'static str,
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Marker);
+
+    impl Component for Marker {
+        fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+            f.buffer_mut()
+                .set_string(area.x, area.y, self.symbol, ratatui::style::Style::default());
+        }
+    }
+
+    #[test]
+    fn test_children_without_an_area_are_not_drawn() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let marker = |symbol| Box::new(Marker { symbol, ..Default::default() });
+        let mut parent = Marker {
+            symbol: "p",
+            ..Default::default()
+        };
+        parent.set_area(Rect::new(0, 0, 4, 2));
+        parent.child_insert("placed", marker("a"));
+        parent.child_insert("unplaced", marker("b"));
+        parent.child_mut("placed").unwrap().set_area(Rect::new(2, 1, 2, 1));
+
+        let mut terminal = Terminal::new(TestBackend::new(4, 2)).unwrap();
+        terminal.draw(|f| handle_draw(&mut parent, f, ClipPolicy::Clip)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer[(0, 0)].symbol(), "p");
+        assert_eq!(buffer[(2, 1)].symbol(), "a");
+        assert!(parent.child_mut("unplaced").unwrap().area().is_none());
+    }
+
+    fn draw_overflowing(policy: ClipPolicy) -> ratatui::buffer::Buffer {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut parent = Marker {
+            symbol: "p",
+            ..Default::default()
+        };
+        parent.set_area(Rect::new(0, 0, 6, 1));
+        parent.child_insert("wide", Box::new(Marker { symbol: "wide", ..Default::default() }));
+        parent.child_mut("wide").unwrap().set_area(Rect::new(1, 0, 2, 1));
+
+        let mut terminal = Terminal::new(TestBackend::new(6, 1)).unwrap();
+        terminal.draw(|f| handle_draw(&mut parent, f, policy)).unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    #[test]
+    fn test_drawing_outside_the_area_is_clipped() {
+        let symbols = |buffer: &ratatui::buffer::Buffer| {
+            buffer.content.iter().map(|cell| cell.symbol().to_string()).collect::<String>()
+        };
+
+        assert_eq!(symbols(&draw_overflowing(ClipPolicy::Clip)), "pwi   ");
+        assert_eq!(symbols(&draw_overflowing(ClipPolicy::Off)), "pwide ");
+    }
+
+    #[test]
+    #[should_panic(expected = "Component 'Marker' drew outside its area")]
+    fn test_strict_clipping_panics_on_overflow() {
+        draw_overflowing(ClipPolicy::Strict);
+    }
+
+    #[derive(Debug, Default)]
+    struct Ticker {
+        keys: usize,
+        ticks: usize,
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Ticker);
+
+    impl Component for Ticker {
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+        fn subscribed_events(&self) -> EventMask {
+            EventMask::TICK
+        }
+
+        fn handle_key_events(&mut self, _key: KeyEvent) -> Option<Action> {
+            self.keys += 1;
+            None
+        }
+
+        fn handle_tick_event(&mut self, _count: u64, _elapsed: Duration) -> Option<Action> {
+            self.ticks += 1;
+            None
+        }
+    }
+
+    #[test]
+    fn test_unsubscribed_handlers_are_skipped() {
+        let mut parent = Ticker::default();
+        parent.child_insert("input", Box::new(Input::default()));
+
+        let key = Some(Event::Key(KeyEvent::from(crossterm::event::KeyCode::Char('a'))));
+        let tick = Some(Event::Tick { count: 1, elapsed: Duration::ZERO });
+        handle_event_for(&mut parent, &key);
+        handle_event_for(&mut parent, &tick);
+        assert_eq!((parent.keys, parent.ticks), (0, 1));
+
+        // Children subscribe on their own
+        let paste = Some(Event::Paste("hi".to_string()));
+        let actions = handle_event_for(&mut parent, &paste);
+        assert_eq!(actions, [Action::AppAction("hi".to_string())]);
+    }
+
+    #[derive(Debug, Default)]
+    struct Clicks {
+        clicks: Vec<Option<Position>>,
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Clicks);
+
+    impl Component for Clicks {
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+        fn handle_local_mouse_events(&mut self, mouse: LocalMouseEvent) -> Option<Action> {
+            self.clicks.push(mouse.position());
+            None
+        }
+    }
+
+    #[test]
+    fn test_mouse_events_are_translated_to_the_component_area() {
+        use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+        let click = |column, row| {
+            Some(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            }))
+        };
+        let mut clicks = Clicks::default();
+        clicks.set_area(Rect::new(5, 2, 10, 3));
+
+        handle_event_for(&mut clicks, &click(5, 2));
+        handle_event_for(&mut clicks, &click(7, 4));
+        handle_event_for(&mut clicks, &click(0, 0));
+        assert_eq!(clicks.clicks, [Some(Position::new(0, 0)), Some(Position::new(2, 2)), None]);
+    }
+
+    #[derive(Debug, Default)]
+    struct Layout {
+        layouts: usize,
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Layout);
+
+    impl Component for Layout {
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {
+            if self.area_changed() {
+                self.layouts += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_area_changed_tracks_the_last_drawn_area() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut layout = Layout::default();
+        let mut terminal = Terminal::new(TestBackend::new(4, 2)).unwrap();
+        let mut draw = |layout: &mut Layout, area| {
+            layout.set_area(area);
+            terminal.draw(|f| handle_draw(layout, f, ClipPolicy::Off)).unwrap();
+        };
+
+        draw(&mut layout, Rect::new(0, 0, 4, 2));
+        draw(&mut layout, Rect::new(0, 0, 4, 2));
+        assert_eq!(layout.layouts, 1);
+        draw(&mut layout, Rect::new(0, 0, 2, 2));
+        assert_eq!(layout.layouts, 2);
+        assert!(!layout.area_changed());
+    }
+
+    #[derive(Debug, Default)]
+    struct Recorder {
+        order: DispatchOrder,
+        log: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Recorder);
+
+    impl Component for Recorder {
+        fn init(&mut self, _area: Rect) {
+            self.log.lock().unwrap().push(format!("init {}", self.name()));
+        }
+
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+        fn on_message(&mut self, _message: &Message) {
+            self.log.lock().unwrap().push(self.name());
+        }
+
+        fn message_order(&self) -> DispatchOrder {
+            self.order
+        }
+
+        fn on_mount(&mut self) {
+            self.log.lock().unwrap().push(format!("mount {}", self.name()));
+        }
+
+        fn on_unmount(&mut self) {
+            self.log.lock().unwrap().push(format!("unmount {}", self.name()));
+        }
+
+        fn handle_tick_event(&mut self, count: u64, elapsed: Duration) -> Option<Action> {
+            let tick = format!("tick {} after {:?}", count, elapsed);
+            self.log.lock().unwrap().push(tick.clone());
+            Some(Action::AppAction(tick))
+        }
+    }
+
+    #[test]
+    fn test_ticks_carry_their_count_and_elapsed_time() {
+        let log = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut parent = Recorder { log: log.clone(), ..Default::default() };
+        parent.child_insert("child", Box::new(Recorder { log: log.clone(), ..Default::default() }));
+
+        let tick = Event::Tick { count: 3, elapsed: Duration::from_millis(1500) };
+        let actions = handle_event_for(&mut parent, &Some(tick));
+        assert_eq!(*log.lock().unwrap(), ["tick 3 after 1.5s", "tick 3 after 1.5s"]);
+        assert_eq!(actions, vec![Action::AppAction("tick 3 after 1.5s".to_string()); 2]);
+    }
+
+    #[test]
+    fn test_bottom_up_components_get_messages_after_children() {
+        let log = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder = |name: &str, order| {
+            let mut recorder = Recorder {
+                order,
+                log: log.clone(),
+                ..Default::default()
+            };
+            recorder._ctx.name = Some(name.to_string());
+            recorder
+        };
+
+        let mut middle = recorder("middle", DispatchOrder::TopDown);
+        middle.child_insert("leaf", Box::new(recorder("leaf", DispatchOrder::TopDown)));
+        let mut root = recorder("root", DispatchOrder::BottomUp);
+        root.child_insert("middle", Box::new(middle));
+        log.lock().unwrap().clear();
+
+        handle_message(&mut root, &Message::new("refresh"));
+        assert_eq!(*log.lock().unwrap(), ["middle", "leaf", "root"]);
+    }
+
+    #[test]
+    fn test_reconcile_keeps_matching_children() {
+        let log = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut parent = Input::default();
+        mount_root(&mut parent);
+        parent.child_insert("files", list("files", 5));
+        parent.child_insert("logs", list("logs", 2));
+        let mut stale = Recorder {
+            log: log.clone(),
+            ..Default::default()
+        };
+        let nested = Recorder {
+            log: log.clone(),
+            ..Default::default()
+        };
+        stale.child_insert("nested", Box::new(nested));
+        parent.child_insert("stale", Box::new(stale));
+        // Inserting a child mounts its whole subtree, once
+        assert_eq!(*log.lock().unwrap(), ["mount Recorder", "mount Recorder"]);
+        log.lock().unwrap().clear();
+
+        reconcile_children(
+            &mut parent,
+            vec![
+                ("files".to_string(), list("files", 0)),
+                ("logs".to_string(), Box::new(Input::default())),
+                ("new".to_string(), list("new", 1)),
+            ],
+        );
+
+        let names = parent.get_children().keys().cloned().collect::<Vec<_>>();
+        assert_eq!(names, ["files", "logs", "new"]);
+        assert_eq!(offset(&mut parent, "files"), 5);
+        assert_eq!(offset(&mut parent, "new"), 1);
+        assert!(parent.child("logs").unwrap().is::<Input>());
+        assert_eq!(*log.lock().unwrap(), ["unmount Recorder", "unmount Recorder"]);
+    }
+
+    #[test]
+    fn test_prebuilt_subtrees_mount_once_when_they_join() {
+        let log = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder = |name: &str| {
+            let mut recorder = Recorder {
+                log: log.clone(),
+                ..Default::default()
+            };
+            recorder._ctx.name = Some(name.to_string());
+            recorder
+        };
+
+        // Nothing is mounted until the subtree joins a mounted parent
+        let mut mid = recorder("mid");
+        mid.child_insert("leaf", Box::new(recorder("leaf")));
+        assert!(log.lock().unwrap().is_empty());
+
+        let mut root = recorder("root");
+        mount_root(&mut root);
+        root.child_insert("mid", Box::new(mid));
+        assert_eq!(*log.lock().unwrap(), ["mount mid", "mount leaf"]);
+
+        // Adding to a mounted subtree mounts only the new child
+        log.lock().unwrap().clear();
+        root.child_mut("mid").unwrap().child_insert("other", Box::new(recorder("other")));
+        assert_eq!(*log.lock().unwrap(), ["mount other"]);
+
+        log.lock().unwrap().clear();
+        let mut mid = root.child_remove("mid").unwrap();
+        assert_eq!(*log.lock().unwrap(), ["unmount mid", "unmount leaf", "unmount other"]);
+
+        // A detached subtree can be rearranged without firing hooks
+        log.lock().unwrap().clear();
+        mid.child_remove("leaf");
+        assert!(log.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_clear_children_unmounts_every_child() {
+        let log = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut parent = Input::default();
+        mount_root(&mut parent);
+        for name in ["b", "a"] {
+            let mut child = Recorder {
+                log: log.clone(),
+                ..Default::default()
+            };
+            child._ctx.name = Some(name.to_string());
+            parent.child_insert(name, Box::new(child));
+        }
+        assert_eq!(parent.child_names(), ["a", "b"]);
+        assert_eq!(parent.children_len(), 2);
+
+        let mut visited = Vec::new();
+        parent.for_each_child(&mut |child| visited.push(child.name()));
+        assert_eq!(visited, ["a", "b"]);
+
+        log.lock().unwrap().clear();
+        parent.clear_children();
+        assert_eq!(parent.children_len(), 0);
+        assert_eq!(*log.lock().unwrap(), ["unmount a", "unmount b"]);
+    }
+
+    #[test]
+    fn test_children_added_after_init_are_initialized_on_draw() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let log = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder = |name: &str| {
+            let mut recorder = Recorder {
+                log: log.clone(),
+                ..Default::default()
+            };
+            recorder._ctx.name = Some(name.to_string());
+            recorder
+        };
+        let area = Rect::new(0, 0, 4, 2);
+        let mut root = recorder("root");
+        root.set_area(area);
+        root.child_insert("first", Box::new(recorder("first")));
+        init(&mut root, area);
+        // Initialized components are skipped, so a second pass is a no-op
+        init(&mut root, area);
+        root.child_insert("late", Box::new(recorder("late")));
+        for name in ["first", "late"] {
+            root.child_mut(name).unwrap().set_area(area);
+        }
+        log.lock().unwrap().retain(|entry| entry.starts_with("init"));
+        assert_eq!(*log.lock().unwrap(), ["init root", "init first"]);
+
+        let mut terminal = Terminal::new(TestBackend::new(4, 2)).unwrap();
+        log.lock().unwrap().clear();
+        terminal.draw(|f| handle_draw(&mut root, f, ClipPolicy::Off)).unwrap();
+        assert_eq!(*log.lock().unwrap(), ["init late"]);
+
+        log.lock().unwrap().clear();
+        root.reinit();
+        terminal.draw(|f| handle_draw(&mut root, f, ClipPolicy::Off)).unwrap();
+        assert_eq!(*log.lock().unwrap(), ["init root", "init first", "init late"]);
+    }
+
+    #[derive(Debug, Default)]
+    struct Broken {
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Broken);
+
+    impl Component for Broken {
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+        fn try_draw(
+            &mut self,
+            f: &mut Frame<'_>,
+            area: Rect,
+            _theme: &ThemeManager,
+        ) -> anyhow::Result<()> {
+            f.buffer_mut().set_string(area.x, area.y, "half", Style::default());
+            anyhow::bail!("missing logo.png")
+        }
+    }
+
+    #[test]
+    fn test_render_errors_are_shown_in_the_area() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut parent = Broken::default();
+        parent.set_area(Rect::new(0, 0, 20, 4));
+        parent.child_insert("marker", Box::new(Marker { symbol: "m", ..Default::default() }));
+        parent.child_mut("marker").unwrap().set_area(Rect::new(19, 3, 1, 1));
+
+        let mut terminal = Terminal::new(TestBackend::new(20, 4)).unwrap();
+        terminal.draw(|f| handle_draw(&mut parent, f, ClipPolicy::Clip)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y| (0..20).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        assert!(row(0).contains("render error"), "{}", row(0));
+        assert!(row(1).contains("missing logo.png"), "{}", row(1));
+        assert_eq!(buffer[(1, 1)].fg, Color::Red);
+        assert_eq!(buffer[(19, 3)].symbol(), "m");
+    }
+
+    #[test]
+    fn test_render_errors_are_clipped_to_the_frame() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut broken = Broken::default();
+        broken.set_area(Rect::new(5, 0, 10, 3));
+
+        let mut terminal = Terminal::new(TestBackend::new(10, 3)).unwrap();
+        terminal.draw(|f| handle_draw(&mut broken, f, ClipPolicy::Off)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer[(5, 0)].fg, Color::Red);
+        assert_eq!(buffer[(4, 0)].symbol(), " ");
+    }
+
+    #[derive(Debug, Default)]
+    struct Themed {
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Themed);
+
+    impl Component for Themed {
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+
+        fn draw_themed(&mut self, f: &mut Frame<'_>, area: Rect, theme: &ThemeManager) {
+            let style = theme.get_current_style("themed");
+            f.buffer_mut().set_string(area.x, area.y, "t", style);
+        }
+    }
+
+    #[test]
+    fn test_draw_themed_gets_the_theme_passed_in() {
+        use crate::theme::Theme;
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut theme = ThemeManager::new();
+        theme.add_theme(Theme::new("dark").add_style("themed", Style::default().fg(Color::Blue)));
+        theme.set_active_theme("dark");
+
+        // The component was never given a theme of its own
+        let mut themed = Themed::default();
+        themed.set_area(Rect::new(0, 0, 1, 1));
+        let mut terminal = Terminal::new(TestBackend::new(1, 1)).unwrap();
+        terminal
+            .draw(|f| handle_draw_themed(&mut themed, f, ClipPolicy::Clip, &theme))
+            .unwrap();
+        assert_eq!(terminal.backend().buffer()[(0, 0)].fg, Color::Blue);
+
+        terminal.draw(|f| handle_draw(&mut themed, f, ClipPolicy::Clip)).unwrap();
+        assert_eq!(terminal.backend().buffer()[(0, 0)].fg, Color::Reset);
+    }
+
+    #[derive(Debug, Default)]
+    struct Static {
+        draws: usize,
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Static);
+
+    impl Component for Static {
+        fn cache_render(&self) -> bool {
+            true
+        }
+
+        fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+            self.draws += 1;
+            f.buffer_mut().set_string(area.x, area.y, "help", Style::default());
+        }
+    }
+
+    #[test]
+    fn test_cached_components_are_redrawn_only_when_invalidated() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut panel = Static::default();
+        let mut terminal = Terminal::new(TestBackend::new(6, 2)).unwrap();
+        let mut draw = |panel: &mut Static, area| {
+            panel.set_area(area);
+            terminal.draw(|f| handle_draw(panel, f, ClipPolicy::Clip)).unwrap();
+            terminal.backend().buffer().clone()
+        };
+
+        let area = Rect::new(0, 0, 6, 2);
+        draw(&mut panel, area);
+        let buffer = draw(&mut panel, area);
+        assert_eq!(panel.draws, 1);
+        assert_eq!(buffer[(0, 0)].symbol(), "h");
+
+        panel.mark_dirty();
+        draw(&mut panel, area);
+        assert_eq!(panel.draws, 2);
+        draw(&mut panel, Rect::new(1, 1, 5, 1));
+        assert_eq!(panel.draws, 3);
+        handle_theme(&mut panel, &ThemeManager::default());
+        draw(&mut panel, Rect::new(1, 1, 5, 1));
+        assert_eq!(panel.draws, 4);
+    }
+}
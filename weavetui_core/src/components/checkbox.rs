@@ -0,0 +1,141 @@
+//! Checkbox component for boolean form inputs.
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    widgets::Paragraph,
+    Frame,
+};
+
+use super::FormValue;
+use crate::{
+    event::{Action, Message},
+    theme::ColorRole,
+    Component, ComponentAccessor, ComponentContext,
+};
+
+/// A labelled on/off input drawn as `[x] label` or `[ ] label`.
+///
+/// While focused, space or enter toggles it and sends an `Action::Message` with the
+/// checkbox's event as the topic and the new state, `true` or `false`, as the payload.
+/// Styled with the `checkbox` theme style, patched with `checkbox.focused` while focused
+/// (reversed by default).
+#[derive(Debug)]
+pub struct Checkbox {
+    label: String,
+    checked: bool,
+    event: String,
+    pub _ctx: ComponentContext,
+}
+
+impl_component_accessor!(Checkbox);
+
+impl Checkbox {
+    /// Create an unchecked checkbox that emits `checkbox:changed` when toggled
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            checked: false,
+            event: "checkbox:changed".to_string(),
+            _ctx: ComponentContext::default(),
+        }
+    }
+
+    /// Set the initial state (chainable)
+    pub fn with_checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    /// Set the topic of the message sent when the checkbox is toggled (chainable)
+    pub fn with_event(mut self, event: &str) -> Self {
+        self.event = event.to_string();
+        self
+    }
+
+    /// Check if the checkbox is checked
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    /// Set the state without emitting an event
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+    }
+
+    /// Get the label
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Flip the state and return the change event
+    pub fn toggle(&mut self) -> Action {
+        self.checked = !self.checked;
+        Action::Message(
+            Message::new(&self.event)
+                .with_payload(&self.checked.to_string())
+                .with_source(&self.name()),
+        )
+    }
+}
+
+impl Component for Checkbox {
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn form_value(&self) -> Option<FormValue> {
+        Some(FormValue::Bool(self.checked))
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let style = if self.is_focused() {
+            self.get_theme_manager()
+                .highlight(ColorRole::Focus)
+                .patch(self.compose_style(&["checkbox", "checkbox.focused"]))
+        } else {
+            self.get_style("checkbox")
+        };
+
+        let mark = if self.checked { "x" } else { " " };
+        f.render_widget(
+            Paragraph::new(format!("[{}] {}", mark, self.label)).style(style),
+            area,
+        );
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        if !self.is_focused() {
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Char(' ') | KeyCode::Enter => Some(self.toggle()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggles_only_while_focused() {
+        let mut checkbox = Checkbox::new("Wrap lines").with_event("settings:wrap");
+        let space = KeyEvent::from(KeyCode::Char(' '));
+
+        assert_eq!(checkbox.handle_key_events(space), None);
+        assert!(!checkbox.is_checked());
+
+        checkbox.set_focused(true);
+        let changed = Message::new("settings:wrap")
+            .with_payload("true")
+            .with_source("Checkbox");
+        assert_eq!(checkbox.handle_key_events(space), Some(Action::Message(changed)));
+        assert!(checkbox.is_checked());
+
+        checkbox.handle_key_events(KeyEvent::from(KeyCode::Enter));
+        assert!(!checkbox.is_checked());
+    }
+}
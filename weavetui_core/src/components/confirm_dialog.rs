@@ -0,0 +1,262 @@
+//! Yes/no confirmation dialog.
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Margin, Rect},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    component_manager::BackdropStyle, event::Action, geometry::centered_rect, theme::ColorRole,
+    Component, ComponentAccessor, ComponentContext,
+};
+
+/// A centered dialog asking a yes/no question, e.g. "Quit without saving?".
+///
+/// Add it as the last root component so it's drawn on top of the rest. While open, `y`
+/// confirms, `n` or esc cancels, left, right and tab move between the buttons and enter
+/// presses the selected one. Deciding sends the confirm or cancel action and closes the
+/// dialog; [`ConfirmDialog::open`] shows it again. Other components still see key presses,
+/// so focus the dialog to keep focus-gated components from reacting to them.
+///
+/// Styled with the `dialog` theme style; buttons use `dialog.button`, patched with
+/// `dialog.button.selected` (reversed by default) for the selected one. Use
+/// [`ConfirmDialog::with_backdrop`] to dim the content behind the dialog.
+#[derive(Debug)]
+pub struct ConfirmDialog {
+    message: String,
+    confirm: Action,
+    cancel: Action,
+    labels: (String, String),
+    confirm_selected: bool,
+    backdrop: Option<BackdropStyle>,
+    pub _ctx: ComponentContext,
+}
+
+impl_component_accessor!(ConfirmDialog);
+
+impl ConfirmDialog {
+    /// Create an open dialog sending `confirm:yes` or `confirm:no`, with "Yes" selected
+    pub fn new(message: &str) -> Self {
+        Self {
+            message: message.to_string(),
+            confirm: Action::AppAction("confirm:yes".to_string()),
+            cancel: Action::AppAction("confirm:no".to_string()),
+            labels: ("Yes".to_string(), "No".to_string()),
+            confirm_selected: true,
+            backdrop: None,
+            _ctx: ComponentContext::default(),
+        }
+    }
+
+    /// Set the action sent when the user confirms (chainable)
+    pub fn on_confirm(mut self, action: Action) -> Self {
+        self.confirm = action;
+        self
+    }
+
+    /// Set the action sent when the user cancels (chainable)
+    pub fn on_cancel(mut self, action: Action) -> Self {
+        self.cancel = action;
+        self
+    }
+
+    /// Set the button labels (chainable)
+    pub fn with_labels(mut self, confirm: &str, cancel: &str) -> Self {
+        self.labels = (confirm.to_string(), cancel.to_string());
+        self
+    }
+
+    /// Cover the content behind the dialog while it's open (chainable)
+    pub fn with_backdrop(mut self, backdrop: BackdropStyle) -> Self {
+        self.backdrop = Some(backdrop);
+        self
+    }
+
+    /// Start out closed, to be shown later with [`ConfirmDialog::open`] (chainable)
+    pub fn closed(mut self) -> Self {
+        self._ctx.active = false;
+        self
+    }
+
+    /// Show the dialog with the confirm button selected
+    pub fn open(&mut self) {
+        self.confirm_selected = true;
+        self.set_active(true);
+    }
+
+    /// Hide the dialog without sending anything
+    pub fn close(&mut self) {
+        self.set_active(false);
+    }
+
+    /// Check if the dialog is shown
+    pub fn is_open(&self) -> bool {
+        self.is_active()
+    }
+
+    /// Get the message
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Close the dialog and return the action for the decision
+    fn decide(&mut self, confirmed: bool) -> Action {
+        self.close();
+        if confirmed {
+            self.confirm.clone()
+        } else {
+            self.cancel.clone()
+        }
+    }
+
+    fn button(&self, label: &str, selected: bool) -> Span<'static> {
+        let style = if selected {
+            self.get_theme_manager()
+                .highlight(ColorRole::Selection)
+                .patch(self.compose_style(&["dialog.button", "dialog.button.selected"]))
+        } else {
+            self.get_style("dialog.button")
+        };
+        Span::styled(format!(" {} ", label), style)
+    }
+}
+
+impl Component for ConfirmDialog {
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn backdrop(&self) -> Option<BackdropStyle> {
+        self.backdrop
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let (confirm, cancel) = &self.labels;
+        let buttons = Line::from(vec![
+            self.button(confirm, self.confirm_selected),
+            Span::raw("   "),
+            self.button(cancel, !self.confirm_selected),
+        ]);
+
+        // Wrapped line count, assuming one cell per character
+        let min_width = buttons.width() as u16 + 4;
+        let width = (self.message.chars().count() as u16 + 4).clamp(min_width, 60);
+        let inner_width = width.saturating_sub(4).max(1) as usize;
+        let lines = self
+            .message
+            .lines()
+            .map(|line| line.chars().count().div_ceil(inner_width).max(1))
+            .sum::<usize>()
+            .max(1) as u16;
+        let rect = centered_rect(width, lines + 4, area);
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .style(self.get_style("dialog"));
+        let inner = block.inner(rect).inner(Margin::new(1, 0));
+        f.render_widget(Clear, rect);
+        f.render_widget(block, rect);
+
+        let [message, _, buttons_row] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+        f.render_widget(
+            Paragraph::new(self.message.as_str())
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            message,
+        );
+        f.render_widget(Paragraph::new(buttons).alignment(Alignment::Center), buttons_row);
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => Some(self.decide(true)),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => Some(self.decide(false)),
+            KeyCode::Enter => Some(self.decide(self.confirm_selected)),
+            KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab => {
+                self.confirm_selected = !self.confirm_selected;
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::{Modifier, Style};
+
+    #[test]
+    fn test_decisions_send_actions_and_close() {
+        let mut dialog = ConfirmDialog::new("Quit without saving?")
+            .on_confirm(Action::Quit)
+            .on_cancel(Action::AppAction("quit:cancelled".to_string()));
+        let press =
+            |dialog: &mut ConfirmDialog, code| dialog.handle_key_events(KeyEvent::from(code));
+
+        assert_eq!(press(&mut dialog, KeyCode::Char('y')), Some(Action::Quit));
+        assert!(!dialog.is_open());
+
+        dialog.open();
+        assert_eq!(press(&mut dialog, KeyCode::Right), None);
+        assert_eq!(
+            press(&mut dialog, KeyCode::Enter),
+            Some(Action::AppAction("quit:cancelled".to_string()))
+        );
+
+        // Reopening selects the confirm button again
+        dialog.open();
+        assert_eq!(press(&mut dialog, KeyCode::Enter), Some(Action::Quit));
+
+        dialog.open();
+        assert_eq!(
+            press(&mut dialog, KeyCode::Esc),
+            Some(Action::AppAction("quit:cancelled".to_string()))
+        );
+        assert!(!ConfirmDialog::new("?").closed().is_open());
+    }
+
+    #[test]
+    fn test_draws_centered_over_the_area() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut dialog = ConfirmDialog::new("Delete?");
+        let mut terminal = Terminal::new(TestBackend::new(30, 9)).unwrap();
+        terminal.draw(|f| dialog.draw(f, f.area())).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y| (0..30).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        assert!(row(3).contains("Delete?"));
+        assert!(row(5).contains(" Yes     No "));
+    }
+
+    #[test]
+    fn test_backdrop_dims_content_behind_the_dialog() {
+        use crate::component_manager::{handle_draw, ClipPolicy};
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut dialog = ConfirmDialog::new("Delete?").with_backdrop(BackdropStyle::Dim);
+        dialog.set_area(Rect::new(0, 0, 30, 9));
+        let mut terminal = Terminal::new(TestBackend::new(30, 9)).unwrap();
+        terminal
+            .draw(|f| {
+                f.buffer_mut().set_string(0, 0, "behind", Style::default());
+                handle_draw(&mut dialog, f, ClipPolicy::Clip);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer[(0, 0)].symbol(), "b");
+        assert!(buffer[(0, 0)].modifier.contains(Modifier::DIM));
+        // The dialog itself is cleared before drawing, so it isn't dimmed
+        assert!(!buffer[(15, 3)].modifier.contains(Modifier::DIM));
+    }
+}
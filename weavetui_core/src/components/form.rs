@@ -0,0 +1,383 @@
+//! Form container coordinating several named input fields.
+
+use std::{collections::HashMap, fmt};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::{
+    event::Action, keyboard::parse_key_sequence, theme::ColorRole, Component, ComponentAccessor,
+    ComponentContext,
+};
+
+/// The value of a form field, as reported by [`Component::form_value`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FormValue {
+    /// Free text, e.g. from a text input
+    Text(String),
+    /// An on/off state, e.g. from a [`Checkbox`](crate::components::Checkbox)
+    Bool(bool),
+    /// The chosen option, if any, e.g. from a [`RadioGroup`](crate::components::RadioGroup)
+    Choice(Option<String>),
+}
+
+/// Sent by a [`Form`] as `Action::Custom` when it's submitted with valid values
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormSubmission {
+    /// Name of the submitting form, to tell several forms apart
+    pub form: String,
+    /// The value of every field, by field name
+    pub values: HashMap<String, FormValue>,
+}
+
+type Validator = Box<dyn Fn(&FormValue) -> Result<(), String>>;
+
+struct Field {
+    name: String,
+    label: String,
+    height: u16,
+    validator: Option<Validator>,
+}
+
+impl fmt::Debug for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Field")
+            .field("name", &self.name)
+            .field("label", &self.label)
+            .field("height", &self.height)
+            .field("validator", &self.validator.as_ref().map(|_| "<validator>"))
+            .finish()
+    }
+}
+
+/// A column of labelled fields, each a child component like a
+/// [`Checkbox`](crate::components::Checkbox) or [`RadioGroup`](crate::components::RadioGroup).
+///
+/// Focus the form itself, not its fields. While it's focused, tab and shift-tab move between
+/// fields and the form passes its focus on to the current field, which handles the other
+/// keys. The submit key (ctrl-s by default) validates every field and, if all are valid,
+/// sends a [`FormSubmission`] as an `Action::Custom`. Otherwise the errors are shown next to
+/// the invalid fields and the first of them gets focus.
+///
+/// Field values come from [`Component::form_value`], so any component implementing it can
+/// be a field. Labels are styled with `form.label` and errors with `form.error` (red by
+/// default).
+#[derive(Debug)]
+pub struct Form {
+    fields: Vec<Field>,
+    errors: HashMap<String, String>,
+    current: usize,
+    submit_key: KeyEvent,
+    pub _ctx: ComponentContext,
+}
+
+impl_component_accessor!(Form);
+
+impl Default for Form {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Form {
+    /// Create an empty form submitted with ctrl-s
+    pub fn new() -> Self {
+        Self {
+            fields: Vec::new(),
+            errors: HashMap::new(),
+            current: 0,
+            submit_key: KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL),
+            _ctx: ComponentContext::default(),
+        }
+    }
+
+    /// Add a field below the existing ones (chainable).
+    ///
+    /// The field is one row high, or one row per option for a `RadioGroup`; use
+    /// [`Form::with_field_height`] for anything taller. Adding a field under an existing
+    /// name replaces it.
+    pub fn with_field(mut self, name: &str, label: &str, component: Box<dyn Component>) -> Self {
+        let height = component
+            .downcast_ref::<super::RadioGroup>()
+            .map_or(1, |group| group.options().len().max(1) as u16);
+
+        self.fields.retain(|field| field.name != name);
+        self.fields.push(Field {
+            name: name.to_string(),
+            label: label.to_string(),
+            height,
+            validator: None,
+        });
+        self.child_insert(name, component);
+        self
+    }
+
+    /// Set how many rows a field takes (chainable)
+    pub fn with_field_height(mut self, name: &str, height: u16) -> Self {
+        if let Some(field) = self.field_mut(name) {
+            field.height = height;
+        }
+        self
+    }
+
+    /// Check a field's value on submit, returning the error to show if it's invalid (chainable)
+    pub fn with_validator(
+        mut self,
+        name: &str,
+        validator: impl Fn(&FormValue) -> Result<(), String> + 'static,
+    ) -> Self {
+        if let Some(field) = self.field_mut(name) {
+            field.validator = Some(Box::new(validator));
+        }
+        self
+    }
+
+    /// Set the key that submits the form (chainable).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` isn't a single valid key, e.g. `"<ctrl-enter>"`.
+    pub fn with_submit_key(mut self, key: &str) -> Self {
+        let keys = parse_key_sequence(key).unwrap_or_else(|err| panic!("{}", err));
+        let [key] = keys[..] else {
+            panic!("submit key \"{}\" must be a single key", key);
+        };
+        self.submit_key = key;
+        self
+    }
+
+    /// Get the current value of every field that reports one, by field name
+    pub fn values(&self) -> HashMap<String, FormValue> {
+        self.fields
+            .iter()
+            .filter_map(|field| {
+                let value = self._ctx.children.get(&field.name)?.form_value()?;
+                Some((field.name.clone(), value))
+            })
+            .collect()
+    }
+
+    /// Show an error next to a field, e.g. one reported by a server
+    pub fn set_error(&mut self, field: &str, message: &str) {
+        self.errors.insert(field.to_string(), message.to_string());
+    }
+
+    /// Remove the error shown next to a field
+    pub fn clear_error(&mut self, field: &str) {
+        self.errors.remove(field);
+    }
+
+    /// Get the error shown next to a field
+    pub fn error(&self, field: &str) -> Option<&str> {
+        self.errors.get(field).map(String::as_str)
+    }
+
+    /// Get the name of the field that has focus while the form is focused
+    pub fn current_field(&self) -> Option<&str> {
+        self.fields.get(self.current).map(|field| field.name.as_str())
+    }
+
+    /// Run every validator, replacing the shown errors, and return whether all fields are valid
+    pub fn validate(&mut self) -> bool {
+        let values = self.values();
+        self.errors.clear();
+        for field in &self.fields {
+            let (Some(validator), Some(value)) = (&field.validator, values.get(&field.name))
+            else {
+                continue;
+            };
+            if let Err(message) = validator(value) {
+                self.errors.insert(field.name.clone(), message);
+            }
+        }
+        self.errors.is_empty()
+    }
+
+    /// Validate the form and return the submission action, or move to the first invalid field
+    pub fn submit(&mut self) -> Option<Action> {
+        if self.validate() {
+            return Some(Action::custom(FormSubmission {
+                form: self.name(),
+                values: self.values(),
+            }));
+        }
+
+        if let Some(index) = self
+            .fields
+            .iter()
+            .position(|field| self.errors.contains_key(&field.name))
+        {
+            self.current = index;
+            self.sync_focus();
+        }
+        None
+    }
+
+    fn field_mut(&mut self, name: &str) -> Option<&mut Field> {
+        self.fields.iter_mut().find(|field| field.name == name)
+    }
+
+    /// Move to the next or previous field, wrapping around
+    fn step(&mut self, forward: bool) {
+        let len = self.fields.len();
+        if len > 0 {
+            self.current = if forward {
+                (self.current + 1) % len
+            } else {
+                (self.current + len - 1) % len
+            };
+            self.sync_focus();
+        }
+    }
+
+    /// Focus the current field while the form is focused, and no field otherwise
+    fn sync_focus(&mut self) {
+        let focused = self.is_focused();
+        for (index, field) in self.fields.iter().enumerate() {
+            if let Some(child) = self._ctx.children.get_mut(&field.name) {
+                child.set_focused(focused && index == self.current);
+            }
+        }
+    }
+}
+
+impl Component for Form {
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        self.sync_focus();
+
+        let label_width = self
+            .fields
+            .iter()
+            .map(|field| field.label.chars().count() as u16)
+            .max()
+            .map_or(0, |width| width + 1)
+            .min(area.width);
+        let label_style = self.get_style("form.label");
+        let error_style = Style::default()
+            .fg(self.get_theme_manager().get_current_role(ColorRole::Error))
+            .patch(self.get_style("form.error"));
+
+        let mut y = area.y;
+        for field in &self.fields {
+            let height = field.height.min(area.bottom().saturating_sub(y));
+            let row = Rect::new(area.x, y, area.width, height);
+            y += height;
+
+            let label = Rect { width: label_width, ..row };
+            f.render_widget(Paragraph::new(field.label.as_str()).style(label_style), label);
+
+            let mut input = Rect {
+                x: row.x + label_width,
+                width: row.width - label_width,
+                ..row
+            };
+            if let Some(error) = self.errors.get(&field.name) {
+                let width = (error.chars().count() as u16 + 1).min(input.width.saturating_sub(1));
+                input.width -= width;
+                let error_area = Rect {
+                    x: input.right() + 1,
+                    width: width.saturating_sub(1),
+                    height: height.min(1),
+                    ..input
+                };
+                f.render_widget(Paragraph::new(error.as_str()).style(error_style), error_area);
+            }
+
+            if let Some(child) = self._ctx.children.get_mut(&field.name) {
+                child.set_area(input);
+            }
+        }
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        if !self.is_focused() {
+            self.sync_focus();
+            return None;
+        }
+
+        if key == self.submit_key {
+            return self.submit();
+        }
+        match key.code {
+            KeyCode::Tab => self.step(true),
+            KeyCode::BackTab => self.step(false),
+            _ => self.sync_focus(),
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Checkbox, RadioGroup};
+
+    fn form() -> Form {
+        Form::new()
+            .with_field("terms", "Accept terms", Box::new(Checkbox::new("I agree")))
+            .with_field("plan", "Plan", Box::new(RadioGroup::new(&["Free", "Pro"])))
+            .with_validator("terms", |value| match value {
+                FormValue::Bool(true) => Ok(()),
+                _ => Err("required".to_string()),
+            })
+    }
+
+    fn press(form: &mut Form, key: KeyEvent) -> Option<Action> {
+        crate::component_manager::handle_event_for(form, &Some(crate::event::Event::Key(key)))
+            .pop()
+    }
+
+    #[test]
+    fn test_submit_validates_and_collects_values() {
+        let mut form = form();
+        form.set_focused(true);
+        let submit = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+
+        // Move to the plan, pick "Pro", then submit with the terms unchecked
+        press(&mut form, KeyEvent::from(KeyCode::Tab));
+        assert_eq!(form.current_field(), Some("plan"));
+        press(&mut form, KeyEvent::from(KeyCode::Down));
+        press(&mut form, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(press(&mut form, submit), None);
+        assert_eq!(form.error("terms"), Some("required"));
+        assert_eq!(form.current_field(), Some("terms"));
+
+        press(&mut form, KeyEvent::from(KeyCode::Char(' ')));
+        let action = press(&mut form, submit).unwrap();
+        let submission = action.as_custom::<FormSubmission>().unwrap();
+        assert_eq!(submission.form, "Form");
+        assert_eq!(
+            submission.values,
+            HashMap::from([
+                ("terms".to_string(), FormValue::Bool(true)),
+                ("plan".to_string(), FormValue::Choice(Some("Pro".to_string()))),
+            ])
+        );
+        assert_eq!(form.error("terms"), None);
+    }
+
+    #[test]
+    fn test_errors_are_drawn_next_to_fields() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut form = form();
+        form.set_error("plan", "unavailable");
+        let mut terminal = Terminal::new(TestBackend::new(40, 3)).unwrap();
+        terminal.draw(|f| form.draw(f, f.area())).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y| (0..40).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        assert!(row(1).starts_with("Plan"));
+        assert!(row(1).ends_with(" unavailable"));
+        assert_eq!(form._ctx.children["plan"].area(), Some(Rect::new(13, 1, 15, 2)));
+    }
+}
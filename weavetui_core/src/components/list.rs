@@ -0,0 +1,240 @@
+//! Selectable list with its view state kept apart from the items.
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    widgets::{List as ListWidget, ListItem, ListState as ListWidgetState},
+    Frame,
+};
+
+use crate::{
+    event::{Action, Message},
+    theme::ColorRole,
+    Component, ComponentAccessor, ComponentContext,
+};
+
+/// Selection and scroll position of a [`List`], kept apart from its items.
+///
+/// Holding the state outside the list, e.g. next to data in a store, lets the items be
+/// replaced every frame without losing the cursor. Movement needs the item count, since the
+/// state doesn't know the items.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ListState {
+    selected: Option<usize>,
+    offset: usize,
+}
+
+impl ListState {
+    /// Set the selected index (chainable)
+    pub fn with_selected(mut self, selected: Option<usize>) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Get the selected index
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Select an index, or nothing
+    pub fn select(&mut self, selected: Option<usize>) {
+        self.selected = selected;
+    }
+
+    /// Get the index of the first item in view
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Select the next of `len` items, stopping at the last
+    pub fn select_next(&mut self, len: usize) {
+        let next = self.selected.map_or(0, |selected| selected.saturating_add(1));
+        self.selected = Some(next);
+        self.clamp(len);
+    }
+
+    /// Select the previous of `len` items, stopping at the first
+    pub fn select_previous(&mut self, len: usize) {
+        let previous = self.selected.map_or(0, |selected| selected.saturating_sub(1));
+        self.selected = Some(previous);
+        self.clamp(len);
+    }
+
+    /// Keep the selection within `len` items, selecting the first item if there was no
+    /// selection and nothing if there are no items
+    pub fn clamp(&mut self, len: usize) {
+        self.selected = match len.checked_sub(1) {
+            None => None,
+            Some(last) => Some(self.selected.unwrap_or(0).min(last)),
+        };
+        self.offset = self.offset.min(len.saturating_sub(1));
+    }
+}
+
+/// A list of text items with a selection, moved with the arrow keys while focused.
+///
+/// Enter sends an `Action::Message` with the list's event as the topic and the selected
+/// index as the payload. Items are styled with `list`, the selected one with
+/// `list.selected` (reversed by default) while the list has focus.
+///
+/// The list can draw its own items and state, or items and a [`ListState`] owned by the
+/// caller with [`List::render_stateful`], e.g. from a parent's `draw`.
+#[derive(Debug)]
+pub struct List {
+    items: Vec<String>,
+    state: ListState,
+    event: String,
+    pub _ctx: ComponentContext,
+}
+
+impl_component_accessor!(List);
+
+impl List {
+    /// Create a list of `items` with the first one selected. Enter sends `list:selected`.
+    pub fn new(items: Vec<String>) -> Self {
+        let mut state = ListState::default();
+        state.clamp(items.len());
+        Self {
+            items,
+            state,
+            event: "list:selected".to_string(),
+            _ctx: ComponentContext::default(),
+        }
+    }
+
+    /// Set the message topic sent when an item is chosen with enter (chainable)
+    pub fn with_event(mut self, event: &str) -> Self {
+        self.event = event.to_string();
+        self
+    }
+
+    /// Get the items
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    /// Replace the items, keeping the selection and scroll position where they fit
+    pub fn set_items(&mut self, items: Vec<String>) {
+        self.items = items;
+        self.state.clamp(self.items.len());
+    }
+
+    /// Get the list's own selection and scroll position
+    pub fn state(&self) -> &ListState {
+        &self.state
+    }
+
+    /// Change the list's own selection and scroll position
+    pub fn state_mut(&mut self) -> &mut ListState {
+        &mut self.state
+    }
+
+    /// Draw `items` with `state` in `area`, styled like the list, scrolling `state` to keep
+    /// the selection in view
+    pub fn render_stateful<S: AsRef<str>>(
+        &self,
+        f: &mut Frame<'_>,
+        area: Rect,
+        items: &[S],
+        state: &mut ListState,
+    ) {
+        state.clamp(items.len());
+        let mut highlight = Style::default();
+        if self.is_focused() {
+            highlight = self
+                .get_theme_manager()
+                .highlight(ColorRole::Selection)
+                .patch(self.compose_style(&["list", "list.selected"]));
+        }
+        let list = ListWidget::new(items.iter().map(|item| ListItem::new(item.as_ref())))
+            .style(self.get_style("list"))
+            .highlight_style(highlight);
+
+        let mut widget_state = ListWidgetState::default()
+            .with_selected(state.selected)
+            .with_offset(state.offset);
+        f.render_stateful_widget(list, area, &mut widget_state);
+        state.offset = widget_state.offset();
+    }
+}
+
+impl Component for List {
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let mut state = self.state;
+        self.render_stateful(f, area, &self.items, &mut state);
+        self.state = state;
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        if !self.is_focused() {
+            return None;
+        }
+
+        let len = self.items.len();
+        match key.code {
+            KeyCode::Up => self.state.select_previous(len),
+            KeyCode::Down => self.state.select_next(len),
+            KeyCode::Home => self.state.select(Some(0)),
+            KeyCode::End => self.state.select(Some(usize::MAX)),
+            KeyCode::Enter => {
+                let selected = self.state.selected()?;
+                return Some(Action::Message(
+                    Message::new(&self.event)
+                        .with_payload(&selected.to_string())
+                        .with_source(&self.name()),
+                ));
+            }
+            _ => {}
+        }
+        self.state.clamp(len);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_external_state_survives_new_items() {
+        let list = List::new(Vec::new());
+        let mut state = ListState::default().with_selected(Some(7));
+        let mut terminal = Terminal::new(TestBackend::new(6, 3)).unwrap();
+
+        let items = (0..10).map(|i| format!("item{i}")).collect::<Vec<_>>();
+        terminal.draw(|f| list.render_stateful(f, f.area(), &items, &mut state)).unwrap();
+        assert_eq!((state.selected(), state.offset()), (Some(7), 5));
+        assert_eq!(terminal.backend().buffer()[(4, 2)].symbol(), "7");
+
+        // Fresh items each frame keep the cursor where it fits
+        let items = (0..10).rev().map(|i| format!("new{i}")).collect::<Vec<_>>();
+        terminal.draw(|f| list.render_stateful(f, f.area(), &items, &mut state)).unwrap();
+        assert_eq!((state.selected(), state.offset()), (Some(7), 5));
+        terminal.draw(|f| list.render_stateful(f, f.area(), &items[..4], &mut state)).unwrap();
+        assert_eq!(state.selected(), Some(3));
+    }
+
+    #[test]
+    fn test_keys_move_the_selection() {
+        let mut list = List::new(vec!["a".into(), "b".into(), "c".into()]);
+        list.set_focused(true);
+        list.handle_key_events(KeyEvent::from(KeyCode::Up));
+        assert_eq!(list.state().selected(), Some(0));
+        list.handle_key_events(KeyEvent::from(KeyCode::End));
+        list.handle_key_events(KeyEvent::from(KeyCode::Down));
+        assert_eq!(list.state().selected(), Some(2));
+
+        list.set_items(vec!["a".into()]);
+        let action = list.handle_key_events(KeyEvent::from(KeyCode::Enter)).unwrap();
+        let Action::Message(message) = action else {
+            panic!("expected a message, got {:?}", action);
+        };
+        assert_eq!((message.topic.as_str(), message.payload.as_str()), ("list:selected", "0"));
+    }
+}
@@ -0,0 +1,429 @@
+//! Dropdown and context menu component.
+
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    layout::{Position, Rect},
+    style::Style,
+    widgets::{Block, BorderType, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+use crate::{
+    event::{Action, ActionKind},
+    geometry::anchored_rect,
+    theme::ColorRole,
+    Component, ComponentAccessor, ComponentContext,
+};
+
+const SUBMENU_MARKER: &str = " ›";
+
+/// A single entry in a [`Menu`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuItem {
+    pub label: String,
+    pub action: Option<Action>,
+    pub disabled: bool,
+    pub submenu: Vec<MenuItem>,
+}
+
+impl MenuItem {
+    /// Create an item that emits `action` when chosen
+    pub fn new(label: &str, action: impl Into<ActionKind>) -> Self {
+        Self {
+            label: label.to_string(),
+            action: Some(action.into().into()),
+            disabled: false,
+            submenu: Vec::new(),
+        }
+    }
+
+    /// Create an item that opens a nested menu
+    pub fn submenu(label: &str, items: Vec<MenuItem>) -> Self {
+        Self {
+            label: label.to_string(),
+            action: None,
+            disabled: false,
+            submenu: items,
+        }
+    }
+
+    /// Enable or disable the item (chainable)
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Check if the item opens a nested menu
+    pub fn has_submenu(&self) -> bool {
+        !self.submenu.is_empty()
+    }
+}
+
+/// A bordered vertical menu for dropdowns and context menus.
+///
+/// Up/down move the selection and skip disabled items. Enter (or right) opens a submenu or
+/// chooses the item, left closes a submenu and esc closes the innermost level, dismissing the
+/// menu when pressed at the top level. Choosing an item emits its action and dismisses the menu.
+///
+/// The menu draws itself inside the area it's given, anchored at [`Menu::set_position`] (or the
+/// area's top-left corner) and shifted back inside the area when it would overflow.
+#[derive(Debug)]
+pub struct Menu {
+    items: Vec<MenuItem>,
+    title: Option<String>,
+    position: Option<(u16, u16)>,
+    /// Selected index for each open level, outermost first
+    path: Vec<usize>,
+    /// Screen rects of the open levels from the last draw, used for mouse hits
+    level_rects: Vec<Rect>,
+    pub _ctx: ComponentContext,
+}
+
+impl_component_accessor!(Menu);
+
+impl Menu {
+    /// Create a menu with the given items
+    pub fn new(items: Vec<MenuItem>) -> Self {
+        let mut menu = Self {
+            items,
+            title: None,
+            position: None,
+            path: Vec::new(),
+            level_rects: Vec::new(),
+            _ctx: ComponentContext::default(),
+        };
+        menu.reset_selection();
+        menu
+    }
+
+    /// Set a title shown on the top border (chainable)
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Set where the top-left corner of the menu is drawn, in terminal coordinates
+    pub fn set_position(&mut self, x: u16, y: u16) {
+        self.position = Some((x, y));
+    }
+
+    /// Get the anchor position, if one was set
+    pub fn position(&self) -> Option<(u16, u16)> {
+        self.position
+    }
+
+    /// Replace the items and reset the selection
+    pub fn set_items(&mut self, items: Vec<MenuItem>) {
+        self.items = items;
+        self.reset_selection();
+    }
+
+    /// Get the top-level items
+    pub fn items(&self) -> &[MenuItem] {
+        &self.items
+    }
+
+    /// Show the menu with the selection reset to the first enabled item
+    pub fn open(&mut self) {
+        self.reset_selection();
+        self.set_active(true);
+    }
+
+    /// Show the menu at a position, e.g. where the user right-clicked
+    pub fn open_at(&mut self, x: u16, y: u16) {
+        self.set_position(x, y);
+        self.open();
+    }
+
+    /// Dismiss the menu
+    pub fn close(&mut self) {
+        self.path.truncate(1);
+        self.set_active(false);
+    }
+
+    /// Get the selected index at each open level, outermost first
+    pub fn selected_path(&self) -> &[usize] {
+        &self.path
+    }
+
+    /// Get the item under the cursor in the innermost open level
+    pub fn selected(&self) -> Option<&MenuItem> {
+        let depth = self.path.len() - 1;
+        self.level_items(depth).get(self.path[depth])
+    }
+
+    fn reset_selection(&mut self) {
+        self.path = vec![first_enabled(&self.items)];
+    }
+
+    fn level_items(&self, depth: usize) -> &[MenuItem] {
+        let mut items = self.items.as_slice();
+        for &index in &self.path[..depth] {
+            items = &items[index].submenu;
+        }
+        items
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let depth = self.path.len() - 1;
+        let items = self.level_items(depth);
+        let len = items.len() as isize;
+        if len == 0 {
+            return;
+        }
+
+        let mut index = self.path[depth] as isize;
+        for _ in 0..len {
+            index = (index + delta).rem_euclid(len);
+            if !items[index as usize].disabled {
+                break;
+            }
+        }
+        self.path[depth] = index as usize;
+    }
+
+    /// Open the selected submenu or choose the selected item
+    fn enter(&mut self) -> Option<Action> {
+        let item = self.selected()?;
+        if item.disabled {
+            return None;
+        }
+
+        if item.has_submenu() {
+            let first = first_enabled(&item.submenu);
+            self.path.push(first);
+            return None;
+        }
+
+        let action = item.action.clone();
+        self.close();
+        action
+    }
+
+    /// Close the innermost submenu, returning `false` when already at the top level
+    fn back(&mut self) -> bool {
+        if self.path.len() > 1 {
+            self.path.pop();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn click(&mut self, column: u16, row: u16) -> Option<Action> {
+        let position = Position::new(column, row);
+        let Some(depth) = self
+            .level_rects
+            .iter()
+            .rposition(|rect| rect.contains(position))
+        else {
+            self.close();
+            return None;
+        };
+
+        // Clicks on the borders don't pick an item
+        let top = self.level_rects[depth].y;
+        if row <= top {
+            return None;
+        }
+        let index = (row - top - 1) as usize;
+        if index >= self.level_items(depth).len() {
+            return None;
+        }
+
+        self.path.truncate(depth + 1);
+        self.path[depth] = index;
+        self.enter()
+    }
+}
+
+impl Component for Menu {
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let block_style = self.get_style("menu");
+        let item_style = self.get_style("menu.item");
+        let selected_style = self
+            .get_theme_manager()
+            .highlight(ColorRole::Selection)
+            .patch(self.compose_style(&["menu.item", "menu.item.selected"]));
+        let disabled_style = Style::default()
+            .fg(self.get_theme_manager().get_current_role(ColorRole::Muted))
+            .patch(self.compose_style(&["menu.item", "menu.item.disabled"]));
+
+        self.level_rects.clear();
+        let mut anchor = self.position.unwrap_or((area.x, area.y));
+
+        for depth in 0..self.path.len() {
+            let items = self.level_items(depth);
+            let inner_width = items
+                .iter()
+                .map(|item| {
+                    let marker = if item.has_submenu() { SUBMENU_MARKER } else { "" };
+                    item.label.chars().count() + marker.chars().count()
+                })
+                .max()
+                .unwrap_or(0);
+
+            let list_items = items
+                .iter()
+                .map(|item| {
+                    let marker = if item.has_submenu() { SUBMENU_MARKER } else { "" };
+                    let label_width = inner_width - marker.chars().count();
+                    let style = if item.disabled { disabled_style } else { item_style };
+                    ListItem::new(format!(" {:<label_width$}{} ", item.label, marker)).style(style)
+                })
+                .collect::<Vec<_>>();
+
+            let rect = anchored_rect(
+                anchor,
+                inner_width as u16 + 4,
+                items.len() as u16 + 2,
+                area,
+            );
+
+            let mut block = Block::bordered()
+                .border_type(BorderType::Rounded)
+                .style(block_style);
+            if depth == 0 {
+                if let Some(title) = &self.title {
+                    block = block.title(title.as_str());
+                }
+            }
+
+            let selected = self.path[depth];
+            let mut state = ListState::default().with_selected(Some(selected));
+            f.render_widget(Clear, rect);
+            f.render_stateful_widget(
+                List::new(list_items)
+                    .block(block)
+                    .highlight_style(selected_style),
+                rect,
+                &mut state,
+            );
+
+            // Nested levels open beside the selected row, aligning their border with it
+            anchor = (rect.right(), rect.y + selected as u16);
+            self.level_rects.push(rect);
+        }
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::Enter => return self.enter(),
+            KeyCode::Right if self.selected().is_some_and(MenuItem::has_submenu) => {
+                return self.enter();
+            }
+            KeyCode::Left => {
+                self.back();
+            }
+            KeyCode::Esc if !self.back() => self.close(),
+            _ => {}
+        }
+        None
+    }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Option<Action> {
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => self.click(mouse.column, mouse.row),
+            _ => None,
+        }
+    }
+}
+
+/// Index of the first enabled item, or 0 if all are disabled
+fn first_enabled(items: &[MenuItem]) -> usize {
+    items.iter().position(|item| !item.disabled).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(menu: &mut Menu, code: KeyCode) -> Option<Action> {
+        menu.handle_key_events(KeyEvent::from(code))
+    }
+
+    fn sample_menu() -> Menu {
+        Menu::new(vec![
+            MenuItem::new("Open", "app:open"),
+            MenuItem::new("Save", "app:save").disabled(true),
+            MenuItem::submenu(
+                "Export",
+                vec![
+                    MenuItem::new("PDF", "app:export:pdf"),
+                    MenuItem::new("HTML", "app:export:html"),
+                ],
+            ),
+            MenuItem::new("Quit", Action::Quit),
+        ])
+    }
+
+    #[test]
+    fn test_navigation_skips_disabled_items() {
+        let mut menu = sample_menu();
+        press(&mut menu, KeyCode::Down);
+        assert_eq!(menu.selected_path(), &[2]);
+
+        press(&mut menu, KeyCode::Up);
+        assert_eq!(menu.selected_path(), &[0]);
+
+        press(&mut menu, KeyCode::Up);
+        assert_eq!(menu.selected_path(), &[3]);
+    }
+
+    #[test]
+    fn test_submenu_selection_emits_action_and_dismisses() {
+        let mut menu = sample_menu();
+        press(&mut menu, KeyCode::Down);
+        assert_eq!(press(&mut menu, KeyCode::Right), None);
+        assert_eq!(menu.selected_path(), &[2, 0]);
+
+        press(&mut menu, KeyCode::Down);
+        let action = press(&mut menu, KeyCode::Enter);
+        assert_eq!(action, Some(Action::AppAction("app:export:html".to_string())));
+        assert!(!menu.is_active());
+    }
+
+    #[test]
+    fn test_esc_closes_innermost_level_first() {
+        let mut menu = sample_menu();
+        press(&mut menu, KeyCode::Down);
+        press(&mut menu, KeyCode::Enter);
+
+        press(&mut menu, KeyCode::Esc);
+        assert_eq!(menu.selected_path(), &[2]);
+        assert!(menu.is_active());
+
+        press(&mut menu, KeyCode::Esc);
+        assert!(!menu.is_active());
+    }
+
+    #[test]
+    fn test_clicks_on_the_border_are_ignored() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut menu = sample_menu();
+        let mut terminal = Terminal::new(TestBackend::new(20, 8)).unwrap();
+        terminal.draw(|f| menu.draw(f, f.area())).unwrap();
+        let click = |menu: &mut Menu, row| {
+            menu.handle_mouse_events(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: 2,
+                row,
+                modifiers: crossterm::event::KeyModifiers::NONE,
+            })
+        };
+
+        assert_eq!(click(&mut menu, 0), None);
+        assert_eq!(click(&mut menu, 5), None);
+        assert!(menu.is_active());
+
+        let action = click(&mut menu, 1);
+        assert_eq!(action, Some(Action::AppAction("app:open".to_string())));
+    }
+}
@@ -0,0 +1,121 @@
+//! Gauge colored by how full it is.
+
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    widgets::Gauge,
+    Frame,
+};
+
+use crate::{theme::ColorRole, Component, ComponentAccessor, ComponentContext};
+
+/// A horizontal gauge showing a value from 0 to 1, colored by thresholds.
+///
+/// Below the medium threshold the bar is green, from it up to the high threshold yellow, and
+/// red from there on; the `gauge.low`, `gauge.medium` and `gauge.high` theme styles replace
+/// these colors. The label defaults to the value as a percentage.
+#[derive(Debug)]
+pub struct MeterGauge {
+    value: f64,
+    label: Option<String>,
+    medium: f64,
+    high: f64,
+    pub _ctx: ComponentContext,
+}
+
+impl_component_accessor!(MeterGauge);
+
+impl MeterGauge {
+    /// Create a gauge at `value`, clamped to 0..=1, turning yellow at 0.5 and red at 0.8
+    pub fn new(value: f64) -> Self {
+        Self {
+            value: clamp_ratio(value),
+            label: None,
+            medium: 0.5,
+            high: 0.8,
+            _ctx: ComponentContext::default(),
+        }
+    }
+
+    /// Set the values where the gauge turns from low to medium and from medium to high
+    /// (chainable)
+    pub fn with_thresholds(mut self, medium: f64, high: f64) -> Self {
+        self.medium = medium;
+        self.high = high;
+        self
+    }
+
+    /// Set the text shown over the bar instead of the percentage (chainable)
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Get the value, from 0 to 1
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Change the value, clamped to 0..=1
+    pub fn set_value(&mut self, value: f64) {
+        self.value = clamp_ratio(value);
+    }
+
+    /// Change the text shown over the bar, `None` showing the percentage
+    pub fn set_label(&mut self, label: Option<&str>) {
+        self.label = label.map(str::to_string);
+    }
+
+    /// Get the theme style name and the color role used when the theme doesn't set it
+    fn level(&self) -> (&'static str, ColorRole) {
+        if self.value >= self.high {
+            ("gauge.high", ColorRole::Error)
+        } else if self.value >= self.medium {
+            ("gauge.medium", ColorRole::Warning)
+        } else {
+            ("gauge.low", ColorRole::Success)
+        }
+    }
+}
+
+/// Clamp a value to a ratio, treating NaN as empty
+pub(super) fn clamp_ratio(value: f64) -> f64 {
+    if value.is_nan() {
+        0.0
+    } else {
+        value.clamp(0.0, 1.0)
+    }
+}
+
+impl Component for MeterGauge {
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let (style_name, role) = self.level();
+        let style = Style::default()
+            .fg(self.get_theme_manager().get_current_role(role))
+            .patch(self.get_style(style_name));
+        let label = match &self.label {
+            Some(label) => label.clone(),
+            None => format!("{:.0}%", self.value * 100.0),
+        };
+
+        let gauge = Gauge::default().gauge_style(style).ratio(self.value).label(label);
+        f.render_widget(gauge, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_is_clamped_and_picks_the_level() {
+        let mut gauge = MeterGauge::new(1.5).with_thresholds(0.3, 0.6);
+        assert_eq!(gauge.value(), 1.0);
+        assert_eq!(gauge.level().0, "gauge.high");
+
+        gauge.set_value(0.3);
+        assert_eq!(gauge.level().0, "gauge.medium");
+        gauge.set_value(f64::NAN);
+        assert_eq!((gauge.value(), gauge.level().0), (0.0, "gauge.low"));
+    }
+}
@@ -0,0 +1,108 @@
+//! Built-in components for common UI patterns.
+//!
+//! These components are regular [`Component`](crate::Component) implementations and can be
+//! used as roots or children like any component declared with `#[component]`.
+
+/// Implements `ComponentAccessor` for a built-in component storing its state in a `_ctx` field.
+///
+/// Mirrors what the `#[component]` attribute generates, since the derive crate can't be used here.
+macro_rules! impl_component_accessor {
+    ($name:ident) => {
+        impl $crate::ComponentAccessor for $name {
+            fn name(&self) -> String {
+                self._ctx
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| stringify!($name).to_string())
+            }
+
+            fn area(&self) -> Option<ratatui::layout::Rect> {
+                self._ctx.area
+            }
+
+            fn set_area(&mut self, area: ratatui::layout::Rect) {
+                self._ctx.area = Some(area);
+            }
+
+            fn is_active(&self) -> bool {
+                self._ctx.active
+            }
+
+            fn set_active(&mut self, active: bool) {
+                self._ctx.active = active;
+                (self as &mut dyn $crate::Component).on_active_changed(active);
+            }
+
+            fn is_focused(&self) -> bool {
+                self._ctx.focused
+            }
+
+            fn set_focused(&mut self, focused: bool) {
+                self._ctx.focused = focused;
+            }
+
+            fn register_action_handler(
+                &mut self,
+                tx: tokio::sync::mpsc::UnboundedSender<$crate::event::Action>,
+            ) {
+                self._ctx.action_tx = Some(tx);
+            }
+
+            fn send_action(&self, action: $crate::event::Action) {
+                if let Some(tx) = &self._ctx.action_tx {
+                    let _ = tx.send(action);
+                }
+            }
+
+            fn get_context(&self) -> &$crate::ComponentContext {
+                &self._ctx
+            }
+
+            fn get_context_mut(&mut self) -> &mut $crate::ComponentContext {
+                &mut self._ctx
+            }
+
+            fn get_children(&mut self) -> &mut $crate::Children {
+                &mut self._ctx.children
+            }
+
+            fn get_theme_manager(&self) -> &$crate::theme::ThemeManager {
+                &self._ctx.theme_manager
+            }
+
+            fn set_theme_manager(&mut self, theme_manager: $crate::theme::ThemeManager) {
+                self._ctx.theme_manager = theme_manager;
+            }
+        }
+    };
+}
+
+mod checkbox;
+mod confirm_dialog;
+mod form;
+mod list;
+mod menu;
+mod meter_gauge;
+mod notifications;
+mod progress_list;
+mod radio_group;
+mod split;
+mod sparkline;
+mod text_view;
+mod tree;
+mod viewport;
+
+pub use checkbox::Checkbox;
+pub use confirm_dialog::ConfirmDialog;
+pub use form::{Form, FormSubmission, FormValue};
+pub use list::{List, ListState};
+pub use menu::{Menu, MenuItem};
+pub use meter_gauge::MeterGauge;
+pub use notifications::{Corner, Notifications};
+pub use progress_list::{ProgressList, TaskStatus};
+pub use radio_group::RadioGroup;
+pub use split::Split;
+pub use sparkline::Sparkline;
+pub use text_view::TextView;
+pub use tree::{Tree, TreeNode};
+pub use viewport::Viewport;
@@ -0,0 +1,218 @@
+//! Stacked toast notifications that dismiss themselves.
+
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    widgets::{Block, BorderType, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    event::{Action, NotifyLevel},
+    theme::ColorRole,
+    Component, ComponentAccessor, ComponentContext,
+};
+
+/// Corner of the screen notifications stack from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Debug, Clone)]
+struct Toast {
+    level: NotifyLevel,
+    text: String,
+    expires_at: Instant,
+}
+
+/// Transient notifications ("Saved!", "Error: ...") stacked in a corner of the screen.
+///
+/// Add it as the last root component (or use `App::with_notifications`) so toasts are drawn
+/// on top, then dispatch `Action::Notify`. Each toast expires on its own after its `ttl`;
+/// the oldest is closest to the corner. Toasts are styled with the `notification` and
+/// `notification.<level>` theme styles, falling back to a colored border per level.
+#[derive(Debug)]
+pub struct Notifications {
+    toasts: Vec<Toast>,
+    corner: Corner,
+    width: u16,
+    pub _ctx: ComponentContext,
+}
+
+impl_component_accessor!(Notifications);
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Notifications {
+    /// Create an empty notification stack in the top-right corner
+    pub fn new() -> Self {
+        Self {
+            toasts: Vec::new(),
+            corner: Corner::default(),
+            width: 40,
+            _ctx: ComponentContext::default(),
+        }
+    }
+
+    /// Set the corner toasts stack from (chainable)
+    pub fn with_corner(mut self, corner: Corner) -> Self {
+        self.corner = corner;
+        self
+    }
+
+    /// Set the width of each toast, borders included (chainable)
+    pub fn with_width(mut self, width: u16) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Show a notification for `ttl`
+    pub fn push(&mut self, level: NotifyLevel, text: &str, ttl: Duration) {
+        self.toasts.push(Toast {
+            level,
+            text: text.to_string(),
+            expires_at: Instant::now() + ttl,
+        });
+    }
+
+    /// Dismiss every notification
+    pub fn clear(&mut self) {
+        self.toasts.clear();
+    }
+
+    /// Get the level and text of the notifications still showing, oldest first
+    pub fn visible(&self) -> Vec<(NotifyLevel, &str)> {
+        self.toasts
+            .iter()
+            .map(|toast| (toast.level, toast.text.as_str()))
+            .collect()
+    }
+
+    fn prune(&mut self) {
+        let now = Instant::now();
+        self.toasts.retain(|toast| toast.expires_at > now);
+    }
+
+    fn toast_style(&self, level: NotifyLevel) -> Style {
+        let role = match level {
+            NotifyLevel::Info => ColorRole::Info,
+            NotifyLevel::Success => ColorRole::Success,
+            NotifyLevel::Warning => ColorRole::Warning,
+            NotifyLevel::Error => ColorRole::Error,
+        };
+        let level_key = format!("notification.{}", level.as_str());
+
+        Style::default()
+            .fg(self.get_theme_manager().get_current_role(role))
+            .patch(self.compose_style(&["notification", &level_key]))
+    }
+}
+
+impl Component for Notifications {
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        self.prune();
+
+        let width = self.width.min(area.width);
+        let inner_width = width.saturating_sub(2).max(1) as usize;
+        let mut offset = 0;
+
+        for toast in &self.toasts {
+            // Wrapped line count, assuming one cell per character
+            let lines = toast
+                .text
+                .lines()
+                .map(|line| line.chars().count().div_ceil(inner_width).max(1))
+                .sum::<usize>()
+                .max(1) as u16;
+            let height = lines + 2;
+            if offset + height > area.height {
+                break;
+            }
+
+            let x = match self.corner {
+                Corner::TopLeft | Corner::BottomLeft => area.x,
+                Corner::TopRight | Corner::BottomRight => area.right() - width,
+            };
+            let y = match self.corner {
+                Corner::TopLeft | Corner::TopRight => area.y + offset,
+                Corner::BottomLeft | Corner::BottomRight => area.bottom() - offset - height,
+            };
+            let rect = Rect::new(x, y, width, height);
+            offset += height;
+
+            let block = Block::bordered()
+                .border_type(BorderType::Rounded)
+                .border_style(self.toast_style(toast.level));
+            f.render_widget(Clear, rect);
+            f.render_widget(
+                Paragraph::new(toast.text.as_str())
+                    .block(block)
+                    .wrap(Wrap { trim: false }),
+                rect,
+            );
+        }
+    }
+
+    fn update(&mut self, action: &Action) {
+        match action {
+            Action::Notify { level, text, ttl } => self.push(*level, text, *ttl),
+            Action::Tick | Action::Render => self.prune(),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, style::Color, Terminal};
+
+    fn notify(level: NotifyLevel, text: &str, ttl: Duration) -> Action {
+        Action::Notify {
+            level,
+            text: text.to_string(),
+            ttl,
+        }
+    }
+
+    #[test]
+    fn test_toasts_stack_and_expire_independently() {
+        let mut notifications = Notifications::new().with_width(12);
+        notifications.update(&notify(NotifyLevel::Success, "Saved!", Duration::from_secs(60)));
+        notifications.update(&notify(NotifyLevel::Error, "Oops", Duration::ZERO));
+        notifications.update(&notify(NotifyLevel::Info, "Synced", Duration::from_secs(60)));
+
+        notifications.update(&Action::Tick);
+        assert_eq!(
+            notifications.visible(),
+            vec![(NotifyLevel::Success, "Saved!"), (NotifyLevel::Info, "Synced")]
+        );
+
+        let mut terminal = Terminal::new(TestBackend::new(20, 8)).unwrap();
+        terminal
+            .draw(|f| notifications.draw(f, f.area()))
+            .unwrap();
+
+        // Stacked from the top-right corner, oldest first
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| {
+            (8..20)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        };
+        assert_eq!(row(1), "│Saved!    │");
+        assert_eq!(row(4), "│Synced    │");
+        assert_eq!(buffer[(8, 1)].fg, Color::Green);
+    }
+}
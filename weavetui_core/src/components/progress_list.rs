@@ -0,0 +1,255 @@
+//! Rows of labeled gauges tracking several tasks at once.
+
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Style,
+    text::Span,
+    widgets::Gauge,
+    Frame,
+};
+
+use super::meter_gauge::clamp_ratio;
+use crate::{event::Message, theme::ColorRole, Component, ComponentAccessor, ComponentContext};
+
+/// Where a task in a [`ProgressList`] stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskStatus {
+    Running,
+    Done,
+    Failed,
+}
+
+impl TaskStatus {
+    /// Get the theme style name and the color role used when the theme doesn't set it
+    fn style(self) -> (&'static str, ColorRole) {
+        match self {
+            TaskStatus::Running => ("progress.running", ColorRole::Primary),
+            TaskStatus::Done => ("progress.done", ColorRole::Success),
+            TaskStatus::Failed => ("progress.failed", ColorRole::Error),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Task {
+    name: String,
+    label: String,
+    progress: f64,
+    status: TaskStatus,
+}
+
+/// A list of tasks, each drawn as one row with its label and a gauge of its progress.
+///
+/// Tasks are shown in the order they were added, and setting the progress of a task that
+/// isn't listed yet adds it, labeled with its name. A task reaching full progress while
+/// running is done. Gauges are colored by status with the `progress.running`,
+/// `progress.done` and `progress.failed` theme styles, falling back to the theme's primary,
+/// success and error colors. Done tasks can be hidden with `with_hide_done`.
+///
+/// Besides the typed API, the list follows `task:update:<name>:<percent>` app actions or
+/// message topics, e.g. `task:update:build:40`, and `task:status:<name>:<status>` ones, with
+/// `running`, `done` or `failed` as the status.
+#[derive(Debug)]
+pub struct ProgressList {
+    tasks: Vec<Task>,
+    hide_done: bool,
+    pub _ctx: ComponentContext,
+}
+
+impl_component_accessor!(ProgressList);
+
+impl Default for ProgressList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressList {
+    /// Create an empty list
+    pub fn new() -> Self {
+        Self {
+            tasks: Vec::new(),
+            hide_done: false,
+            _ctx: ComponentContext::default(),
+        }
+    }
+
+    /// Leave done tasks out of the list instead of showing them full (chainable)
+    pub fn with_hide_done(mut self, hide: bool) -> Self {
+        self.hide_done = hide;
+        self
+    }
+
+    /// Add a running task with no progress, or relabel it if it's already listed
+    pub fn add_task(&mut self, name: &str, label: &str) {
+        self.task_mut(name).label = label.to_string();
+    }
+
+    /// Set a task's progress, clamped to 0..=1, adding the task if needed
+    pub fn set_progress(&mut self, name: &str, progress: f64) {
+        let task = self.task_mut(name);
+        task.progress = clamp_ratio(progress);
+        if task.progress >= 1.0 && task.status == TaskStatus::Running {
+            task.status = TaskStatus::Done;
+        }
+    }
+
+    /// Set a task's status, adding the task if needed
+    pub fn set_status(&mut self, name: &str, status: TaskStatus) {
+        self.task_mut(name).status = status;
+    }
+
+    /// Remove a task, returning whether it was listed
+    pub fn remove_task(&mut self, name: &str) -> bool {
+        let len = self.tasks.len();
+        self.tasks.retain(|task| task.name != name);
+        self.tasks.len() != len
+    }
+
+    /// Get a task's progress, from 0 to 1
+    pub fn progress(&self, name: &str) -> Option<f64> {
+        self.task(name).map(|task| task.progress)
+    }
+
+    /// Get a task's status
+    pub fn status(&self, name: &str) -> Option<TaskStatus> {
+        self.task(name).map(|task| task.status)
+    }
+
+    fn task(&self, name: &str) -> Option<&Task> {
+        self.tasks.iter().find(|task| task.name == name)
+    }
+
+    fn task_mut(&mut self, name: &str) -> &mut Task {
+        let index = match self.tasks.iter().position(|task| task.name == name) {
+            Some(index) => index,
+            None => {
+                self.tasks.push(Task {
+                    name: name.to_string(),
+                    label: name.to_string(),
+                    progress: 0.0,
+                    status: TaskStatus::Running,
+                });
+                self.tasks.len() - 1
+            }
+        };
+        &mut self.tasks[index]
+    }
+
+    fn visible(&self) -> impl Iterator<Item = &Task> {
+        self.tasks
+            .iter()
+            .filter(|task| !(self.hide_done && task.status == TaskStatus::Done))
+    }
+}
+
+impl Component for ProgressList {
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let label_width = self
+            .visible()
+            .map(|task| task.label.chars().count() as u16 + 1)
+            .max()
+            .unwrap_or(0)
+            .min(area.width / 2);
+        let theme = self.get_theme_manager();
+
+        for (row, task) in self.visible().take(area.height as usize).enumerate() {
+            let row_area = Rect {
+                y: area.y + row as u16,
+                height: 1,
+                ..area
+            };
+            let [label_area, gauge_area] =
+                Layout::horizontal([Constraint::Length(label_width), Constraint::Fill(1)])
+                    .areas(row_area);
+
+            let (style_name, role) = task.status.style();
+            let style = Style::default()
+                .fg(theme.get_current_role(role))
+                .patch(theme.get_current_style(style_name));
+            let percent = format!("{:.0}%", task.progress * 100.0);
+            let label = match task.status {
+                TaskStatus::Failed => format!("{} failed", percent),
+                _ => percent,
+            };
+
+            f.render_widget(Span::raw(task.label.as_str()), label_area);
+            f.render_widget(
+                Gauge::default().gauge_style(style).ratio(task.progress).label(label),
+                gauge_area,
+            );
+        }
+    }
+
+    fn measure(&self, _width: u16) -> Option<u16> {
+        Some(self.visible().count() as u16)
+    }
+
+    fn on_message(&mut self, message: &Message) {
+        let Some((command, rest)) = message
+            .topic
+            .strip_prefix("task:")
+            .and_then(|topic| topic.split_once(':'))
+        else {
+            return;
+        };
+        let Some((name, value)) = rest.rsplit_once(':') else {
+            return;
+        };
+
+        match command {
+            "update" => {
+                if let Ok(percent) = value.trim().parse::<f64>() {
+                    self.set_progress(name, percent / 100.0);
+                }
+            }
+            "status" => {
+                let status = match value {
+                    "running" => TaskStatus::Running,
+                    "done" => TaskStatus::Done,
+                    "failed" => TaskStatus::Failed,
+                    _ => return,
+                };
+                self.set_status(name, status);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, style::Color, Terminal};
+
+    #[test]
+    fn test_tasks_follow_actions_and_hide_when_done() {
+        let mut list = ProgressList::new().with_hide_done(true);
+        list.add_task("build", "Build");
+        list.on_message(&Message::new("task:update:build:40"));
+        list.on_message(&Message::new("task:update:fetch:deps:150"));
+        list.on_message(&Message::new("task:status:lint:failed"));
+        list.on_message(&Message::new("task:update:build:soon"));
+
+        assert_eq!(list.progress("build"), Some(0.4));
+        assert_eq!(list.progress("fetch:deps"), Some(1.0));
+        assert_eq!(list.status("fetch:deps"), Some(TaskStatus::Done));
+        assert_eq!(list.status("lint"), Some(TaskStatus::Failed));
+        assert_eq!(list.measure(20), Some(2));
+
+        let mut terminal = Terminal::new(TestBackend::new(20, 3)).unwrap();
+        terminal.draw(|f| list.draw(f, f.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+        let row = |y| (0..20).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        assert!(row(0).starts_with("Build"), "{}", row(0));
+        assert!(row(1).starts_with("lint"), "{}", row(1));
+        assert!(row(1).contains("0% failed"), "{}", row(1));
+        assert_eq!(buffer[(6, 0)].fg, Color::Blue);
+        assert_eq!(buffer[(6, 1)].fg, Color::Red);
+        assert!(row(2).trim().is_empty());
+
+        assert!(list.remove_task("lint"));
+        assert!(!list.remove_task("lint"));
+        assert_eq!(list.measure(20), Some(1));
+    }
+}
@@ -0,0 +1,184 @@
+//! Radio group component for single-choice form inputs.
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    text::Line,
+    widgets::Paragraph,
+    Frame,
+};
+
+use super::FormValue;
+use crate::{
+    event::{Action, Message},
+    theme::ColorRole,
+    Component, ComponentAccessor, ComponentContext,
+};
+
+/// A vertical set of mutually exclusive options drawn as `(•) option` or `( ) option`.
+///
+/// While focused, up and down move the cursor and space or enter selects the option under
+/// it. When the selection changes, it sends an `Action::Message` with the group's event as
+/// the topic and the selected option as the payload.
+/// Options are styled with the `radio` theme style; the cursor row is patched with
+/// `radio.cursor` (reversed by default) while the group has focus.
+#[derive(Debug)]
+pub struct RadioGroup {
+    options: Vec<String>,
+    selected: Option<usize>,
+    cursor: usize,
+    event: String,
+    pub _ctx: ComponentContext,
+}
+
+impl_component_accessor!(RadioGroup);
+
+impl RadioGroup {
+    /// Create a group with nothing selected that emits `radio:changed` on selection
+    pub fn new(options: &[&str]) -> Self {
+        Self {
+            options: options.iter().map(|option| option.to_string()).collect(),
+            selected: None,
+            cursor: 0,
+            event: "radio:changed".to_string(),
+            _ctx: ComponentContext::default(),
+        }
+    }
+
+    /// Select an option initially and put the cursor on it (chainable)
+    pub fn with_selected(mut self, index: usize) -> Self {
+        self.set_selected(Some(index));
+        self
+    }
+
+    /// Set the topic of the message sent when the selection changes (chainable)
+    pub fn with_event(mut self, event: &str) -> Self {
+        self.event = event.to_string();
+        self
+    }
+
+    /// Get the index of the selected option
+    pub fn selected_index(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Get the label of the selected option
+    pub fn selected(&self) -> Option<&str> {
+        self.selected.map(|index| self.options[index].as_str())
+    }
+
+    /// Select an option without emitting an event; out of range indexes clear the selection
+    pub fn set_selected(&mut self, index: Option<usize>) {
+        self.selected = index.filter(|&index| index < self.options.len());
+        if let Some(index) = self.selected {
+            self.cursor = index;
+        }
+    }
+
+    /// Get the options
+    pub fn options(&self) -> &[String] {
+        &self.options
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let len = self.options.len() as isize;
+        if len > 0 {
+            self.cursor = (self.cursor as isize + delta).rem_euclid(len) as usize;
+        }
+    }
+
+    /// Select the option under the cursor, returning the change event if it changed
+    fn select_cursor(&mut self) -> Option<Action> {
+        if self.cursor >= self.options.len() || self.selected == Some(self.cursor) {
+            return None;
+        }
+
+        self.selected = Some(self.cursor);
+        Some(Action::Message(
+            Message::new(&self.event)
+                .with_payload(&self.options[self.cursor])
+                .with_source(&self.name()),
+        ))
+    }
+}
+
+impl Component for RadioGroup {
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn form_value(&self) -> Option<FormValue> {
+        Some(FormValue::Choice(self.selected().map(str::to_string)))
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let style = self.get_style("radio");
+        let cursor_style = self
+            .get_theme_manager()
+            .highlight(ColorRole::Selection)
+            .patch(self.compose_style(&["radio", "radio.cursor"]));
+
+        let lines = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(index, option)| {
+                let mark = if self.selected == Some(index) { "•" } else { " " };
+                let line = Line::from(format!("({}) {}", mark, option));
+                if self.is_focused() && index == self.cursor {
+                    line.style(cursor_style)
+                } else {
+                    line.style(style)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        f.render_widget(Paragraph::new(lines), area);
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        if !self.is_focused() {
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Up => self.move_cursor(-1),
+            KeyCode::Down => self.move_cursor(1),
+            KeyCode::Char(' ') | KeyCode::Enter => return self.select_cursor(),
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arrows_move_and_space_selects() {
+        let mut group = RadioGroup::new(&["Light", "Dark", "System"]).with_event("settings:theme");
+        group.set_focused(true);
+        let press = |group: &mut RadioGroup, code| group.handle_key_events(KeyEvent::from(code));
+
+        press(&mut group, KeyCode::Up);
+        assert_eq!(group.selected_index(), None);
+        let changed = Message::new("settings:theme")
+            .with_payload("System")
+            .with_source("RadioGroup");
+        assert_eq!(press(&mut group, KeyCode::Char(' ')), Some(Action::Message(changed)));
+        assert_eq!(group.selected(), Some("System"));
+
+        // Re-selecting the same option isn't a change
+        assert_eq!(press(&mut group, KeyCode::Enter), None);
+
+        press(&mut group, KeyCode::Down);
+        press(&mut group, KeyCode::Down);
+        press(&mut group, KeyCode::Enter);
+        assert_eq!(group.selected_index(), Some(1));
+
+        group.set_focused(false);
+        press(&mut group, KeyCode::Up);
+        assert_eq!(press(&mut group, KeyCode::Enter), None);
+    }
+}
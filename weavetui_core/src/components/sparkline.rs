@@ -0,0 +1,123 @@
+//! Rolling chart of recent values.
+
+use std::collections::VecDeque;
+
+use ratatui::{layout::Rect, widgets::Sparkline as SparklineWidget, Frame};
+
+use crate::{event::Message, Component, ComponentAccessor, ComponentContext};
+
+/// A sparkline of the most recent values, e.g. frame times or requests per second.
+///
+/// Values are added with [`Sparkline::push`], or by sending an `Action::Message` with the
+/// topic set by [`Sparkline::with_event`] and the value as the payload. Once the window is
+/// full the oldest value is dropped. The newest values are drawn at the right edge, as many
+/// as fit. Styled with the `sparkline` theme style.
+#[derive(Debug)]
+pub struct Sparkline {
+    values: VecDeque<u64>,
+    window: usize,
+    max: Option<u64>,
+    event: Option<String>,
+    pub _ctx: ComponentContext,
+}
+
+impl_component_accessor!(Sparkline);
+
+impl Sparkline {
+    /// Create an empty sparkline keeping the last `window` values
+    pub fn new(window: usize) -> Self {
+        Self {
+            values: VecDeque::with_capacity(window),
+            window: window.max(1),
+            max: None,
+            event: None,
+            _ctx: ComponentContext::default(),
+        }
+    }
+
+    /// Scale bars against `max` instead of the largest value in view (chainable)
+    pub fn with_max(mut self, max: u64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Push the payload of messages with this topic, when it parses as a number (chainable)
+    pub fn with_event(mut self, event: &str) -> Self {
+        self.event = Some(event.to_string());
+        self
+    }
+
+    /// Add a value, dropping the oldest once the window is full
+    pub fn push(&mut self, value: u64) {
+        if self.values.len() == self.window {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    /// Get the values, oldest first
+    pub fn values(&self) -> impl Iterator<Item = u64> + '_ {
+        self.values.iter().copied()
+    }
+
+    /// Remove every value
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    /// Change how many values are kept, dropping the oldest ones that no longer fit
+    pub fn set_window(&mut self, window: usize) {
+        self.window = window.max(1);
+        let overflow = self.values.len().saturating_sub(self.window);
+        self.values.drain(..overflow);
+    }
+}
+
+impl Component for Sparkline {
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let skip = self.values.len().saturating_sub(area.width as usize);
+        let mut sparkline = SparklineWidget::default()
+            .data(self.values.iter().skip(skip))
+            .style(self.get_style("sparkline"));
+        if let Some(max) = self.max {
+            sparkline = sparkline.max(max);
+        }
+        f.render_widget(sparkline, area);
+    }
+
+    fn on_message(&mut self, message: &Message) {
+        if self.event.as_deref() != Some(message.topic.as_str()) {
+            return;
+        }
+        if let Ok(value) = message.payload.trim().parse() {
+            self.push(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_window_keeps_the_newest_values() {
+        let mut sparkline = Sparkline::new(4).with_event("metrics:fps").with_max(8);
+        for value in [1, 2, 3, 4, 5] {
+            sparkline.push(value);
+        }
+        sparkline.on_message(&Message::new("metrics:fps").with_payload("8"));
+        sparkline.on_message(&Message::new("metrics:fps").with_payload("fast"));
+        sparkline.on_message(&Message::new("metrics:cpu").with_payload("1"));
+        assert_eq!(sparkline.values().collect::<Vec<_>>(), [3, 4, 5, 8]);
+
+        let mut terminal = Terminal::new(TestBackend::new(2, 1)).unwrap();
+        terminal.draw(|f| sparkline.draw(f, f.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer[(1, 0)].symbol(), "█");
+        assert_eq!(buffer[(0, 0)].symbol(), "▅");
+
+        sparkline.set_window(2);
+        assert_eq!(sparkline.values().collect::<Vec<_>>(), [5, 8]);
+    }
+}
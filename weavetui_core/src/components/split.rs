@@ -0,0 +1,281 @@
+//! Two-pane split layout with a movable divider.
+
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    layout::{Direction, Position, Rect},
+    Frame,
+};
+
+use crate::{event::Action, Component, ComponentAccessor, ComponentContext};
+
+/// Lays out two child components next to each other (`Direction::Horizontal`) or on top of
+/// each other (`Direction::Vertical`), separated by a one cell divider.
+///
+/// The divider sits at a fraction of the split's area. While the split is focused, the
+/// arrow keys along its direction move the divider; with mouse capture on, it can also be
+/// dragged. Each pane keeps its minimum size where the area allows. The split only does
+/// layout and never emits actions. The divider is styled with `split.divider`, or
+/// `split.divider.focused` while the split has focus.
+#[derive(Debug)]
+pub struct Split {
+    first: String,
+    second: String,
+    direction: Direction,
+    ratio: f32,
+    step: f32,
+    min_sizes: (u16, u16),
+    dragging: bool,
+    pub _ctx: ComponentContext,
+}
+
+impl_component_accessor!(Split);
+
+impl Split {
+    /// Create a side by side split of two named children, dividing the area in half
+    pub fn new(
+        (first, first_component): (&str, Box<dyn Component>),
+        (second, second_component): (&str, Box<dyn Component>),
+    ) -> Self {
+        let mut split = Self {
+            first: first.to_string(),
+            second: second.to_string(),
+            direction: Direction::Horizontal,
+            ratio: 0.5,
+            step: 0.05,
+            min_sizes: (1, 1),
+            dragging: false,
+            _ctx: ComponentContext::default(),
+        };
+        split.child_insert(first, first_component);
+        split.child_insert(second, second_component);
+        split
+    }
+
+    /// Set whether the panes are side by side or stacked (chainable)
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Set the initial divider position (chainable)
+    pub fn with_ratio(mut self, ratio: f32) -> Self {
+        self.set_ratio(ratio);
+        self
+    }
+
+    /// Set how far one key press moves the divider, as a fraction of the area (chainable)
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Set the smallest size, in cells, of the first and second pane (chainable)
+    pub fn with_min_sizes(mut self, first: u16, second: u16) -> Self {
+        self.min_sizes = (first, second);
+        self
+    }
+
+    /// Get the share of the area given to the first pane
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    /// Move the divider, clamping `ratio` between 0 and 1
+    pub fn set_ratio(&mut self, ratio: f32) {
+        self.ratio = ratio.clamp(0.0, 1.0);
+    }
+
+    /// Get whether the panes are side by side or stacked
+    pub fn orientation(&self) -> Direction {
+        self.direction
+    }
+
+    /// Set whether the panes are side by side or stacked
+    pub fn set_orientation(&mut self, direction: Direction) {
+        self.direction = direction;
+    }
+
+    /// Split `area` into the first pane, the divider and the second pane
+    fn layout(&self, area: Rect) -> (Rect, Rect, Rect) {
+        let total = self.length(area).saturating_sub(1);
+        let (min_first, min_second) = self.min_sizes;
+        let first = ((total as f32 * self.ratio).round() as u16)
+            .min(total.saturating_sub(min_second))
+            .max(min_first.min(total));
+        let second = total - first;
+
+        match self.direction {
+            Direction::Horizontal => (
+                Rect::new(area.x, area.y, first, area.height),
+                Rect::new(area.x + first, area.y, area.width.min(1), area.height),
+                Rect::new(area.x + first + 1, area.y, second, area.height),
+            ),
+            Direction::Vertical => (
+                Rect::new(area.x, area.y, area.width, first),
+                Rect::new(area.x, area.y + first, area.width, area.height.min(1)),
+                Rect::new(area.x, area.y + first + 1, area.width, second),
+            ),
+        }
+    }
+
+    /// Get the size of `area` along the split direction
+    fn length(&self, area: Rect) -> u16 {
+        match self.direction {
+            Direction::Horizontal => area.width,
+            Direction::Vertical => area.height,
+        }
+    }
+
+    /// Put the divider at `column`, `row`, as when dragging it
+    fn drag_to(&mut self, area: Rect, column: u16, row: u16) {
+        let (offset, total) = match self.direction {
+            Direction::Horizontal => (column.saturating_sub(area.x), area.width),
+            Direction::Vertical => (row.saturating_sub(area.y), area.height),
+        };
+        if total > 1 {
+            self.set_ratio(offset as f32 / (total - 1) as f32);
+        }
+    }
+}
+
+impl Component for Split {
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let (first, divider, second) = self.layout(area);
+
+        let style = if self.is_focused() {
+            self.get_style("split.divider")
+                .patch(self.get_style("split.divider.focused"))
+        } else {
+            self.get_style("split.divider")
+        };
+        let symbol = match self.direction {
+            Direction::Horizontal => "│",
+            Direction::Vertical => "─",
+        };
+        let buffer = f.buffer_mut();
+        for position in divider.positions() {
+            if let Some(cell) = buffer.cell_mut(position) {
+                cell.set_symbol(symbol).set_style(style);
+            }
+        }
+
+        for (name, pane) in [(&self.first, first), (&self.second, second)] {
+            if let Some(child) = self._ctx.children.get_mut(name.as_str()) {
+                child.set_area(pane);
+            }
+        }
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        if !self.is_focused() {
+            return None;
+        }
+
+        match (self.direction, key.code) {
+            (Direction::Horizontal, KeyCode::Left) | (Direction::Vertical, KeyCode::Up) => {
+                self.set_ratio(self.ratio - self.step)
+            }
+            (Direction::Horizontal, KeyCode::Right) | (Direction::Vertical, KeyCode::Down) => {
+                self.set_ratio(self.ratio + self.step)
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Option<Action> {
+        let area = self.area()?;
+
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let (_, divider, _) = self.layout(area);
+                self.dragging = divider.contains(Position::new(mouse.column, mouse.row));
+            }
+            MouseEventKind::Drag(MouseButton::Left) if self.dragging => {
+                self.drag_to(area, mouse.column, mouse.row);
+            }
+            MouseEventKind::Up(_) => self.dragging = false,
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Pane {
+        pub _ctx: ComponentContext,
+    }
+
+    impl_component_accessor!(Pane);
+
+    impl Component for Pane {
+        fn draw(&mut self, _f: &mut Frame<'_>, _area: Rect) {}
+    }
+
+    fn split() -> Split {
+        Split::new(
+            ("left", Box::new(Pane::default())),
+            ("right", Box::new(Pane::default())),
+        )
+    }
+
+    #[test]
+    fn test_layout_respects_ratio_and_min_sizes() {
+        let area = Rect::new(0, 0, 21, 5);
+
+        let (first, divider, second) = split().with_ratio(0.25).layout(area);
+        assert_eq!((first.width, divider.x, second.x, second.width), (5, 5, 6, 15));
+
+        let (first, _, second) = split().with_ratio(0.0).with_min_sizes(4, 8).layout(area);
+        assert_eq!((first.width, second.width), (4, 16));
+
+        let (first, _, second) = split().with_ratio(1.0).with_min_sizes(4, 8).layout(area);
+        assert_eq!((first.width, second.width), (12, 8));
+
+        let (first, divider, second) = split()
+            .with_direction(Direction::Vertical)
+            .layout(Rect::new(0, 0, 10, 9));
+        assert_eq!((first.height, divider.y, second.height), (4, 4, 4));
+    }
+
+    #[test]
+    fn test_keys_and_dragging_move_the_divider() {
+        let mut split = split().with_step(0.25);
+        split.set_area(Rect::new(0, 0, 11, 3));
+        let press = |split: &mut Split, code| split.handle_key_events(KeyEvent::from(code));
+
+        press(&mut split, KeyCode::Left);
+        assert_eq!(split.ratio(), 0.5);
+
+        split.set_focused(true);
+        press(&mut split, KeyCode::Left);
+        assert_eq!(split.ratio(), 0.25);
+        press(&mut split, KeyCode::Up);
+        assert_eq!(split.ratio(), 0.25);
+
+        let mouse = |kind, column| MouseEvent {
+            kind,
+            column,
+            row: 1,
+            modifiers: crossterm::event::KeyModifiers::NONE,
+        };
+        // The divider is at column 3 (a quarter of the 10 cells besides it)
+        split.handle_mouse_events(mouse(MouseEventKind::Down(MouseButton::Left), 3));
+        split.handle_mouse_events(mouse(MouseEventKind::Drag(MouseButton::Left), 8));
+        split.handle_mouse_events(mouse(MouseEventKind::Up(MouseButton::Left), 8));
+        assert_eq!(split.ratio(), 0.8);
+
+        // Dragging that didn't start on the divider is ignored
+        split.handle_mouse_events(mouse(MouseEventKind::Down(MouseButton::Left), 0));
+        split.handle_mouse_events(mouse(MouseEventKind::Drag(MouseButton::Left), 5));
+        assert_eq!(split.ratio(), 0.8);
+    }
+}
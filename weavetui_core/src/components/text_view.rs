@@ -0,0 +1,222 @@
+//! Scrollable, wrapped text display.
+
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
+use ratatui::{
+    layout::{Position, Rect},
+    text::{Span, Text},
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{event::Action, Component, ComponentAccessor, ComponentContext};
+
+/// Lines scrolled by one turn of the mouse wheel
+const WHEEL_LINES: usize = 3;
+
+/// A block of text, word-wrapped to its width by default and scrolled vertically.
+///
+/// While focused, up and down scroll by a line, page up and page down by a screenful, and
+/// home and end jump to the start and the end. The mouse wheel scrolls it while over it.
+/// Styled with the `text` theme style. [`Component::measure`] reports the wrapped height,
+/// so a parent can size the view to fit its text.
+#[derive(Debug)]
+pub struct TextView {
+    text: String,
+    wrap: bool,
+    scroll: usize,
+    /// Size of the area from the last draw, used to clamp scrolling
+    viewport: (u16, u16),
+    pub _ctx: ComponentContext,
+}
+
+impl_component_accessor!(TextView);
+
+impl TextView {
+    /// Create a word-wrapping view of `text`, scrolled to the top
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            wrap: true,
+            scroll: 0,
+            viewport: (0, 0),
+            _ctx: ComponentContext::default(),
+        }
+    }
+
+    /// Wrap long lines at word boundaries, or cut them off at the edge (chainable)
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Get the text
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replace the text, keeping the scroll position where the new text allows
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.scroll_to(self.scroll);
+    }
+
+    /// Get the index of the first line in view
+    pub fn scroll_offset(&self) -> usize {
+        self.scroll
+    }
+
+    /// Scroll so `line` is the first in view, stopping once the last line is at the bottom
+    pub fn scroll_to(&mut self, line: usize) {
+        let (_, height) = self.viewport;
+        self.scroll = line.min(self.line_count().saturating_sub(height as usize));
+    }
+
+    /// Count the lines as drawn at the width of the last draw, or the text's own lines
+    /// before the first draw
+    pub fn line_count(&self) -> usize {
+        match self.viewport {
+            (0, _) => self.text.lines().count(),
+            (width, _) => self.wrapped_line_count(width),
+        }
+    }
+
+    /// Count the lines the text takes at `width`
+    fn wrapped_line_count(&self, width: u16) -> usize {
+        if width == 0 {
+            return 0;
+        }
+        if !self.wrap {
+            return self.text.lines().count();
+        }
+        self.text
+            .lines()
+            .map(|line| wrapped_rows(line, width as usize))
+            .sum()
+    }
+
+    fn paragraph(&self) -> Paragraph<'_> {
+        let paragraph = Paragraph::new(Text::raw(self.text.as_str()));
+        if self.wrap {
+            paragraph.wrap(Wrap { trim: false })
+        } else {
+            paragraph
+        }
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        self.scroll_to(self.scroll.saturating_add_signed(delta));
+    }
+}
+
+/// Count the rows a line takes when word-wrapped to `width`, like the paragraph drawing it.
+///
+/// A word that doesn't fit moves to the next row, dropping the space before it, and a word
+/// wider than a row is split across rows.
+fn wrapped_rows(line: &str, width: usize) -> usize {
+    let mut rows = 1;
+    let mut column = 0;
+    for (index, word) in line.split(' ').enumerate() {
+        let word = Span::raw(word).width();
+        let space = usize::from(index > 0);
+        if column + space + word <= width {
+            column += space + word;
+            continue;
+        }
+        if word == 0 {
+            continue;
+        }
+        if column > 0 {
+            rows += 1;
+        }
+        rows += (word - 1) / width;
+        column = (word - 1) % width + 1;
+    }
+    rows
+}
+
+impl Component for TextView {
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn measure(&self, width: u16) -> Option<u16> {
+        Some(self.wrapped_line_count(width).min(u16::MAX as usize) as u16)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        self.viewport = (area.width, area.height);
+        self.scroll_to(self.scroll);
+
+        let paragraph = self
+            .paragraph()
+            .style(self.get_style("text"))
+            .scroll((self.scroll.min(u16::MAX as usize) as u16, 0));
+        f.render_widget(paragraph, area);
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        if !self.is_focused() {
+            return None;
+        }
+
+        let page = self.viewport.1.max(1) as isize;
+        match key.code {
+            KeyCode::Up => self.scroll_by(-1),
+            KeyCode::Down => self.scroll_by(1),
+            KeyCode::PageUp => self.scroll_by(-page),
+            KeyCode::PageDown => self.scroll_by(page),
+            KeyCode::Home => self.scroll_to(0),
+            KeyCode::End => self.scroll_to(usize::MAX),
+            _ => {}
+        }
+        None
+    }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Option<Action> {
+        let position = Position::new(mouse.column, mouse.row);
+        if !self.area().is_some_and(|area| area.contains(position)) {
+            return None;
+        }
+
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.scroll_by(-(WHEEL_LINES as isize)),
+            MouseEventKind::ScrollDown => self.scroll_by(WHEEL_LINES as isize),
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_wraps_measures_and_scrolls() {
+        let view = TextView::new("one two three four\nfive");
+        assert_eq!(view.measure(14), Some(3));
+        assert_eq!(view.with_wrap(false).measure(14), Some(2));
+        // Words wider than the view are split across rows
+        assert_eq!(TextView::new("abcdefghij k").measure(4), Some(3));
+        assert_eq!(TextView::new("ab cd").measure(0), Some(0));
+
+        let mut view = TextView::new("one two three four\nfive");
+        view.set_focused(true);
+        let mut terminal = Terminal::new(TestBackend::new(14, 2)).unwrap();
+        terminal.draw(|f| view.draw(f, f.area())).unwrap();
+        assert_eq!(view.line_count(), 3);
+
+        view.handle_key_events(KeyEvent::from(KeyCode::End));
+        assert_eq!(view.scroll_offset(), 1);
+        terminal.draw(|f| view.draw(f, f.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+        let row = |y| (0..14).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        assert_eq!(row(0).trim_end(), "four");
+        assert_eq!(row(1).trim_end(), "five");
+
+        // Shorter text pulls the scroll position back
+        view.set_text("short");
+        assert_eq!(view.scroll_offset(), 0);
+    }
+}
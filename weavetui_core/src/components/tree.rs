@@ -0,0 +1,467 @@
+//! Tree component for hierarchical, collapsible lists.
+
+use std::{collections::HashSet, fmt};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    text::Line,
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::{
+    event::{Action, Message},
+    theme::ColorRole,
+    Component, ComponentAccessor, ComponentContext,
+};
+
+/// A node in a [`Tree`], with its children.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeNode {
+    pub label: String,
+    pub children: Vec<TreeNode>,
+    /// Data identifying the node, e.g. a file path; sent when the node is chosen
+    pub payload: Option<String>,
+    /// Whether the children are yet to be loaded by the tree's loader
+    pub lazy: bool,
+}
+
+impl TreeNode {
+    /// Create a node without children
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            children: Vec::new(),
+            payload: None,
+            lazy: false,
+        }
+    }
+
+    /// Set the children (chainable)
+    pub fn with_children(mut self, children: Vec<TreeNode>) -> Self {
+        self.children = children;
+        self
+    }
+
+    /// Set the payload (chainable)
+    pub fn with_payload(mut self, payload: &str) -> Self {
+        self.payload = Some(payload.to_string());
+        self
+    }
+
+    /// Load the children with the tree's loader the first time the node is expanded (chainable)
+    pub fn lazy(mut self) -> Self {
+        self.lazy = true;
+        self
+    }
+
+    /// Check if the node can be expanded, i.e. it has or will load children
+    pub fn is_branch(&self) -> bool {
+        self.lazy || !self.children.is_empty()
+    }
+
+    /// Get what identifies the node among its siblings: the payload, or else the label
+    fn key(&self) -> &str {
+        self.payload.as_deref().unwrap_or(&self.label)
+    }
+}
+
+type Loader = Box<dyn FnMut(&TreeNode) -> Vec<TreeNode>>;
+
+/// A collapsible tree of labelled nodes, e.g. for a file explorer or an outline.
+///
+/// While focused, up and down move the cursor over the visible nodes. Right expands the node
+/// under the cursor or moves into it, left collapses it or moves to its parent. Enter or space
+/// expands and collapses branches; on a leaf it sends an `Action::Message` with the tree's
+/// event as the topic and the node's payload (or label) as the payload.
+///
+/// Nodes marked [`TreeNode::lazy`] get their children from the loader set with
+/// [`Tree::with_loader`] when first expanded. Expansion is remembered by the payloads (or
+/// labels) of a node and its ancestors, so it survives [`Tree::set_nodes`] with fresh data.
+/// Nodes are styled with `tree`; the cursor row is patched with `tree.selected` (reversed
+/// by default) while the tree has focus.
+pub struct Tree {
+    nodes: Vec<TreeNode>,
+    /// Keys of the expanded nodes, from the root down
+    expanded: HashSet<Vec<String>>,
+    /// Index of the selected node at each level, outermost first
+    selected: Vec<usize>,
+    /// First visible row, scrolled to keep the selection in view
+    offset: usize,
+    event: String,
+    loader: Option<Loader>,
+    pub _ctx: ComponentContext,
+}
+
+impl fmt::Debug for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tree")
+            .field("nodes", &self.nodes)
+            .field("expanded", &self.expanded)
+            .field("selected", &self.selected)
+            .field("event", &self.event)
+            .field("loader", &self.loader.as_ref().map(|_| "<loader>"))
+            .finish()
+    }
+}
+
+impl_component_accessor!(Tree);
+
+impl Tree {
+    /// Create a collapsed tree that sends `tree:selected` when a leaf is chosen
+    pub fn new(nodes: Vec<TreeNode>) -> Self {
+        let selected = if nodes.is_empty() { vec![] } else { vec![0] };
+        Self {
+            nodes,
+            expanded: HashSet::new(),
+            selected,
+            offset: 0,
+            event: "tree:selected".to_string(),
+            loader: None,
+            _ctx: ComponentContext::default(),
+        }
+    }
+
+    /// Set the message topic sent when a leaf is chosen (chainable)
+    pub fn with_event(mut self, event: &str) -> Self {
+        self.event = event.to_string();
+        self
+    }
+
+    /// Set the callback loading the children of lazy nodes (chainable)
+    pub fn with_loader(mut self, loader: impl FnMut(&TreeNode) -> Vec<TreeNode> + 'static) -> Self {
+        self.loader = Some(Box::new(loader));
+        self
+    }
+
+    /// Get the top-level nodes
+    pub fn nodes(&self) -> &[TreeNode] {
+        &self.nodes
+    }
+
+    /// Replace the nodes, keeping the expansion of nodes that are still there.
+    ///
+    /// The selection stays at the same position if it still exists, or goes back to the
+    /// first node.
+    pub fn set_nodes(&mut self, nodes: Vec<TreeNode>) {
+        self.nodes = nodes;
+        if self.node(&self.selected).is_none() || !self.visible().contains(&self.selected) {
+            self.selected = if self.nodes.is_empty() { vec![] } else { vec![0] };
+        }
+    }
+
+    /// Get the index of the selected node at each level, outermost first
+    pub fn selected_path(&self) -> &[usize] {
+        &self.selected
+    }
+
+    /// Get the selected node
+    pub fn selected(&self) -> Option<&TreeNode> {
+        self.node(&self.selected)
+    }
+
+    /// Check if the node at `path` is expanded
+    pub fn is_expanded(&self, path: &[usize]) -> bool {
+        self.keys(path).is_some_and(|keys| self.expanded.contains(&keys))
+    }
+
+    /// Expand the node at `path`, loading its children if it's lazy
+    pub fn expand(&mut self, path: &[usize]) {
+        let Some(keys) = self.keys(path) else {
+            return;
+        };
+
+        let node = node_mut(&mut self.nodes, path);
+        if let (Some(loader), Some(node)) = (self.loader.as_mut(), node) {
+            if node.lazy {
+                node.children = loader(node);
+                node.lazy = false;
+            }
+        }
+        if self.node(path).is_some_and(TreeNode::is_branch) {
+            self.expanded.insert(keys);
+        }
+    }
+
+    /// Collapse the node at `path`, moving the selection up to it if it was inside
+    pub fn collapse(&mut self, path: &[usize]) {
+        if let Some(keys) = self.keys(path) {
+            self.expanded.remove(&keys);
+        }
+        if self.selected.len() > path.len() && self.selected.starts_with(path) {
+            self.selected.truncate(path.len());
+        }
+    }
+
+    /// Expand every loaded branch. Lazy nodes aren't loaded, so they stay collapsed.
+    pub fn expand_all(&mut self) {
+        let mut keys = Vec::new();
+        expand_loaded(&self.nodes, &mut keys, &mut self.expanded);
+    }
+
+    /// Collapse every node, moving the selection to its top-level ancestor
+    pub fn collapse_all(&mut self) {
+        self.expanded.clear();
+        self.selected.truncate(1);
+    }
+
+    fn node(&self, path: &[usize]) -> Option<&TreeNode> {
+        let (&first, rest) = path.split_first()?;
+        let mut node = self.nodes.get(first)?;
+        for &index in rest {
+            node = node.children.get(index)?;
+        }
+        Some(node)
+    }
+
+    /// Get the keys of the node at `path` and its ancestors, from the root down
+    fn keys(&self, path: &[usize]) -> Option<Vec<String>> {
+        (1..=path.len())
+            .map(|depth| self.node(&path[..depth]).map(|node| node.key().to_string()))
+            .collect()
+    }
+
+    /// Get the paths of the nodes not hidden in a collapsed parent, in display order
+    fn visible(&self) -> Vec<Vec<usize>> {
+        let mut rows = Vec::new();
+        self.collect_visible(&self.nodes, &mut Vec::new(), &mut Vec::new(), &mut rows);
+        rows
+    }
+
+    fn collect_visible(
+        &self,
+        nodes: &[TreeNode],
+        path: &mut Vec<usize>,
+        keys: &mut Vec<String>,
+        rows: &mut Vec<Vec<usize>>,
+    ) {
+        for (index, node) in nodes.iter().enumerate() {
+            path.push(index);
+            keys.push(node.key().to_string());
+            rows.push(path.clone());
+            if self.expanded.contains(keys) {
+                self.collect_visible(&node.children, path, keys, rows);
+            }
+            keys.pop();
+            path.pop();
+        }
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let rows = self.visible();
+        if let Some(row) = rows.iter().position(|path| *path == self.selected) {
+            let row = (row as isize + delta).clamp(0, rows.len() as isize - 1);
+            self.selected = rows[row as usize].clone();
+        }
+    }
+
+    /// Expand or collapse the selected branch, or choose the selected leaf
+    fn activate(&mut self) -> Option<Action> {
+        let node = self.selected()?;
+        if !node.is_branch() {
+            let payload = node.key().to_string();
+            return Some(Action::Message(
+                Message::new(&self.event)
+                    .with_payload(&payload)
+                    .with_source(&self.name()),
+            ));
+        }
+
+        let path = self.selected.clone();
+        if self.is_expanded(&path) {
+            self.collapse(&path);
+        } else {
+            self.expand(&path);
+        }
+        None
+    }
+
+    fn right(&mut self) {
+        let path = self.selected.clone();
+        if !self.is_expanded(&path) {
+            self.expand(&path);
+        } else if self.selected().is_some_and(|node| !node.children.is_empty()) {
+            self.selected.push(0);
+        }
+    }
+
+    fn left(&mut self) {
+        let path = self.selected.clone();
+        if self.is_expanded(&path) {
+            self.collapse(&path);
+        } else if self.selected.len() > 1 {
+            self.selected.pop();
+        }
+    }
+}
+
+impl Component for Tree {
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let style = self.get_style("tree");
+        let selected_style = self
+            .get_theme_manager()
+            .highlight(ColorRole::Selection)
+            .patch(self.compose_style(&["tree", "tree.selected"]));
+
+        let rows = self.visible();
+        let height = area.height as usize;
+        if let Some(row) = rows.iter().position(|path| *path == self.selected) {
+            if row < self.offset {
+                self.offset = row;
+            } else if height > 0 && row >= self.offset + height {
+                self.offset = row + 1 - height;
+            }
+        }
+
+        let lines = rows
+            .iter()
+            .skip(self.offset)
+            .take(height)
+            .filter_map(|path| {
+                let node = self.node(path)?;
+                let marker = match (node.is_branch(), self.is_expanded(path)) {
+                    (false, _) => "  ",
+                    (true, true) => "▾ ",
+                    (true, false) => "▸ ",
+                };
+                let indent = "  ".repeat(path.len() - 1);
+                let line = Line::from(format!("{}{}{}", indent, marker, node.label));
+                Some(if self.is_focused() && *path == self.selected {
+                    line.style(selected_style)
+                } else {
+                    line.style(style)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        f.render_widget(Paragraph::new(lines), area);
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        if !self.is_focused() {
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Up => self.move_cursor(-1),
+            KeyCode::Down => self.move_cursor(1),
+            KeyCode::Right => self.right(),
+            KeyCode::Left => self.left(),
+            KeyCode::Char(' ') | KeyCode::Enter => return self.activate(),
+            _ => {}
+        }
+        None
+    }
+}
+
+fn node_mut<'a>(nodes: &'a mut [TreeNode], path: &[usize]) -> Option<&'a mut TreeNode> {
+    let (&first, rest) = path.split_first()?;
+    let mut node = nodes.get_mut(first)?;
+    for &index in rest {
+        node = node.children.get_mut(index)?;
+    }
+    Some(node)
+}
+
+/// Add the keys of every branch with loaded children under `keys` to `expanded`
+fn expand_loaded(nodes: &[TreeNode], keys: &mut Vec<String>, expanded: &mut HashSet<Vec<String>>) {
+    for node in nodes {
+        if node.children.is_empty() {
+            continue;
+        }
+        keys.push(node.key().to_string());
+        expanded.insert(keys.clone());
+        expand_loaded(&node.children, keys, expanded);
+        keys.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(tree: &mut Tree, code: KeyCode) -> Option<Action> {
+        tree.handle_key_events(KeyEvent::from(code))
+    }
+
+    fn sample_tree() -> Tree {
+        let mut tree = Tree::new(vec![
+            TreeNode::new("src").with_children(vec![
+                TreeNode::new("main.rs").with_payload("src/main.rs"),
+                TreeNode::new("lib.rs").with_payload("src/lib.rs"),
+            ]),
+            TreeNode::new("target").lazy(),
+        ])
+        .with_loader(|node| vec![TreeNode::new(&format!("{}/debug", node.label))]);
+        tree.set_focused(true);
+        tree
+    }
+
+    #[test]
+    fn test_navigation_expands_and_chooses_leaves() {
+        let mut tree = sample_tree();
+        press(&mut tree, KeyCode::Right);
+        assert!(tree.is_expanded(&[0]));
+        press(&mut tree, KeyCode::Right);
+        press(&mut tree, KeyCode::Down);
+        assert_eq!(tree.selected_path(), &[0, 1]);
+
+        let action = press(&mut tree, KeyCode::Enter).unwrap();
+        let Action::Message(message) = action else {
+            panic!("expected a message, got {:?}", action);
+        };
+        assert_eq!(message.topic, "tree:selected");
+        assert_eq!(message.payload, "src/lib.rs");
+
+        // Left goes to the parent, then collapses it
+        press(&mut tree, KeyCode::Left);
+        assert_eq!(tree.selected_path(), &[0]);
+        press(&mut tree, KeyCode::Left);
+        assert!(!tree.is_expanded(&[0]));
+        press(&mut tree, KeyCode::Down);
+        assert_eq!(tree.selected().unwrap().label, "target");
+    }
+
+    #[test]
+    fn test_lazy_children_load_once_and_expansion_survives_new_data() {
+        let mut tree = sample_tree();
+        tree.expand_all();
+        assert!(tree.is_expanded(&[0]));
+        assert!(!tree.is_expanded(&[1]));
+
+        tree.expand(&[1]);
+        assert_eq!(tree.nodes()[1].children, [TreeNode::new("target/debug")]);
+        assert!(!tree.nodes()[1].lazy);
+
+        tree.set_nodes(vec![
+            TreeNode::new("docs").with_children(vec![TreeNode::new("intro.md")]),
+            TreeNode::new("src").with_children(vec![TreeNode::new("main.rs")]),
+        ]);
+        assert!(!tree.is_expanded(&[0]));
+        assert!(tree.is_expanded(&[1]));
+
+        tree.collapse_all();
+        assert!(!tree.is_expanded(&[1]));
+    }
+
+    #[test]
+    fn test_draws_indented_rows_with_markers() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut tree = sample_tree();
+        tree.expand(&[0]);
+        let mut terminal = Terminal::new(TestBackend::new(16, 4)).unwrap();
+        terminal.draw(|f| tree.draw(f, f.area())).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y| (0..16).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        assert_eq!(row(0).trim_end(), "▾ src");
+        assert_eq!(row(1).trim_end(), "    main.rs");
+        assert_eq!(row(3).trim_end(), "▸ target");
+    }
+}
@@ -0,0 +1,274 @@
+//! Virtual list drawing only the rows in view.
+
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
+use ratatui::{
+    layout::{Position, Rect},
+    Frame,
+};
+
+use crate::{
+    event::{Action, Message},
+    theme::ColorRole,
+    Component, ComponentAccessor, ComponentContext,
+};
+
+/// Rows moved by one turn of the mouse wheel
+const WHEEL_ROWS: usize = 3;
+
+type RenderRow = Box<dyn FnMut(&mut Frame<'_>, usize, Rect)>;
+
+/// A scrolling list of any number of rows, drawn by a callback instead of child components.
+///
+/// Only the rows in view are drawn, each by calling the render callback with the row's index
+/// and area, so a list of a million rows costs the same as one of a screenful. The data
+/// stays with the app: the viewport only knows the row count, the scroll offset and the
+/// selected index.
+///
+/// While focused, up and down move the selection, page up and page down move it by a
+/// screenful, and home and end jump to the first and last row. The mouse wheel moves it over
+/// the viewport. Enter sends an `Action::Message` with the viewport's event as the topic and
+/// the selected index as the payload. The selected row is patched with `viewport.selected`
+/// (reversed by default) after the callback draws it, while the viewport has focus.
+pub struct Viewport {
+    total_rows: usize,
+    row_height: u16,
+    offset: usize,
+    selected: Option<usize>,
+    /// Rows that fit in the area, from the last draw
+    page: usize,
+    event: String,
+    render: RenderRow,
+    pub _ctx: ComponentContext,
+}
+
+impl fmt::Debug for Viewport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Viewport")
+            .field("total_rows", &self.total_rows)
+            .field("row_height", &self.row_height)
+            .field("offset", &self.offset)
+            .field("selected", &self.selected)
+            .field("event", &self.event)
+            .field("render", &"<fn>")
+            .finish()
+    }
+}
+
+impl_component_accessor!(Viewport);
+
+impl Viewport {
+    /// Create a viewport over `total_rows` one-row-high rows, drawn by `render`, with the
+    /// first row selected. Enter sends `viewport:selected`.
+    pub fn new(
+        total_rows: usize,
+        render: impl FnMut(&mut Frame<'_>, usize, Rect) + 'static,
+    ) -> Self {
+        Self {
+            total_rows,
+            row_height: 1,
+            offset: 0,
+            selected: (total_rows > 0).then_some(0),
+            page: 0,
+            event: "viewport:selected".to_string(),
+            render: Box::new(render),
+            _ctx: ComponentContext::default(),
+        }
+    }
+
+    /// Set how many lines each row takes (chainable)
+    pub fn with_row_height(mut self, height: u16) -> Self {
+        self.row_height = height.max(1);
+        self
+    }
+
+    /// Set the message topic sent when a row is chosen with enter (chainable)
+    pub fn with_event(mut self, event: &str) -> Self {
+        self.event = event.to_string();
+        self
+    }
+
+    /// Get the number of rows
+    pub fn total_rows(&self) -> usize {
+        self.total_rows
+    }
+
+    /// Change the number of rows, e.g. after data was loaded, keeping the selection in range
+    pub fn set_total_rows(&mut self, total_rows: usize) {
+        self.total_rows = total_rows;
+        let last = total_rows.checked_sub(1);
+        self.selected = match (self.selected, last) {
+            (_, None) => None,
+            (None, Some(_)) => Some(0),
+            (Some(selected), Some(last)) => Some(selected.min(last)),
+        };
+        self.offset = self.offset.min(total_rows.saturating_sub(self.page.max(1)));
+    }
+
+    /// Get the index of the first row in view
+    pub fn scroll_offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Scroll so row `offset` is the first in view, leaving the selection where it is
+    pub fn scroll_to(&mut self, offset: usize) {
+        self.offset = offset.min(self.total_rows.saturating_sub(1));
+    }
+
+    /// Get the index of the selected row, `None` when there are no rows
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Select a row, clamped to the last one, and scroll it into view
+    pub fn select(&mut self, index: usize) {
+        if self.total_rows == 0 {
+            return;
+        }
+        let index = index.min(self.total_rows - 1);
+        self.selected = Some(index);
+        if index < self.offset {
+            self.offset = index;
+        } else if self.page > 0 && index >= self.offset + self.page {
+            self.offset = index + 1 - self.page;
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if let Some(selected) = self.selected {
+            self.select(selected.saturating_add_signed(delta));
+        }
+    }
+}
+
+impl Component for Viewport {
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        self.page = (area.height / self.row_height) as usize;
+        if let Some(selected) = self.selected {
+            self.select(selected);
+        }
+
+        let selected_style = self
+            .get_theme_manager()
+            .highlight(ColorRole::Selection)
+            .patch(self.compose_style(&["viewport", "viewport.selected"]));
+        let last = self.total_rows.min(self.offset + self.page);
+        for (row, index) in (self.offset..last).enumerate() {
+            let row_area = Rect {
+                y: area.y + row as u16 * self.row_height,
+                height: self.row_height,
+                ..area
+            };
+            (self.render)(f, index, row_area);
+            if self.is_focused() && self.selected == Some(index) {
+                f.buffer_mut().set_style(row_area, selected_style);
+            }
+        }
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        if !self.is_focused() {
+            return None;
+        }
+
+        let page = self.page.max(1) as isize;
+        match key.code {
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-page),
+            KeyCode::PageDown => self.move_selection(page),
+            KeyCode::Home => self.select(0),
+            KeyCode::End => self.select(usize::MAX),
+            KeyCode::Enter => {
+                let selected = self.selected?;
+                return Some(Action::Message(
+                    Message::new(&self.event)
+                        .with_payload(&selected.to_string())
+                        .with_source(&self.name()),
+                ));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Option<Action> {
+        let position = Position::new(mouse.column, mouse.row);
+        if !self.area().is_some_and(|area| area.contains(position)) {
+            return None;
+        }
+
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.move_selection(-(WHEEL_ROWS as isize)),
+            MouseEventKind::ScrollDown => self.move_selection(WHEEL_ROWS as isize),
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{
+        backend::TestBackend,
+        style::{Modifier, Style},
+        Terminal,
+    };
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn test_only_visible_rows_are_rendered() {
+        let rendered = Rc::new(RefCell::new(Vec::new()));
+        let log = rendered.clone();
+        let mut viewport = Viewport::new(1_000_000, move |f, index, area| {
+            log.borrow_mut().push(index);
+            f.buffer_mut().set_string(area.x, area.y, index.to_string(), Style::default());
+        });
+        viewport.set_focused(true);
+        let mut terminal = Terminal::new(TestBackend::new(10, 3)).unwrap();
+        terminal.draw(|f| viewport.draw(f, f.area())).unwrap();
+        assert_eq!(*rendered.borrow(), [0, 1, 2]);
+
+        // Moving past the bottom scrolls by one row
+        for _ in 0..3 {
+            viewport.handle_key_events(KeyEvent::from(KeyCode::Down));
+        }
+        assert_eq!((viewport.selected(), viewport.scroll_offset()), (Some(3), 1));
+
+        rendered.borrow_mut().clear();
+        viewport.handle_key_events(KeyEvent::from(KeyCode::End));
+        terminal.draw(|f| viewport.draw(f, f.area())).unwrap();
+        assert_eq!(*rendered.borrow(), [999_997, 999_998, 999_999]);
+
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer[(0, 2)].symbol(), "9");
+        assert!(buffer[(0, 2)].modifier.contains(Modifier::REVERSED));
+        assert!(!buffer[(0, 1)].modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn test_selection_follows_row_count_and_enter_sends_index() {
+        let mut viewport = Viewport::new(0, |_, _, _| {});
+        viewport.set_focused(true);
+        assert_eq!(viewport.selected(), None);
+        assert_eq!(viewport.handle_key_events(KeyEvent::from(KeyCode::Enter)), None);
+
+        viewport.set_total_rows(50);
+        viewport.select(40);
+        viewport.set_total_rows(10);
+        assert_eq!(viewport.selected(), Some(9));
+
+        let action = viewport.handle_key_events(KeyEvent::from(KeyCode::Enter)).unwrap();
+        let Action::Message(message) = action else {
+            panic!("expected a message, got {:?}", action);
+        };
+        assert_eq!(message.topic, "viewport:selected");
+        assert_eq!(message.payload, "9");
+    }
+}
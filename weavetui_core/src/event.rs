@@ -2,7 +2,13 @@
 
 use {
     crossterm::event::{KeyEvent, MouseEvent},
-    std::fmt::{Display, Formatter, Result},
+    std::{
+        any::Any,
+        fmt::{Debug, Display, Formatter, Result},
+        str::FromStr,
+        sync::Arc,
+        time::Duration,
+    },
     strum::EnumString,
 };
 
@@ -14,7 +20,189 @@ pub enum Action {
     Resize(u16, u16),
     Quit,
     AppAction(String),
+    /// An unbound key press, written like a keybinding (e.g. `ctrl-a`).
+    ///
+    /// Use `keyboard::key_from_action` to get the `KeyEvent` back.
     Key(String),
+    Message(Message),
+    /// Show a transient notification for `ttl`, drawn by the `Notifications` component
+    #[strum(disabled)]
+    Notify {
+        level: NotifyLevel,
+        text: String,
+        ttl: Duration,
+    },
+    /// A typed, app-defined action, e.g. a variant of an enum deriving `IntoAction`
+    #[strum(disabled)]
+    Custom(CustomAction),
+}
+
+impl Action {
+    /// How urgently the app handles this action within a run loop iteration.
+    ///
+    /// `Quit` is `Critical`; everything else is `Normal`, so actions are handled in the order
+    /// they were sent. Apps can raise individual app actions and message topics with
+    /// `App::with_action_priority`.
+    pub fn priority(&self) -> Priority {
+        match self {
+            Action::Quit => Priority::Critical,
+            _ => Priority::Normal,
+        }
+    }
+
+    /// Whether handling this action twice in a row has the same effect as handling it once.
+    ///
+    /// Repeats of idempotent actions in one batch are collapsed by the app. That's `Quit`,
+    /// `Render` and `Resize`; app actions and message topics can be marked idempotent with
+    /// `App::with_idempotent_action`, since the app can't know what they do.
+    pub fn is_idempotent(&self) -> bool {
+        matches!(self, Action::Quit | Action::Render | Action::Resize(..))
+    }
+
+    /// Resolve an action name from a keymap, e.g. `"quit"` or `"save"`.
+    ///
+    /// Built-in variant names are matched ignoring case, like `quit` and `render`; variants
+    /// with fields, like `resize`, get default values. Anything else becomes
+    /// `Action::AppAction` with the name, which an app can map to a typed action with
+    /// `App::register_action`.
+    pub fn resolve(name: &str) -> Self {
+        Action::from_str(name).unwrap_or_else(|_| Action::AppAction(name.to_string()))
+    }
+
+    /// Wrap an app-defined value in `Action::Custom`
+    pub fn custom<T: Any + Send + Sync + Debug + PartialEq>(value: T) -> Self {
+        Action::Custom(CustomAction::new(value))
+    }
+
+    /// Get the value of an `Action::Custom` if it's a `T`
+    pub fn as_custom<T: Any>(&self) -> Option<&T> {
+        match self {
+            Action::Custom(custom) => custom.downcast_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// An app-defined value carried by [`Action::Custom`].
+///
+/// Components get the value back with [`CustomAction::downcast_ref`] (or
+/// [`Action::as_custom`]) in `update`. Two custom actions are equal when they hold equal
+/// values of the same type.
+#[derive(Clone)]
+pub struct CustomAction(Arc<dyn CustomValue>);
+
+impl CustomAction {
+    /// Wrap a value
+    pub fn new<T: Any + Send + Sync + Debug + PartialEq>(value: T) -> Self {
+        Self(Arc::new(value))
+    }
+
+    /// Get the value if it's a `T`
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.0.as_any().downcast_ref()
+    }
+
+    /// Whether the value is a `T`
+    pub fn is<T: Any>(&self) -> bool {
+        self.0.as_any().is::<T>()
+    }
+}
+
+impl Debug for CustomAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.0.fmt(f)
+    }
+}
+
+impl PartialEq for CustomAction {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_dyn(other.0.as_any())
+    }
+}
+
+impl Eq for CustomAction {}
+
+/// Object-safe view of a custom action value
+trait CustomValue: Send + Sync + Debug {
+    fn as_any(&self) -> &dyn Any;
+
+    fn eq_dyn(&self, other: &dyn Any) -> bool;
+}
+
+impl<T: Any + Send + Sync + Debug + PartialEq> CustomValue for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_dyn(&self, other: &dyn Any) -> bool {
+        other.downcast_ref::<T>() == Some(self)
+    }
+}
+
+/// Processing order for queued actions, from least to most urgent.
+///
+/// Each run loop iteration batches higher priority actions before lower ones, keeping the
+/// order actions were sent within a priority.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    #[default]
+    Normal,
+    High,
+    /// Handled alone, ahead of everything else queued
+    Critical,
+}
+
+/// Severity of a notification, which picks its theme styles
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum NotifyLevel {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl NotifyLevel {
+    /// Lowercase name, used as the suffix of the level's theme style keys
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotifyLevel::Info => "info",
+            NotifyLevel::Success => "success",
+            NotifyLevel::Warning => "warning",
+            NotifyLevel::Error => "error",
+        }
+    }
+}
+
+/// A message between components, attributed to the component that sent it
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Message {
+    /// Name of the sending component; empty for messages from keybindings or the app
+    pub source: String,
+    pub topic: String,
+    pub payload: String,
+}
+
+impl Message {
+    /// Create a message with a topic and no payload or source
+    pub fn new(topic: &str) -> Self {
+        Self {
+            topic: topic.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Attach a payload (chainable)
+    pub fn with_payload(mut self, payload: &str) -> Self {
+        self.payload = payload.to_string();
+        self
+    }
+
+    /// Set the sending component's name (chainable)
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.source = source.to_string();
+        self
+    }
 }
 
 impl Display for Action {
@@ -25,13 +213,26 @@ impl Display for Action {
     }
 }
 
+/// Where an event being handled came from, see `ComponentContext::event_source`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EventSource {
+    /// Read from the terminal, or produced by the app loop like ticks
+    #[default]
+    Terminal,
+    /// Sent through an `EventInjector`, e.g. by a test or a signal handler
+    Injected,
+    /// Played back from a recording
+    Replay,
+}
+
 /// Events from terminal input and the app loop
 #[derive(Clone, Debug)]
 pub enum Event {
     Init,
     Quit,
     Error,
-    Tick,
+    /// A periodic tick; `count` starts at 1 and `elapsed` is measured from the loop's start
+    Tick { count: u64, elapsed: Duration },
     Render,
     FocusGained,
     FocusLost,
@@ -41,6 +242,73 @@ pub enum Event {
     Resize(u16, u16),
 }
 
+bitflags::bitflags! {
+    /// Kinds of events a component wants its handlers called for, see
+    /// `Component::subscribed_events`
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct EventMask: u8 {
+        const KEY = 1;
+        const MOUSE = 1 << 1;
+        const TICK = 1 << 2;
+        const RENDER = 1 << 3;
+        const PASTE = 1 << 4;
+        const RESIZE = 1 << 5;
+        /// `FocusGained` and `FocusLost`
+        const FOCUS = 1 << 6;
+    }
+}
+
+impl EventMask {
+    /// Get the kind of an event, or an empty mask for events every component gets, like
+    /// `Init` and `Quit`
+    pub fn of(event: &Event) -> Self {
+        match event {
+            Event::Key(_) => Self::KEY,
+            Event::Mouse(_) => Self::MOUSE,
+            Event::Tick { .. } => Self::TICK,
+            Event::Render => Self::RENDER,
+            Event::Paste(_) => Self::PASTE,
+            Event::Resize(..) => Self::RESIZE,
+            Event::FocusGained | Event::FocusLost => Self::FOCUS,
+            Event::Init | Event::Quit | Event::Error => Self::empty(),
+        }
+    }
+}
+
+/// Events matched by `App::with_event_bindings` to send an action
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventPattern {
+    FocusGained,
+    FocusLost,
+    /// Any paste
+    Paste,
+    /// Any resize
+    Resize,
+    /// A resize to less than this width or less than this height
+    ResizeBelow(u16, u16),
+    /// A resize to at least this width and at least this height
+    ResizeAtLeast(u16, u16),
+}
+
+impl EventPattern {
+    /// Returns whether `event` matches the pattern
+    pub fn matches(&self, event: &Event) -> bool {
+        match (self, event) {
+            (EventPattern::FocusGained, Event::FocusGained)
+            | (EventPattern::FocusLost, Event::FocusLost)
+            | (EventPattern::Paste, Event::Paste(_))
+            | (EventPattern::Resize, Event::Resize(..)) => true,
+            (EventPattern::ResizeBelow(w, h), Event::Resize(width, height)) => {
+                width < w || height < h
+            }
+            (EventPattern::ResizeAtLeast(w, h), Event::Resize(width, height)) => {
+                width >= w && height >= h
+            }
+            _ => false,
+        }
+    }
+}
+
 /// Action type for keybinding flexibility
 pub enum ActionKind {
     Stringified(String),
@@ -64,3 +332,13 @@ impl From<Action> for ActionKind {
         ActionKind::Full(a)
     }
 }
+
+impl From<ActionKind> for Action {
+    /// Resolve into an `Action` with [`Action::resolve`]
+    fn from(kind: ActionKind) -> Self {
+        match kind {
+            ActionKind::Full(action) => action,
+            ActionKind::Stringified(cmd) => Action::resolve(&cmd),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Geometry helpers for positioning components inside an area.
+
+use ratatui::layout::Rect;
+
+/// Place a `width` x `height` rect with its top-left corner at `anchor`,
+/// shifting it back inside `bounds` when it would overflow.
+///
+/// The size is clamped to `bounds`, so the result always fits.
+pub fn anchored_rect(anchor: (u16, u16), width: u16, height: u16, bounds: Rect) -> Rect {
+    let width = width.min(bounds.width);
+    let height = height.min(bounds.height);
+
+    let x = anchor
+        .0
+        .clamp(bounds.x, bounds.right().saturating_sub(width));
+    let y = anchor
+        .1
+        .clamp(bounds.y, bounds.bottom().saturating_sub(height));
+
+    Rect::new(x, y, width, height)
+}
+
+/// Center a `width` x `height` rect inside `bounds`, clamping its size to fit.
+pub fn centered_rect(width: u16, height: u16, bounds: Rect) -> Rect {
+    let width = width.min(bounds.width);
+    let height = height.min(bounds.height);
+
+    Rect::new(
+        bounds.x + (bounds.width - width) / 2,
+        bounds.y + (bounds.height - height) / 2,
+        width,
+        height,
+    )
+}
@@ -1,29 +1,173 @@
 //! Internal structures and types for component management.
 
-use std::collections::BTreeMap;
-use ratatui::layout::Rect;
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use ratatui::{buffer::Buffer, layout::Rect};
 use tokio::sync::mpsc::UnboundedSender;
-use crate::event::Action;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use crate::event::{Action, EventSource};
+use crate::rng::{Rng, SharedRng};
 use crate::theme::ThemeManager;
 use crate::Component;
 
 #[derive(Debug)]
 pub struct ComponentContext {
+    /// Name reported instead of the type name, e.g. for roots added with `App::add_component_named`
+    pub name: Option<String>,
     pub children: BTreeMap<String, Box<dyn Component>>,
     pub area: Option<Rect>,
+    /// Area the component was last drawn in, compared by [`Component::area_changed`]
+    pub drawn_area: Option<Rect>,
+    /// Whether `init` has run since the component was created or last reinitialized
+    pub initialized: bool,
+    /// Whether the component is part of an app's tree, see [`Component::on_mount`]
+    pub mounted: bool,
+    /// Cells from the last draw, reused while [`Component::cache_render`] is on
+    pub render_cache: Option<Buffer>,
     pub active: bool,
+    pub focused: bool,
     pub action_tx: Option<UnboundedSender<Action>>,
     pub theme_manager: ThemeManager,
+    pub local_state: LocalStateCache,
+    /// Cancelled when the app shuts down, stopping tasks started with [`ComponentContext::spawn`]
+    pub shutdown: CancellationToken,
+    /// Where the event currently being handled came from
+    pub event_source: EventSourceCell,
+    /// The app's random number generator, see [`ComponentContext::rng`]
+    pub shared_rng: SharedRng,
 }
 
 impl Default for ComponentContext {
     fn default() -> Self {
         Self {
+            name: None,
             children: BTreeMap::new(),
             area: None,
+            drawn_area: None,
+            initialized: false,
+            mounted: false,
+            render_cache: None,
             active: true,
+            focused: false,
             action_tx: None,
             theme_manager: ThemeManager::default(),
+            local_state: LocalStateCache::default(),
+            shutdown: CancellationToken::new(),
+            event_source: EventSourceCell::default(),
+            shared_rng: SharedRng::default(),
         }
     }
+}
+
+impl ComponentContext {
+    /// Lock the app's random number generator, e.g. to shuffle items or jitter an
+    /// animation.
+    ///
+    /// Use it instead of a thread-local generator so tests can fix the numbers with
+    /// `App::with_seed`. Don't hold the guard across an `.await` or while calling into other
+    /// components, which lock the same generator.
+    pub fn rng(&self) -> MutexGuard<'_, Rng> {
+        self.shared_rng.lock()
+    }
+
+    /// Run `future` in the background on the current tokio runtime, e.g. to load data
+    /// without blocking the event loop.
+    ///
+    /// The task is cancelled when the app shuts down: once [`App::run`](crate::app::App::run)
+    /// returns, the future is dropped at its next `.await` and the handle resolves to `None`.
+    /// A task that finishes first resolves to `Some` with the future's output. Cancellation
+    /// never interrupts code between two awaits, so a task blocking the thread keeps running.
+    ///
+    /// To deliver results, clone `action_tx` into the future and send an action from it;
+    /// the action is handled like any other on the next loop iteration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a tokio runtime.
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<Option<F::Output>>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let shutdown = self.shutdown.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown.cancelled() => None,
+                output = future => Some(output),
+            }
+        })
+    }
+}
+
+/// The source of the event the app is handling, shared by the app and every component.
+///
+/// Cloning the cell is cheap and every clone sees the same value. The app sets it before
+/// handing an event to components, so event hooks like `handle_key_events` can check
+/// whether a key was typed or injected, e.g. to skip animations during a replay. Outside
+/// event handling it reads `EventSource::Terminal`.
+#[derive(Clone, Debug, Default)]
+pub struct EventSourceCell(Arc<AtomicU8>);
+
+impl EventSourceCell {
+    /// Get the source of the event being handled
+    pub fn get(&self) -> EventSource {
+        match self.0.load(Ordering::Relaxed) {
+            1 => EventSource::Injected,
+            2 => EventSource::Replay,
+            _ => EventSource::Terminal,
+        }
+    }
+
+    /// Set the source of the event being handled
+    pub fn set(&self, source: EventSource) {
+        let value = match source {
+            EventSource::Terminal => 0,
+            EventSource::Injected => 1,
+            EventSource::Replay => 2,
+        };
+        self.0.store(value, Ordering::Relaxed);
+    }
+}
+
+/// Shared store for component-local state that outlives component instances.
+///
+/// Cloning the cache is cheap and every clone refers to the same storage, so one cache is
+/// shared by the whole component tree. State is stored under the key returned by
+/// `Component::local_state_key` and handed back when a component with the same key is
+/// inserted as a child again.
+#[derive(Clone, Default)]
+pub struct LocalStateCache(Arc<Mutex<HashMap<String, Box<dyn Any + Send>>>>);
+
+impl LocalStateCache {
+    /// Store state under a key, replacing anything stored before
+    pub fn insert(&self, key: String, state: Box<dyn Any + Send>) {
+        self.0.lock().unwrap().insert(key, state);
+    }
+
+    /// Remove and return the state stored under a key
+    pub fn take(&self, key: &str) -> Option<Box<dyn Any + Send>> {
+        self.0.lock().unwrap().remove(key)
+    }
+
+    /// Check if state is stored under a key
+    pub fn contains(&self, key: &str) -> bool {
+        self.0.lock().unwrap().contains_key(key)
+    }
+
+    /// Drop all stored state
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+}
+
+impl fmt::Debug for LocalStateCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.0.lock().unwrap();
+        f.debug_set().entries(state.keys()).finish()
+    }
 }
\ No newline at end of file
@@ -3,39 +3,134 @@
 use {
     super::event::{Action, ActionKind},
     crate::kb,
-    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
-    std::{collections::HashMap, str::FromStr},
+    crossterm::event::{KeyCode, KeyEvent, KeyEventState, KeyModifiers},
+    std::{
+        collections::HashMap,
+        fmt,
+        time::{Duration, Instant},
+    },
 };
 
+/// How a component responds to the keys offered to `Component::handle_key_sequence`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeySeqResult {
+    /// The sequence was complete and handled, so the buffer is cleared
+    Consumed,
+    /// The sequence is a valid prefix, so the keys stay buffered for the next key
+    Pending,
+    /// The sequence means nothing to the component, so the keys fall through to the keymap
+    Rejected,
+}
+
+/// Clear the lock and keypad bits that terminals speaking the kitty keyboard protocol set
+/// on key events, so keys match their bindings whether NumLock or CapsLock is on
+pub fn without_lock_state(mut key: KeyEvent) -> KeyEvent {
+    key.state
+        .remove(KeyEventState::NUM_LOCK | KeyEventState::CAPS_LOCK | KeyEventState::KEYPAD);
+    key
+}
+
+/// The keyboard layout the user types on, used to match `<phys:...>` bindings by position.
+///
+/// Terminals report the character a key produced, not which key was pressed, so the
+/// position is worked out from the character and the layout. Physical bindings name keys by
+/// where they sit on a US QWERTY keyboard: `<phys:w>` is the key above `s`, which types `z`
+/// on AZERTY. With the default `Qwerty` layout, physical bindings behave like plain ones.
+///
+/// A sequence with any `<phys:...>` key is matched by position as a whole, so `<phys:g><x>`
+/// is the same binding as `<phys:g><phys:x>`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyboardLayout {
+    #[default]
+    Qwerty,
+    Azerty,
+    Qwertz,
+    Dvorak,
+    Colemak,
+}
+
+/// The number row and the three letter rows of US QWERTY, ten keys each
+const QWERTY_KEYS: &str = "1234567890qwertyuiopasdfghjkl;zxcvbnm,./";
+
+impl KeyboardLayout {
+    /// Characters on the same keys as [`QWERTY_KEYS`]
+    fn keys(&self) -> &'static str {
+        match self {
+            KeyboardLayout::Qwerty => QWERTY_KEYS,
+            KeyboardLayout::Azerty => "&é\"'(-è_çàazertyuiopqsdfghjklmwxcvbn,;:!",
+            KeyboardLayout::Qwertz => "1234567890qwertzuiopasdfghjklöyxcvbnm,.-",
+            KeyboardLayout::Dvorak => "1234567890',.pyfgcrlaoeuidhtns;qjkxbmwvz",
+            KeyboardLayout::Colemak => "1234567890qwfpgjluy;arstdhneiozxcvbkm,./",
+        }
+    }
+
+    /// Get the key at the same position on US QWERTY as a key typed on this layout, to look
+    /// up `<phys:...>` bindings with [`KeyBindings::get_physical`].
+    ///
+    /// Returns `None` for keys that aren't characters on the main block of the layout.
+    pub fn physical(&self, key: &KeyEvent) -> Option<KeyEvent> {
+        let KeyCode::Char(c) = key.code else {
+            return None;
+        };
+        let position = self.keys().chars().position(|k| k == c.to_ascii_lowercase())?;
+        let c = QWERTY_KEYS.chars().nth(position)?;
+        Some(KeyEvent::new(KeyCode::Char(c), key.modifiers))
+    }
+}
+
+/// Key sequences bound to actions.
+///
+/// Sequences written with `<phys:...>` keys are kept apart from the others, by their
+/// position on US QWERTY, and looked up with [`KeyBindings::get_physical`].
 #[derive(Clone, Debug)]
-pub struct KeyBindings(pub HashMap<Vec<KeyEvent>, Action>);
+pub struct KeyBindings(
+    pub HashMap<Vec<KeyEvent>, Action>,
+    HashMap<Vec<KeyEvent>, Action>,
+);
 
 impl KeyBindings {
-    /// Create new keybindings from an array of key-action pairs
+    /// Create new keybindings from an array of key-action pairs.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the offending key if a key sequence can't be parsed. Use
+    /// [`KeyBindings::try_new`] for keymaps that aren't hardcoded, e.g. from a config file.
     pub fn new<const N: usize>(raw: [(&str, impl Into<ActionKind>); N]) -> Self {
-        let mut keybindings = HashMap::new();
+        Self::try_new(raw).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Create new keybindings from an array of key-action pairs, failing on the first key
+    /// sequence that can't be parsed
+    pub fn try_new<const N: usize>(
+        raw: [(&str, impl Into<ActionKind>); N],
+    ) -> Result<Self, KeyParseError> {
+        let mut keybindings = KeyBindings::empty();
         for (key_str, cmd) in raw.into_iter() {
             let cmd: ActionKind = cmd.into();
+            let (keys, physical) = parse_bound_sequence(key_str)?;
+            let bindings = if physical {
+                &mut keybindings.1
+            } else {
+                &mut keybindings.0
+            };
 
             match cmd {
                 ActionKind::Full(action) => {
-                    keybindings.insert(parse_key_sequence(key_str).unwrap(), action);
+                    bindings.insert(keys, action);
                 }
 
                 ActionKind::Stringified(cmd) => {
-                    let action = Action::from_str(&cmd);
-                    match action {
-                        Ok(action) => {
-                            keybindings.insert(parse_key_sequence(key_str).unwrap(), action)
-                        }
-                        Err(_) => keybindings
-                            .insert(parse_key_sequence(key_str).unwrap(), Action::AppAction(cmd)),
-                    };
+                    bindings.insert(keys, Action::resolve(&cmd));
                 }
             }
         }
 
-        KeyBindings(keybindings)
+        Ok(keybindings)
+    }
+
+    /// Create keybindings with nothing bound
+    pub fn empty() -> Self {
+        KeyBindings(HashMap::new(), HashMap::new())
     }
 
     /// Get the action for a key sequence
@@ -43,12 +138,124 @@ impl KeyBindings {
         self.0.get(key_events)
     }
 
+    /// Get the action for a sequence of physical keys, as given by
+    /// [`KeyboardLayout::physical`], bound with `<phys:...>`
+    pub fn get_physical(&self, key_events: &[KeyEvent]) -> Option<&Action> {
+        self.1.get(key_events)
+    }
+
+    /// Get every bound action, physical bindings included
+    pub fn actions(&self) -> impl Iterator<Item = &Action> {
+        self.0.values().chain(self.1.values())
+    }
+
     /// Merge another set of keybindings into this one
     pub fn extend(&mut self, other: KeyBindings) {
         self.0.extend(other.0);
+        self.1.extend(other.1);
+    }
+
+    /// Iterate over every binding with whether it's physical
+    fn entries(&self) -> impl Iterator<Item = (&Vec<KeyEvent>, &Action, bool)> {
+        let typed = self.0.iter().map(|(keys, action)| (keys, action, false));
+        typed.chain(self.1.iter().map(|(keys, action)| (keys, action, true)))
+    }
+
+    /// List the bindings for display, e.g. in a help screen.
+    ///
+    /// Entries are sorted by category, uncategorized ones first, then by key string, so the
+    /// order doesn't change between runs.
+    pub fn describe(&self) -> Vec<KeyBindingInfo> {
+        let mut infos = self
+            .entries()
+            .map(|(keys, action, physical)| KeyBindingInfo {
+                keys: sequence_string(keys, physical),
+                action: action.clone(),
+                category: action_category(action),
+            })
+            .collect::<Vec<_>>();
+        infos.sort_by(|a, b| (&a.category, &a.keys).cmp(&(&b.category, &b.keys)));
+        infos
+    }
+
+    /// List the keys that can follow `prefix`, each with the action of a sequence it leads
+    /// to, e.g. for a which-key popup after a leader key.
+    ///
+    /// A key starting several longer sequences is listed once per sequence. Entries are
+    /// sorted by their whole sequence, written like in keymaps, so keys finishing a sequence
+    /// come right before the longer ones they start. An empty prefix lists every binding.
+    pub fn completions(&self, prefix: &[KeyEvent]) -> Vec<(KeyEvent, &Action)> {
+        let mut completions = self
+            .0
+            .iter()
+            .filter(|(keys, _)| keys.len() > prefix.len() && keys.starts_with(prefix))
+            .map(|(keys, action)| (sequence_string(keys, false), keys[prefix.len()], action))
+            .collect::<Vec<_>>();
+        completions.sort_by(|a, b| a.0.cmp(&b.0));
+        completions
+            .into_iter()
+            .map(|(_, key, action)| (key, action))
+            .collect()
+    }
+
+    /// List bound key sequences starting with a key close to `key`, e.g. `<ctrl-j>` for
+    /// `j`, to suggest when `key` isn't bound.
+    ///
+    /// A key is close when it's the same key with other modifiers, or the same letter in the
+    /// other case. Sequences are written like in keymaps and sorted.
+    pub fn near_matches(&self, key: &KeyEvent) -> Vec<String> {
+        let mut matches = self
+            .entries()
+            .filter(|(keys, _, _)| keys.first().is_some_and(|first| is_near(first, key)))
+            .map(|(keys, _, physical)| sequence_string(keys, physical))
+            .collect::<Vec<_>>();
+        matches.sort();
+        matches
     }
 }
 
+/// Write a key sequence like in keymaps, e.g. `<g><g>`, marking the character keys of a
+/// physical binding with `phys:`
+fn sequence_string(keys: &[KeyEvent], physical: bool) -> String {
+    keys.iter()
+        .map(|key| match key.code {
+            KeyCode::Char(_) if physical => format!("<phys:{}>", key_event_to_string(key)),
+            _ => format!("<{}>", key_event_to_string(key)),
+        })
+        .collect()
+}
+
+/// Whether two different keys share a key code, ignoring modifiers and letter case
+fn is_near(a: &KeyEvent, b: &KeyEvent) -> bool {
+    let same_code = match (a.code, b.code) {
+        (KeyCode::Char(a), KeyCode::Char(b)) => a.eq_ignore_ascii_case(&b),
+        (a, b) => a == b,
+    };
+    same_code && (a.code, a.modifiers) != (b.code, b.modifiers)
+}
+
+/// A keybinding as listed by [`KeyBindings::describe`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyBindingInfo {
+    /// The key sequence, written like in keymaps, e.g. `<ctrl-s>` or `<g><g>`
+    pub keys: String,
+    /// The bound action
+    pub action: Action,
+    /// The group of the action, e.g. `todo` for `app:todo:add` or `todo:add`
+    pub category: Option<String>,
+}
+
+/// Get the category of an app action: its first segment, not counting an `app:` prefix
+fn action_category(action: &Action) -> Option<String> {
+    let Action::AppAction(cmd) = action else {
+        return None;
+    };
+    let cmd = cmd.strip_prefix("app:").unwrap_or(cmd);
+    cmd.split_once(':')
+        .map(|(category, _)| category.to_string())
+        .filter(|category| !category.is_empty())
+}
+
 impl Default for KeyBindings {
     /// Default keybindings with Ctrl-C to quit
     fn default() -> Self {
@@ -58,34 +265,134 @@ impl Default for KeyBindings {
     }
 }
 
+/// Bindings that fire when the same key is pressed twice in quick succession, like `<esc>`
+/// twice to quit.
+///
+/// A press completing a double tap isn't counted as the start of another, so three quick
+/// presses fire once.
+#[derive(Clone, Debug, Default)]
+pub struct DoubleTapBindings {
+    bindings: HashMap<KeyEvent, (Action, Duration)>,
+    last_press: Option<(KeyEvent, Instant)>,
+}
+
+impl DoubleTapBindings {
+    /// Fire `action` when `key` is pressed twice within `within`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` isn't a single valid key, e.g. `"<esc>"`.
+    pub fn bind_double(&mut self, key: &str, action: impl Into<ActionKind>, within: Duration) {
+        let keys = parse_key_sequence(key).unwrap_or_else(|err| panic!("{}", err));
+        let [key] = keys[..] else {
+            panic!("double tap binding \"{}\" must be a single key", key);
+        };
+        self.bindings.insert(key, (action.into().into(), within));
+    }
+
+    /// Get the bound actions
+    pub fn actions(&self) -> impl Iterator<Item = &Action> {
+        self.bindings.values().map(|(action, _)| action)
+    }
+
+    /// Whether no double taps are bound
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    /// Record a key press at `now`, returning the action if it completes a double tap
+    pub fn press(&mut self, key: KeyEvent, now: Instant) -> Option<Action> {
+        let Some((action, within)) = self.bindings.get(&key) else {
+            self.last_press = None;
+            return None;
+        };
+
+        match self.last_press.take() {
+            Some((last, at)) if last == key && now.duration_since(at) <= *within => {
+                Some(action.clone())
+            }
+            _ => {
+                self.last_press = Some((key, now));
+                None
+            }
+        }
+    }
+}
+
+/// Error from parsing a key sequence string, pointing at the key that failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyParseError {
+    /// The whole key sequence string
+    pub input: String,
+    /// The key that failed to parse, without angle brackets
+    pub token: String,
+    /// Position of the failing key in the sequence, starting at 0
+    pub index: usize,
+    /// Why the key was rejected
+    pub reason: &'static str,
+}
+
+impl fmt::Display for KeyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} \"<{}>\" at position {} in key sequence \"{}\"",
+            self.reason, self.token, self.index, self.input
+        )
+    }
+}
+
+impl std::error::Error for KeyParseError {}
+
 /// For internal use. Parses a string into a [`KeyEvent`].
 fn parse_key_event(raw: &str) -> Result<KeyEvent, std::io::Error> {
+    parse_bound_key(raw).map(|(key, _)| key)
+}
+
+/// For internal use. Parses a string into a [`KeyEvent`] and whether it's a physical key.
+///
+/// A `phys:` prefix, as in `phys:ctrl-w`, marks a character key as physical.
+fn parse_bound_key(raw: &str) -> Result<(KeyEvent, bool), std::io::Error> {
     let raw_lower = raw.to_ascii_lowercase();
-    let (remaining, modifiers) = extract_modifiers(&raw_lower);
-    parse_key_code_with_modifiers(remaining, modifiers)
+    let (raw_lower, physical) = match raw_lower.strip_prefix("phys:") {
+        Some(rest) => (rest, true),
+        None => (raw_lower.as_str(), false),
+    };
+    let (remaining, modifiers) = extract_modifiers(raw_lower);
+    let key = parse_key_code_with_modifiers(remaining, modifiers)?;
+    // Keys like arrows are in the same place on every layout
+    Ok((key, physical && matches!(key.code, KeyCode::Char(_))))
 }
 
+/// Modifier prefixes accepted in key strings, long forms first.
+///
+/// Every prefix ends with `-`, so a bare key like `c` is never mistaken for the `c-` prefix.
+const MODIFIER_PREFIXES: [(&str, KeyModifiers); 8] = [
+    ("ctrl-", KeyModifiers::CONTROL),
+    ("alt-", KeyModifiers::ALT),
+    ("shift-", KeyModifiers::SHIFT),
+    ("super-", KeyModifiers::SUPER),
+    ("c-", KeyModifiers::CONTROL),
+    ("a-", KeyModifiers::ALT),
+    ("s-", KeyModifiers::SHIFT),
+    ("m-", KeyModifiers::SUPER),
+];
+
 /// For internal use. Extracts the modifiers from a string formatted as `modifier-key`.
+///
+/// Accepts the long (`ctrl-`, `alt-`, `shift-`, `super-`) and short (`c-`, `a-`, `s-`, `m-`)
+/// prefixes in any combination.
 fn extract_modifiers(raw: &str) -> (&str, KeyModifiers) {
     let mut modifiers = KeyModifiers::empty();
     let mut current = raw;
 
-    loop {
-        match current {
-            rest if rest.starts_with("ctrl-") => {
-                modifiers.insert(KeyModifiers::CONTROL);
-                current = &rest[5..];
-            }
-            rest if rest.starts_with("alt-") => {
-                modifiers.insert(KeyModifiers::ALT);
-                current = &rest[4..];
-            }
-            rest if rest.starts_with("shift-") => {
-                modifiers.insert(KeyModifiers::SHIFT);
-                current = &rest[6..];
-            }
-            _ => break, // break out of the loop if no known prefix is detected
-        };
+    // Only strip a prefix when a key follows it, so `<c-->` is ctrl plus `-`
+    while let Some((prefix, modifier)) = MODIFIER_PREFIXES
+        .iter()
+        .find(|(prefix, _)| current.len() > prefix.len() && current.starts_with(prefix))
+    {
+        modifiers.insert(*modifier);
+        current = &current[prefix.len()..];
     }
 
     (current, modifiers)
@@ -147,7 +454,38 @@ fn parse_key_code_with_modifiers(
     Ok(KeyEvent::new(c, modifiers))
 }
 
-/// Convert a key event to its string format
+/// Modifiers in the order key strings are written with
+const MODIFIER_ORDER: [(&str, KeyModifiers); 4] = [
+    ("ctrl", KeyModifiers::CONTROL),
+    ("shift", KeyModifiers::SHIFT),
+    ("alt", KeyModifiers::ALT),
+    ("super", KeyModifiers::SUPER),
+];
+
+/// Write modifiers in canonical order, ctrl, shift, alt then super, joined by `-`, e.g.
+/// `ctrl-alt`. Empty for no modifiers.
+pub fn modifiers_to_string(modifiers: KeyModifiers) -> String {
+    MODIFIER_ORDER
+        .iter()
+        .filter(|(_, modifier)| modifiers.intersects(*modifier))
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Rewrite a key sequence in canonical form, e.g. `<a-c-x>` as `<ctrl-alt-x>`.
+///
+/// Modifiers may be written in any order and with short prefixes in keymaps, so two
+/// spellings of the same binding are only equal once canonicalized. Canonical form is what
+/// [`key_event_to_string`] writes, and parses back to the same keys.
+pub fn canonical_key_sequence(input: &str) -> Result<String, KeyParseError> {
+    parse_bound_sequence(input).map(|(keys, physical)| sequence_string(&keys, physical))
+}
+
+/// Convert a key event to its string format.
+///
+/// Modifiers come first in canonical order, ctrl, shift, alt then super, so each key has
+/// one spelling whichever order its binding was written in, e.g. `ctrl-shift-alt-x`.
 pub fn key_event_to_string(key_event: &KeyEvent) -> String {
     let char;
     let key_code = match key_event.code {
@@ -170,6 +508,11 @@ pub fn key_event_to_string(key_event: &KeyEvent) -> String {
             &char
         }
         KeyCode::Char(' ') => "space",
+        // Shifted letters arrive uppercase, but are spelled `shift-a` like in keybindings
+        KeyCode::Char(c) if key_event.modifiers.contains(KeyModifiers::SHIFT) => {
+            char = c.to_ascii_lowercase().to_string();
+            &char
+        }
         KeyCode::Char(c) => {
             char = c.to_string();
             &char
@@ -187,30 +530,7 @@ pub fn key_event_to_string(key_event: &KeyEvent) -> String {
         KeyCode::Modifier(_) => "",
     };
 
-    let mut modifiers = Vec::with_capacity(3);
-
-    if key_event.modifiers.intersects(KeyModifiers::CONTROL) {
-        modifiers.push("ctrl");
-    }
-
-    if key_event.modifiers.intersects(KeyModifiers::SHIFT) {
-        modifiers.push("shift");
-    }
-
-    if key_event.modifiers.intersects(KeyModifiers::ALT) {
-        modifiers.push("alt");
-    }
-
-    // if the modifiers is "shift" and the key code is a letter, we just return the letter
-    // otherwise we return the modifiers joined by a dash and the key code
-    if modifiers.len() == 1
-        && key_code.chars().count() == 1
-        && key_code.chars().all(char::is_alphabetic)
-    {
-        return key_code.to_string();
-    }
-
-    let mut key = modifiers.join("-");
+    let mut key = modifiers_to_string(key_event.modifiers);
 
     if !key.is_empty() {
         key.push('-');
@@ -221,13 +541,31 @@ pub fn key_event_to_string(key_event: &KeyEvent) -> String {
     key
 }
 
+/// Get the key event carried by an [`Action::Key`], as written by [`key_event_to_string`]
+pub fn key_from_action(action: &Action) -> Option<KeyEvent> {
+    match action {
+        Action::Key(key) => parse_key_event(key).ok(),
+        _ => None,
+    }
+}
+
 /// Parse a key sequence string like "<ctrl-c>" or "abc" into key events
-pub fn parse_key_sequence(raw: &str) -> Result<Vec<KeyEvent>, std::io::Error> {
+pub fn parse_key_sequence(input: &str) -> Result<Vec<KeyEvent>, KeyParseError> {
+    parse_bound_sequence(input).map(|(keys, _)| keys)
+}
+
+/// Parse a key sequence string into key events and whether any of them is a physical key
+fn parse_bound_sequence(input: &str) -> Result<(Vec<KeyEvent>, bool), KeyParseError> {
+    let error = |token: &str, index: usize, reason: &'static str| KeyParseError {
+        input: input.to_string(),
+        token: token.to_string(),
+        index,
+        reason,
+    };
+
+    let raw = input;
     if raw.chars().filter(|c| *c == '>').count() != raw.chars().filter(|c| *c == '<').count() {
-        return Err(std::io::Error::new(
-            std::io::ErrorKind::InvalidInput,
-            "Invalid key sequence",
-        ));
+        return Err(error(raw.trim_matches(['<', '>']), 0, "unbalanced angle brackets"));
     }
     let raw = if !raw.contains("><") {
         let raw = raw.strip_prefix('<').unwrap_or(raw);
@@ -248,5 +586,246 @@ pub fn parse_key_sequence(raw: &str) -> Result<Vec<KeyEvent>, std::io::Error> {
         })
         .collect::<Vec<_>>();
 
-    sequences.into_iter().map(parse_key_event).collect()
+    let keys = sequences
+        .into_iter()
+        .enumerate()
+        .map(|(index, token)| {
+            parse_bound_key(token).map_err(|_| error(token, index, "invalid key"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let physical = keys.iter().any(|(_, physical)| *physical);
+    Ok((keys.into_iter().map(|(key, _)| key).collect(), physical))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(raw: &str) -> KeyEvent {
+        parse_key_event(raw).unwrap()
+    }
+
+    #[test]
+    fn test_short_modifier_aliases_match_long_forms() {
+        for (short, long) in [
+            ("c-x", "ctrl-x"),
+            ("a-x", "alt-x"),
+            ("s-x", "shift-x"),
+            ("m-x", "super-x"),
+            ("c-a-enter", "ctrl-alt-enter"),
+        ] {
+            assert_eq!(key(short), key(long), "{short}");
+        }
+    }
+
+    #[test]
+    fn test_aliases_round_trip_to_canonical_form() {
+        for (alias, canonical) in [
+            ("c-enter", "ctrl-enter"),
+            ("a-left", "alt-left"),
+            ("s-tab", "shift-tab"),
+            ("m-f1", "super-f1"),
+            ("super-esc", "super-esc"),
+            ("c-s-up", "ctrl-shift-up"),
+        ] {
+            let event = key(alias);
+            assert_eq!(key_event_to_string(&event), canonical);
+            assert_eq!(key(canonical), event);
+        }
+    }
+
+    #[test]
+    fn test_modifier_order_is_canonical() {
+        let permutations = [
+            "ctrl-shift-alt-x",
+            "ctrl-alt-shift-x",
+            "shift-ctrl-alt-x",
+            "shift-alt-ctrl-x",
+            "alt-ctrl-shift-x",
+            "a-s-c-x",
+        ];
+        for raw in permutations {
+            assert_eq!(key(raw), key(permutations[0]), "{raw}");
+            assert_eq!(key_event_to_string(&key(raw)), "ctrl-shift-alt-x", "{raw}");
+        }
+
+        assert_eq!(
+            canonical_key_sequence("<alt-ctrl-x><m-s-up>").unwrap(),
+            "<ctrl-alt-x><shift-super-up>"
+        );
+        assert_eq!(canonical_key_sequence("<g><c-g>").unwrap(), "<g><ctrl-g>");
+        assert!(canonical_key_sequence("<ctrl-nope>").is_err());
+        assert_eq!(modifiers_to_string(KeyModifiers::SUPER | KeyModifiers::CONTROL), "ctrl-super");
+        assert_eq!(modifiers_to_string(KeyModifiers::NONE), "");
+    }
+
+    #[test]
+    fn test_key_actions_round_trip_with_modifiers() {
+        for event in [
+            KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL),
+            KeyEvent::new(KeyCode::Char('A'), KeyModifiers::SHIFT),
+            KeyEvent::new(KeyCode::Char('7'), KeyModifiers::ALT),
+        ] {
+            let action = Action::Key(key_event_to_string(&event));
+            assert_eq!(key_from_action(&action), Some(event), "{action:?}");
+        }
+
+        assert_eq!(key_event_to_string(&key("ctrl-a")), "ctrl-a");
+        assert_eq!(key_from_action(&Action::Quit), None);
+    }
+
+    #[test]
+    fn test_double_taps_fire_once_within_the_window() {
+        let mut taps = DoubleTapBindings::default();
+        taps.bind_double("<esc>", "quit", Duration::from_millis(300));
+        let esc = key("esc");
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert_eq!(taps.press(esc, at(0)), None);
+        assert_eq!(taps.press(esc, at(200)), Some(Action::Quit));
+        // A third quick press starts over instead of firing again
+        assert_eq!(taps.press(esc, at(300)), None);
+
+        // Too slow, so the second press becomes the first of a new double tap
+        assert_eq!(taps.press(esc, at(1000)), None);
+        assert_eq!(taps.press(esc, at(1100)), Some(Action::Quit));
+
+        // Another key in between breaks the double tap
+        assert_eq!(taps.press(esc, at(2000)), None);
+        taps.press(key("x"), at(2050));
+        assert_eq!(taps.press(esc, at(2100)), None);
+    }
+
+    #[test]
+    fn test_bare_keys_are_not_prefixes() {
+        assert_eq!(key("c"), KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE));
+        assert_eq!(key("m"), KeyEvent::new(KeyCode::Char('m'), KeyModifiers::NONE));
+        assert_eq!(key("c--"), KeyEvent::new(KeyCode::Char('-'), KeyModifiers::CONTROL));
+        assert!(parse_key_event("c-").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_the_failing_key() {
+        let err = parse_key_sequence("<ctrl-a><ctrl-foo>").unwrap_err();
+        assert_eq!((err.token.as_str(), err.index), ("ctrl-foo", 1));
+        assert_eq!(
+            err.to_string(),
+            "invalid key \"<ctrl-foo>\" at position 1 in key sequence \"<ctrl-a><ctrl-foo>\""
+        );
+
+        let err = parse_key_sequence("<ctrl-a").unwrap_err();
+        assert_eq!(err.reason, "unbalanced angle brackets");
+
+        assert!(KeyBindings::try_new([("<q>", "quit"), ("<hyper-x>", "app:x")]).is_err());
+        assert!(KeyBindings::try_new([("<q>", "quit")]).is_ok());
+    }
+
+    #[test]
+    fn test_describe_groups_and_sorts_bindings() {
+        let bindings = KeyBindings::new(kb![
+            "<q>" => "app:quit",
+            "<ctrl-n>" => "app:todo:add",
+            "<d><d>" => "app:todo:delete",
+            "<ctrl-s>" => "file:save",
+            "<ctrl-c>" => Action::Quit
+        ]);
+
+        let described = bindings
+            .describe()
+            .into_iter()
+            .map(|info| (info.category, info.keys))
+            .collect::<Vec<_>>();
+        let entry = |category: Option<&str>, keys: &str| {
+            (category.map(str::to_string), keys.to_string())
+        };
+        assert_eq!(
+            described,
+            [
+                entry(None, "<ctrl-c>"),
+                entry(None, "<q>"),
+                entry(Some("file"), "<ctrl-s>"),
+                entry(Some("todo"), "<ctrl-n>"),
+                entry(Some("todo"), "<d><d>"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_near_matches_differ_in_modifiers_or_case() {
+        let bindings = KeyBindings::new(kb![
+            "<ctrl-j>" => "app:down",
+            "<shift-j>" => "app:join",
+            "<j><k>" => "app:escape",
+            "<k>" => "app:up"
+        ]);
+
+        let j = KeyEvent::from(KeyCode::Char('j'));
+        assert_eq!(bindings.near_matches(&j), ["<ctrl-j>", "<shift-j>"]);
+        assert!(bindings.near_matches(&KeyEvent::from(KeyCode::Char('x'))).is_empty());
+    }
+
+    #[test]
+    fn test_physical_keys_match_by_position() {
+        let bindings = KeyBindings::new(kb![
+            "<phys:w><phys:ctrl-a><phys:up>" => "app:combo",
+            "<phys:up>" => "app:up",
+            "<w>" => "app:write"
+        ]);
+        let bound = parse_key_sequence("<w><ctrl-a><up>").unwrap();
+        let action = |name: &str| Action::AppAction(name.to_string());
+        assert_eq!(bindings.get_physical(&bound), Some(&action("app:combo")));
+        assert_eq!(bindings.get(&bound), None);
+        assert_eq!(bindings.get_physical(&bound[..1]), None);
+        // Keys that aren't characters sit in the same place on every layout
+        assert_eq!(bindings.get(&[key("up")]), Some(&action("app:up")));
+        assert_eq!(bindings.get(&[key("w")]), Some(&action("app:write")));
+        assert_eq!(
+            bindings.near_matches(&key("ctrl-w")),
+            ["<phys:w><phys:ctrl-a><up>", "<w>"]
+        );
+        assert_eq!(
+            canonical_key_sequence("<phys:c-a><x>").unwrap(),
+            "<phys:ctrl-a><phys:x>"
+        );
+
+        let typed = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        assert_eq!(KeyboardLayout::Azerty.physical(&typed('z')), Some(bound[0]));
+        assert_eq!(KeyboardLayout::Qwerty.physical(&typed('w')), Some(bound[0]));
+        assert_eq!(KeyboardLayout::Dvorak.physical(&typed(',')), Some(bound[0]));
+        let ctrl_q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL);
+        assert_eq!(KeyboardLayout::Azerty.physical(&ctrl_q), Some(bound[1]));
+        assert_eq!(KeyboardLayout::Azerty.physical(&key("enter")), None);
+    }
+
+    #[test]
+    fn test_completions_list_keys_after_a_prefix() {
+        let kb = KeyBindings::new([
+            ("<space><f><f>", "files:find"),
+            ("<space><f><g>", "files:grep"),
+            ("<space><b>", "buffers:list"),
+            ("<space>", "app:leader"),
+            ("<q>", "quit"),
+        ]);
+        let action = |name: &str| Action::AppAction(name.to_string());
+
+        let completions = kb.completions(&[key("space")]);
+        let listed = completions
+            .iter()
+            .map(|(key, action)| (key_event_to_string(key), (*action).clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            listed,
+            [
+                ("b".to_string(), action("buffers:list")),
+                ("f".to_string(), action("files:find")),
+                ("f".to_string(), action("files:grep")),
+            ]
+        );
+
+        assert_eq!(kb.completions(&[key("space"), key("f"), key("g")]), []);
+        assert_eq!(kb.completions(&[key("x")]), []);
+        assert_eq!(kb.completions(&[]).len(), 5);
+    }
 }
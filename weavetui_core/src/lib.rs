@@ -1,29 +1,48 @@
 //! Core library for the `weavetui` TUI framework.
 
 use downcast_rs::{impl_downcast, Downcast};
+use std::any::Any;
 use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::time::Duration;
 
+// Declared first so `impl_component_accessor!` is available to the other modules
+#[macro_use]
+pub mod components;
 pub mod app;
 pub mod component_manager;
 pub mod event;
+pub mod geometry;
 pub mod internal;
 pub mod keyboard;
 pub mod macros;
+pub mod mouse;
 pub mod redux;
+pub mod rng;
 pub mod theme;
 pub mod tui;
 
-pub use internal::ComponentContext;
+pub use internal::{ComponentContext, EventSourceCell, LocalStateCache};
+
+#[cfg(feature = "serde")]
+pub use serde_json;
 
 use crossterm::event::{KeyEvent, MouseEvent};
 use ratatui::{layout::Rect, Frame};
 use ratatui::style::{Color, Style};
 use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
 
-use event::Action;
+use event::{Action, EventMask, Message};
 
-use crate::{event::Event, keyboard::KeyBindings, theme::ThemeManager};
+use crate::{
+    component_manager::{BackdropStyle, DispatchOrder},
+    components::FormValue,
+    event::Event,
+    keyboard::{KeyBindings, KeySeqResult},
+    mouse::LocalMouseEvent,
+    theme::ThemeManager,
+};
 
 pub type Children = BTreeMap<String, Box<dyn Component>>;
 
@@ -38,11 +57,21 @@ impl ComponentHandler {
         Self { c: component }
     }
 
+    /// Run a closure on the wrapped component if it's a `T`, returning its result
+    pub fn with<T: Component, R>(&mut self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        self.c.downcast_mut::<T>().map(f)
+    }
+
     /// Set up the component in its assigned area
     pub(crate) fn handle_init(&mut self, area: Rect) {
         component_manager::init(self.c.as_mut(), area);
     }
 
+    /// Tell the component and its children the app started, with the terminal size
+    pub(crate) fn handle_start(&mut self, size: (u16, u16)) {
+        component_manager::start(self.c.as_mut(), size);
+    }
+
     /// Give the component a way to send actions back to the app
     pub(crate) fn receive_action_handler(&mut self, tx: UnboundedSender<Action>) {
         component_manager::receive_action_handler(self.c.as_mut(), tx);
@@ -53,29 +82,127 @@ impl ComponentHandler {
         component_manager::handle_event_for(self.c.as_mut(), event)
     }
 
-    /// Tell the component about state changes
-    pub(crate) fn handle_update(&mut self, action: &Action) {
-        component_manager::update(self.c.as_mut(), action);
+    /// Deliver pasted text to the focused component, if it accepts pastes
+    pub(crate) fn handle_paste(&mut self, text: &str) -> Vec<Action> {
+        component_manager::deliver_paste(self.c.as_mut(), text)
+    }
+
+    /// Collect the names of components under a position, outermost first
+    pub(crate) fn handle_hit_test(&mut self, column: u16, row: u16, hits: &mut Vec<String>) {
+        component_manager::hit_test(self.c.as_mut(), column, row, hits);
+    }
+
+    /// Offer buffered keys to the focused component, if there is one in the tree
+    pub(crate) fn handle_key_sequence(&mut self, keys: &[KeyEvent]) -> Option<KeySeqResult> {
+        component_manager::handle_key_sequence(self.c.as_mut(), keys)
+    }
+
+    /// Check if the component or any of its children has focus
+    pub(crate) fn has_focus(&mut self) -> bool {
+        component_manager::has_focus(self.c.as_mut())
+    }
+
+    /// Collect the focus order and name of every component that accepts focus
+    pub(crate) fn handle_focusable(&mut self, focusable: &mut Vec<(Option<i32>, String)>) {
+        component_manager::focusable(self.c.as_mut(), focusable);
+    }
+
+    /// Focus the first component named `name`, unfocusing every other one
+    pub(crate) fn handle_focus(&mut self, name: &str, found: &mut bool) {
+        component_manager::focus_by_name(self.c.as_mut(), name, found);
+    }
+
+    /// Tell the component about state changes and collect its follow-up actions
+    pub(crate) fn handle_update(&mut self, action: &Action) -> Vec<Action> {
+        component_manager::update(self.c.as_mut(), action)
     }
 
     /// Pass custom messages to the component
-    pub(crate) fn handle_message(&mut self, message: &str) {
+    pub(crate) fn handle_message(&mut self, message: &Message) {
         component_manager::handle_message(self.c.as_mut(), message);
     }
 
-    /// Draw the component to the screen
-    pub(crate) fn handle_draw(&mut self, f: &mut Frame<'_>) {
-        component_manager::handle_draw(self.c.as_mut(), f);
+    /// Draw the component to the screen, holding each component to its area
+    pub(crate) fn handle_draw(
+        &mut self,
+        f: &mut Frame<'_>,
+        policy: component_manager::ClipPolicy,
+        theme: &ThemeManager,
+    ) {
+        component_manager::handle_draw_themed(self.c.as_mut(), f, policy, theme);
+    }
+
+    /// Let the component register its own keyboard shortcuts, keyed by tree paths starting
+    /// with `root`
+    pub(crate) fn handle_custom_keybindings(
+        &mut self,
+        root: &str,
+        kb: &mut Vec<(String, KeyBindings)>,
+    ) {
+        component_manager::custom_keybindings(self.c.as_mut(), root, kb);
+    }
+
+    /// Collect the tree paths of the focused component and its ancestors, starting with `root`
+    pub(crate) fn handle_focus_tree_path(&mut self, root: &str, paths: &mut Vec<String>) -> bool {
+        component_manager::focus_tree_path(self.c.as_mut(), root, paths)
     }
 
-    /// Let the component register its own keyboard shortcuts
-    pub(crate) fn handle_custom_keybindings(&mut self, kb: &mut KeyBindings) {
-        component_manager::custom_keybindings(self.c.as_mut(), kb);
+    /// Collect the global keyboard shortcuts of the component and its children
+    pub(crate) fn handle_global_keybindings(&mut self, kb: &mut KeyBindings) {
+        component_manager::global_keybindings(self.c.as_mut(), kb);
+    }
+
+    /// Collect the names from the component down to its focused descendant
+    pub(crate) fn handle_focus_path(&mut self, path: &mut Vec<String>) -> bool {
+        component_manager::focus_path(self.c.as_mut(), path)
     }
 
     /// Apply a theme to the component
-    pub(crate) fn handle_theme(&mut self, th: ThemeManager) {
-        component_manager::handle_theme(self.c.as_mut(), &th);
+    pub(crate) fn handle_theme(&mut self, th: &ThemeManager) {
+        component_manager::handle_theme(self.c.as_mut(), th);
+    }
+
+    /// Share the app's local state cache with the component
+    pub(crate) fn handle_local_state(&mut self, cache: &LocalStateCache) {
+        component_manager::attach_local_state(self.c.as_mut(), cache);
+    }
+
+    /// Link the component's background tasks to the app's shutdown token
+    pub(crate) fn handle_shutdown(&mut self, shutdown: &CancellationToken) {
+        component_manager::attach_shutdown(self.c.as_mut(), shutdown);
+    }
+
+    /// Let the component see where the events it handles came from
+    pub(crate) fn handle_event_source(&mut self, source: &EventSourceCell) {
+        component_manager::attach_event_source(self.c.as_mut(), source);
+    }
+
+    /// Let the component draw from the app's random number generator
+    pub(crate) fn handle_rng(&mut self, rng: &rng::SharedRng) {
+        component_manager::attach_rng(self.c.as_mut(), rng);
+    }
+
+    /// Mark the component part of the app, mounting its children
+    pub(crate) fn handle_mount(&mut self) {
+        component_manager::mount_root(self.c.as_mut());
+    }
+
+    /// Capture the state of the component and its children
+    #[cfg(feature = "serde")]
+    pub(crate) fn handle_snapshot(&mut self) -> serde_json::Value {
+        component_manager::snapshot_tree(self.c.as_mut())
+    }
+
+    /// Restore state captured by `handle_snapshot`
+    #[cfg(feature = "serde")]
+    pub(crate) fn handle_restore(&mut self, value: &serde_json::Value) {
+        component_manager::restore_tree(self.c.as_mut(), value);
+    }
+
+    /// Get the name of the wrapped component
+    #[cfg(feature = "serde")]
+    pub(crate) fn name(&self) -> String {
+        self.c.name()
     }
 }
 
@@ -108,16 +235,56 @@ pub trait ComponentAccessor: Debug {
         self.set_active(false);
     }
 
+    /// Returns whether the component currently has focus.
+    fn is_focused(&self) -> bool {
+        self.get_context().focused
+    }
+
+    /// Sets the focus state of the component.
+    fn set_focused(&mut self, focused: bool) {
+        self.get_context_mut().focused = focused;
+    }
+
     /// Registers an action handler that can send `Action`s for processing.
     fn register_action_handler(&mut self, tx: UnboundedSender<Action>);
 
-    /// Sends a string message through the action handler bus.
-    fn send(&self, action: &str);
+    /// Sends a string message through the action handler bus as an `Action::AppAction`.
+    ///
+    /// The message has no source; use [`ComponentAccessor::send_message`] to attribute it.
+    fn send(&self, action: &str) {
+        self.send_action(Action::AppAction(action.to_string()));
+    }
+
+    /// Sends a message with a topic and payload through the action handler bus.
+    ///
+    /// The message's source is set to this component's name.
+    fn send_message(&self, topic: &str, payload: &str) {
+        self.send_action(Action::Message(
+            Message::new(topic)
+                .with_payload(payload)
+                .with_source(&self.name()),
+        ));
+    }
 
     /// Sends an `Action` through the action handler bus.
     fn send_action(&self, action: Action);
 
-    
+    /// Returns whether the component is purely structural, only arranging its children.
+    ///
+    /// Set by `#[component(container)]`, which also provides an empty `draw`.
+    fn is_container(&self) -> bool {
+        false
+    }
+
+    /// Gets the context holding the component's framework state.
+    ///
+    /// `#[component]` implements this and `get_context_mut` from the `_ctx` field. They're
+    /// required, so components implementing this trait by hand have to add both, returning
+    /// a `ComponentContext` they keep; see the changelog for an example.
+    fn get_context(&self) -> &ComponentContext;
+
+    /// Gets the context holding the component's framework state, mutably.
+    fn get_context_mut(&mut self) -> &mut ComponentContext;
 
     /// Gets all child components. This is necessary if the component has children,
     /// as it will be used by other functions to have knowledge of the children.
@@ -160,6 +327,36 @@ pub trait ComponentAccessor: Debug {
             .map(|theme| theme.get_style(style_name))
             .unwrap_or_default()
     }
+
+    /// Composes several styles from the active theme, later names overriding earlier ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `style_names` - The style names to fold together, from base to most specific.
+    ///
+    /// # Returns
+    ///
+    /// The composed `Style`, skipping names the theme doesn't define.
+    fn compose_style(&self, style_names: &[&str]) -> Style {
+        self.get_theme_manager().compose_style(style_names)
+    }
+
+    /// Captures the component's own state, excluding its children and `_ctx`.
+    ///
+    /// Implemented by `#[component(serde)]` for components whose fields are `Serialize`.
+    /// The default implementation returns `None`, meaning there's nothing to save.
+    #[cfg(feature = "serde")]
+    fn snapshot(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Restores state previously captured by [`ComponentAccessor::snapshot`].
+    ///
+    /// Implemented by `#[component(serde)]`, which restores each field present in `value`
+    /// and leaves the others untouched. The default implementation does nothing.
+    #[cfg(feature = "serde")]
+    #[allow(unused_variables)]
+    fn restore(&mut self, value: &serde_json::Value) {}
 }
 
 impl_downcast!(Component);
@@ -174,7 +371,12 @@ pub trait Component: ComponentAccessor + Downcast {
     /// Initializes the component, optionally using the provided area.
     ///
     /// This method is called once before the first render, allowing the component to perform
-    /// any necessary setup, such as initializing state or creating resources.
+    /// any necessary setup, such as initializing state or creating resources. Children added
+    /// later are initialized right before they're first drawn, and [`Component::reinit`]
+    /// makes it run again.
+    /// The app wires up the component and its children before calling `init`, so the action
+    /// handler, theme and local state are in place: actions sent with `send_action` or
+    /// `send` from here are delivered.
     /// The default implementation does nothing.
     ///
     /// # Arguments
@@ -183,9 +385,26 @@ pub trait Component: ComponentAccessor + Downcast {
     #[allow(unused)]
     fn init(&mut self, area: Rect) {}
 
+    /// Called once when [`App::run`](app::App::run) starts, before the first frame is drawn.
+    ///
+    /// Unlike `init`, which gets the component's area while drawing, this runs as soon as
+    /// the terminal is known, e.g. to pick an initial layout or to quit early when the
+    /// terminal is too small. Components are wired up by then, so actions sent from here are
+    /// delivered. Children inserted later, and apps drawn with `App::render_into`, don't get
+    /// this call.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The terminal's `(width, height)`.
+    #[allow(unused)]
+    fn on_start(&mut self, size: (u16, u16)) {}
+
     /// Renders the component within the given area of the frame.
     ///
     /// This method is called on each render cycle and is responsible for drawing the component's UI.
+    /// Children are drawn after their parent, each in its own area; a parent lays them out by
+    /// calling `set_area` on them here. Children that never get an area aren't drawn.
+    /// Cells drawn outside `area` are discarded by default; see `App::with_clip_policy`.
     ///
     /// # Arguments
     ///
@@ -193,12 +412,104 @@ pub trait Component: ComponentAccessor + Downcast {
     /// * `area` - The area in which the component should be drawn.
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect);
 
+    /// Renders the component like `draw`, but can fail, e.g. when a resource it shows can't
+    /// be loaded.
+    ///
+    /// The app draws components through this method, passing the active theme. On an error,
+    /// the component's area is replaced with a red box titled "render error" showing the
+    /// message, and the rest of the tree is drawn as usual; children are still drawn in the
+    /// areas they have. The default implementation calls `draw_themed` and never fails.
+    /// Components overriding this still have to implement `draw`, which can be left empty.
+    fn try_draw(
+        &mut self,
+        f: &mut Frame<'_>,
+        area: Rect,
+        theme: &ThemeManager,
+    ) -> anyhow::Result<()> {
+        self.draw_themed(f, area, theme);
+        Ok(())
+    }
+
+    /// Renders the component like `draw`, with the theme the app is drawing with.
+    ///
+    /// Unlike `get_style`, which reads the copy of the theme the component was given by the
+    /// app, `theme` is always the active one, also for components added after the theme was
+    /// set. The default implementation ignores it and calls `draw`.
+    fn draw_themed(&mut self, f: &mut Frame<'_>, area: Rect, _theme: &ThemeManager) {
+        self.draw(f, area);
+    }
+
+    /// Returns how many rows the component needs to show all its content at `width`, for
+    /// parents that size children to fit, e.g. a column of text blocks.
+    ///
+    /// `None` means the component has no natural height and takes whatever it's given,
+    /// which is what the default implementation returns.
+    #[allow(unused_variables)]
+    fn measure(&self, width: u16) -> Option<u16> {
+        None
+    }
+
+    /// Returns whether the component's area differs from the one it was last drawn in.
+    ///
+    /// Meant to be called from `draw`, e.g. to reuse an expensive layout computed on a
+    /// previous frame while the area stays the same. Always `true` on the first draw. Only
+    /// draws done by the app (or [`component_manager::handle_draw`]) are remembered, not
+    /// direct calls to `draw`.
+    fn area_changed(&self) -> bool {
+        let ctx = self.get_context();
+        ctx.area != ctx.drawn_area
+    }
+
+    /// Returns whether the app may reuse the component's last drawn cells instead of calling
+    /// `draw`, e.g. for a static help panel that's expensive to lay out.
+    ///
+    /// While this is `true`, the cells the component drew are kept and copied back on later
+    /// frames, until the area changes, the theme changes or [`Component::mark_dirty`] is
+    /// called. Call `mark_dirty` whenever state shown by `draw` changes, or the old content
+    /// stays on screen. Children are drawn and cached on their own. Off by default.
+    fn cache_render(&self) -> bool {
+        false
+    }
+
+    /// Drop the cells kept for [`Component::cache_render`], so the next frame calls `draw`
+    fn mark_dirty(&mut self) {
+        self.get_context_mut().render_cache = None;
+    }
+
+    /// Run `init` again for the component and its children the next time they're drawn,
+    /// e.g. after a config change that setup depends on.
+    fn reinit(&mut self) {
+        component_manager::reinit(self);
+    }
+
     /// Returns the keybindings for this component.
     ///
     /// These keybindings can be used to display help to the user or for other introspective purposes.
+    /// While a component has focus, only its own bindings and those of its ancestors apply
+    /// (innermost first), falling back to the app's bindings. When nothing has focus, every
+    /// component's bindings apply.
     /// The default implementation returns an empty set of keybindings.
     fn keybindings(&self) -> KeyBindings {
-        KeyBindings::default()
+        KeyBindings::empty()
+    }
+
+    /// Returns keybindings that apply whether or not the component has focus, e.g. to open
+    /// a help screen from anywhere.
+    ///
+    /// Global bindings are checked before the focused component's [`Component::keybindings`]
+    /// and the app's; among components, later ones win. The default implementation returns an
+    /// empty set of keybindings.
+    fn global_keybindings(&self) -> KeyBindings {
+        KeyBindings::empty()
+    }
+
+    /// Returns the kinds of events whose handlers should be called for this component.
+    ///
+    /// Handlers for other kinds, like `handle_tick_event` for a component not subscribed to
+    /// `EventMask::TICK`, are skipped. Children are asked separately, so a parent's mask
+    /// doesn't hide events from them. The default implementation subscribes to everything.
+    fn subscribed_events(&self) -> EventMask {
+        EventMask::all()
     }
 
     /// Handles key press events.
@@ -219,11 +530,30 @@ pub trait Component: ComponentAccessor + Downcast {
         None
     }
 
+    /// Handles multi-key sequences such as `dd` or `3j` while the component has focus.
+    ///
+    /// Every key press is appended to a buffer and the whole buffer is offered here before
+    /// the keymap sees the key. Return `Pending` to wait for more keys, `Consumed` once the
+    /// sequence is complete, or `Rejected` to let the key through to the keybindings and
+    /// `handle_key_events`. If a sequence that was pending is rejected, the buffer restarts
+    /// with the latest key. Pending keys are dropped once the app's key sequence timeout
+    /// passes without a new key, or when focus changes.
+    /// The default implementation rejects every sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The buffered keys, oldest first, ending with the key just pressed.
+    #[allow(unused_variables)]
+    fn handle_key_sequence(&mut self, keys: &[KeyEvent]) -> KeySeqResult {
+        KeySeqResult::Rejected
+    }
+
     /// Handles mouse events.
     ///
     /// This method is called when a mouse event is received and the component is active.
     /// It can be used to handle clicks, scrolls, and other mouse interactions.
-    /// The default implementation does nothing.
+    /// Positions are terminal coordinates; see `handle_local_mouse_events` for ones relative
+    /// to the component's area. The default implementation does nothing.
     ///
     /// # Arguments
     ///
@@ -237,16 +567,32 @@ pub trait Component: ComponentAccessor + Downcast {
         None
     }
 
+    /// Handles mouse events in the component's own coordinates.
+    ///
+    /// The app calls this for every mouse event, with the position translated so that the
+    /// top-left cell of the component's area is `(0, 0)`; see [`LocalMouseEvent`] for
+    /// events outside the area. A component without an area gets terminal coordinates. The
+    /// default implementation passes the untranslated event to `handle_mouse_events`, which
+    /// sees terminal coordinates, so override one of the two.
+    fn handle_local_mouse_events(&mut self, mouse: LocalMouseEvent) -> Option<Action> {
+        self.handle_mouse_events(mouse.absolute)
+    }
+
     /// Handles tick events.
     ///
     /// This method is called on each application tick, allowing for periodic updates or animations.
     /// The default implementation does nothing.
     ///
+    /// # Arguments
+    ///
+    /// * `count` - The number of ticks so far, starting at 1.
+    /// * `elapsed` - The time since the event loop started.
+    ///
     /// # Returns
     ///
     /// An `Option<Action>` which is `Some` if the tick triggered an action, and `None` otherwise.
     #[allow(unused_variables)]
-    fn handle_tick_event(&mut self) -> Option<Action> {
+    fn handle_tick_event(&mut self, count: u64, elapsed: Duration) -> Option<Action> {
         None
     }
 
@@ -280,6 +626,15 @@ pub trait Component: ComponentAccessor + Downcast {
         None
     }
 
+    /// Returns whether the component wants paste events while focused.
+    ///
+    /// When any component has focus, paste events are only delivered to focused components
+    /// that return `true` here. Without focus, pastes are broadcast to every active component.
+    /// The default implementation returns `false`.
+    fn accepts_paste(&self) -> bool {
+        false
+    }
+
     /// Updates the component's state based on a received action.
     ///
     /// This method is called for every action that is dispatched in the application,
@@ -292,6 +647,22 @@ pub trait Component: ComponentAccessor + Downcast {
     #[allow(unused_variables)]
     fn update(&mut self, action: &Action) {}
 
+    /// Updates the component's state and returns follow-up actions to dispatch.
+    ///
+    /// Use this instead of `update` to react to an action by emitting others, without
+    /// sending them through the action handler. The returned actions are queued after the
+    /// current batch, so they're handled in a later loop iteration. A component is updated
+    /// before its children, and each component's follow-ups are queued in that order.
+    /// The default implementation calls `update` and returns no actions.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The `Action` to be processed.
+    fn update_with_actions(&mut self, action: &Action) -> Vec<Action> {
+        self.update(action);
+        Vec::new()
+    }
+
     /// Handles custom string-based events.
     ///
     /// This method allows components to communicate with each other using simple string messages.
@@ -303,6 +674,27 @@ pub trait Component: ComponentAccessor + Downcast {
     #[allow(unused_variables)]
     fn on_event(&mut self, message: &str) {}
 
+    /// Handles messages sent by other components, keybindings or the app.
+    ///
+    /// Unlike [`Component::on_event`], the message says which component sent it, so a
+    /// component can tell its own children's messages apart from unrelated ones.
+    /// The default implementation forwards the topic to `on_event`.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message, with its source, topic and payload.
+    fn on_message(&mut self, message: &Message) {
+        self.on_event(&message.topic);
+    }
+
+    /// Returns whether the component gets messages before or after its children.
+    ///
+    /// A parent that aggregates what its children did with a message can return
+    /// [`DispatchOrder::BottomUp`] to see it last. The default is `TopDown`.
+    fn message_order(&self) -> DispatchOrder {
+        DispatchOrder::TopDown
+    }
+
     /// Gets a mutable reference to a child component by name.
     ///
     /// This allows for modifying the state of a child component.
@@ -358,6 +750,131 @@ pub trait Component: ComponentAccessor + Downcast {
         self.get_children().get(name)
     }
 
+    /// Inserts a child component, wiring it up like the children present at startup.
+    ///
+    /// The child receives the parent's action handler, theme and local state cache. A child
+    /// already registered under `name` is removed first (saving its local state), then any
+    /// state saved under the new child's [`Component::local_state_key`] is loaded into it.
+    /// Finally, if this component is mounted, the child and its children get
+    /// [`Component::on_mount`].
+    /// Use this instead of `get_children().insert` when rebuilding children so UI state like
+    /// scroll position survives the recreation.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name to register the child under.
+    /// * `child` - The child component.
+    fn child_insert(&mut self, name: &str, mut child: Box<dyn Component>) {
+        self.child_remove(name);
+
+        let ctx = self.get_context();
+        if let Some(tx) = ctx.action_tx.clone() {
+            component_manager::receive_action_handler(child.as_mut(), tx);
+        }
+        component_manager::handle_theme(child.as_mut(), &ctx.theme_manager);
+        component_manager::attach_local_state(child.as_mut(), &ctx.local_state);
+        component_manager::attach_shutdown(child.as_mut(), &ctx.shutdown);
+        component_manager::attach_event_source(child.as_mut(), &ctx.event_source);
+        component_manager::attach_rng(child.as_mut(), &ctx.shared_rng);
+        if ctx.mounted {
+            component_manager::mount(child.as_mut());
+        }
+
+        self.get_children().insert(name.to_string(), child);
+    }
+
+    /// Removes a child component, saving the local state of it and its children and calling
+    /// their [`Component::on_unmount`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the child component.
+    ///
+    /// # Returns
+    ///
+    /// The removed child, or `None` if there was no child with that name.
+    fn child_remove(&mut self, name: &str) -> Option<Box<dyn Component>> {
+        let mut child = self.get_children().remove(name)?;
+        component_manager::save_local_state(child.as_mut(), &self.get_context().local_state);
+        component_manager::unmount(child.as_mut());
+        Some(child)
+    }
+
+    /// Returns the names of the children, in the order they're drawn and visited
+    fn child_names(&mut self) -> Vec<String> {
+        self.get_children().keys().cloned().collect()
+    }
+
+    /// Returns the number of children
+    fn children_len(&mut self) -> usize {
+        self.get_children().len()
+    }
+
+    /// Removes all children like [`Component::child_remove`], saving their local state and
+    /// calling their [`Component::on_unmount`]
+    fn clear_children(&mut self) {
+        for name in self.child_names() {
+            self.child_remove(&name);
+        }
+    }
+
+    /// Calls `f` with each child, in name order.
+    ///
+    /// Takes the closure by reference so the method can be called on `dyn Component` too,
+    /// e.g. `parent.for_each_child(&mut |child| child.set_active(false))`.
+    fn for_each_child(&mut self, f: &mut dyn FnMut(&mut Box<dyn Component>)) {
+        for child in self.get_children().values_mut() {
+            f(child);
+        }
+    }
+
+    /// Returns the key under which the component's local state is kept across recreations.
+    ///
+    /// The key should be stable for the "same" component, e.g. derived from the data it shows.
+    /// The default implementation returns `None`, opting out of local state persistence.
+    fn local_state_key(&self) -> Option<String> {
+        None
+    }
+
+    /// Captures UI state (scroll position, selection, ...) to restore in a future instance.
+    ///
+    /// Called when the component is removed with [`Component::child_remove`] or replaced with
+    /// [`Component::child_insert`]. The default implementation saves nothing.
+    fn save_local_state(&self) -> Option<Box<dyn Any + Send>> {
+        None
+    }
+
+    /// Restores state captured by [`Component::save_local_state`] on a previous instance.
+    ///
+    /// Called when the component is inserted with [`Component::child_insert`] and state was
+    /// saved under its key. The default implementation does nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The saved state, to be downcast to the type `save_local_state` returned.
+    #[allow(unused_variables)]
+    fn load_local_state(&mut self, state: Box<dyn Any + Send>) {}
+
+    /// Called when the component joins an app's tree.
+    ///
+    /// Runs on [`Component::child_insert`] into a mounted parent, once the component is
+    /// wired up like its new parent and its local state is restored, so actions sent from
+    /// here are delivered. Children inserted before the app started get it when the app
+    /// wires up their root, and a subtree built before being inserted gets it once, when it
+    /// joins. Unlike `init`, it runs again after the component is removed and inserted again.
+    /// It doesn't run for roots or for children added with `get_children().insert`. Use it
+    /// to start work that [`Component::on_unmount`] stops. The default implementation does
+    /// nothing.
+    fn on_mount(&mut self) {}
+
+    /// Called when the component, or an ancestor of it, is removed from its parent.
+    ///
+    /// Runs on [`Component::child_remove`] for components that got `on_mount`, including
+    /// when [`Component::child_insert`] replaces a child, after the local state was saved.
+    /// Use it to stop work the component started, e.g. by cancelling a task. The default
+    /// implementation does nothing.
+    fn on_unmount(&mut self) {}
+
     /// Called when the component's active state changes.
     ///
     /// This method is a hook that allows the component to react to being activated or deactivated.
@@ -368,4 +885,40 @@ pub trait Component: ComponentAccessor + Downcast {
     /// * `active` - The new active state.
     #[allow(unused_variables)]
     fn on_active_changed(&mut self, active: bool) {}
+
+    /// Returns whether `App::focus_next` and `App::focus_prev` can move focus to this component.
+    ///
+    /// Decorative components like labels and borders keep the default of `false`, so tab
+    /// order only visits interactive ones. Inactive components and their children are
+    /// skipped either way. `App::focus` can still focus any component by name.
+    fn accepts_focus(&self) -> bool {
+        false
+    }
+
+    /// Returns how to cover what's already drawn in the component's area before drawing it.
+    ///
+    /// Modals like dialogs can return [`BackdropStyle::Dim`] to dim the content behind
+    /// them. The backdrop covers the whole area given to the component, so a dialog drawn
+    /// centered in the full screen dims all of it. Components drawn earlier are affected;
+    /// ones drawn later, such as the component's children, are not. The default is `None`.
+    fn backdrop(&self) -> Option<BackdropStyle> {
+        None
+    }
+
+    /// Returns the value the component holds when used as a field of a
+    /// [`Form`](components::Form).
+    ///
+    /// Input components return their current value, e.g. [`FormValue::Bool`] for a checkbox.
+    /// The default is `None`, so the component is left out of the form's values.
+    fn form_value(&self) -> Option<FormValue> {
+        None
+    }
+
+    /// Returns the component's place in the tab order, lowest first.
+    ///
+    /// Components returning `Some` are visited before the rest, which follow in tree order.
+    /// The default implementation returns `None`.
+    fn focus_order(&self) -> Option<i32> {
+        None
+    }
 }
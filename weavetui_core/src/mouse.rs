@@ -0,0 +1,183 @@
+//! Mouse bindings that turn wheel and button events into actions, and mouse events in a
+//! component's own coordinates.
+
+use crossterm::event::{KeyModifiers, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+
+use crate::event::{Action, ActionKind};
+
+/// A mouse event kind, optionally limited to a component under the cursor
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MouseBinding {
+    pub kind: MouseEventKind,
+    /// Name of the component the cursor must be over, or `None` to match anywhere
+    pub scope: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MouseBindings(pub Vec<(MouseBinding, Action)>);
+
+impl MouseBindings {
+    /// Create new mouse bindings from an array of event-action pairs
+    pub fn new<const N: usize>(raw: [(MouseEventKind, impl Into<ActionKind>); N]) -> Self {
+        let mut bindings = Self::default();
+        for (kind, cmd) in raw.into_iter() {
+            bindings.add(kind, cmd);
+        }
+        bindings
+    }
+
+    /// Bind an event kind anywhere on screen, replacing any existing unscoped binding
+    pub fn add(&mut self, kind: MouseEventKind, action: impl Into<ActionKind>) {
+        self.insert(MouseBinding { kind, scope: None }, action.into().into());
+    }
+
+    /// Bind an event kind only while the cursor is over the named component
+    pub fn add_scoped(
+        &mut self,
+        kind: MouseEventKind,
+        component: &str,
+        action: impl Into<ActionKind>,
+    ) {
+        let binding = MouseBinding {
+            kind,
+            scope: Some(component.to_string()),
+        };
+        self.insert(binding, action.into().into());
+    }
+
+    /// Get the action for an event kind.
+    ///
+    /// `hovered` lists the components under the cursor, outermost first. Bindings scoped to
+    /// the innermost hovered component win, then outer ones, then unscoped bindings.
+    pub fn get(&self, kind: MouseEventKind, hovered: &[String]) -> Option<&Action> {
+        hovered
+            .iter()
+            .rev()
+            .find_map(|name| self.find(kind, Some(name.as_str())))
+            .or_else(|| self.find(kind, None))
+    }
+
+    /// Merge another set of mouse bindings into this one
+    pub fn extend(&mut self, other: MouseBindings) {
+        for (binding, action) in other.0 {
+            self.insert(binding, action);
+        }
+    }
+
+    /// Check if there are no bindings
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn find(&self, kind: MouseEventKind, scope: Option<&str>) -> Option<&Action> {
+        self.0
+            .iter()
+            .find(|(binding, _)| binding.kind == kind && binding.scope.as_deref() == scope)
+            .map(|(_, action)| action)
+    }
+
+    fn insert(&mut self, binding: MouseBinding, action: Action) {
+        match self.0.iter_mut().find(|(existing, _)| *existing == binding) {
+            Some((_, existing)) => *existing = action,
+            None => self.0.push((binding, action)),
+        }
+    }
+}
+
+/// A mouse event with its position relative to a component's area, see
+/// `Component::handle_local_mouse_events`.
+///
+/// `(0, 0)` is the top-left cell of the area. Components get every mouse event, not only the
+/// ones over them, so the position is negative left of or above the area, and past its size
+/// right of or below it; check [`LocalMouseEvent::is_inside`] before acting on a click.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LocalMouseEvent {
+    pub kind: MouseEventKind,
+    /// Column relative to the left edge of the area
+    pub column: i32,
+    /// Row relative to the top edge of the area
+    pub row: i32,
+    pub modifiers: KeyModifiers,
+    /// Size of the area, as (width, height)
+    pub size: (u16, u16),
+    /// The event as received, in terminal coordinates
+    pub absolute: MouseEvent,
+}
+
+impl LocalMouseEvent {
+    /// Translate `mouse` into the coordinates of `area`
+    pub fn new(mouse: MouseEvent, area: Rect) -> Self {
+        Self {
+            kind: mouse.kind,
+            column: i32::from(mouse.column) - i32::from(area.x),
+            row: i32::from(mouse.row) - i32::from(area.y),
+            modifiers: mouse.modifiers,
+            size: (area.width, area.height),
+            absolute: mouse,
+        }
+    }
+
+    /// Check if the event is over the area
+    pub fn is_inside(&self) -> bool {
+        (0..i32::from(self.size.0)).contains(&self.column)
+            && (0..i32::from(self.size.1)).contains(&self.row)
+    }
+
+    /// Get the position in the area, or `None` if the event is outside it
+    pub fn position(&self) -> Option<Position> {
+        self.is_inside().then(|| Position::new(self.column as u16, self.row as u16))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_bindings_prefer_innermost_component() {
+        let mut bindings = MouseBindings::new([
+            (MouseEventKind::ScrollUp, "app:scroll_up"),
+            (MouseEventKind::ScrollDown, "app:scroll_down"),
+        ]);
+        bindings.add_scoped(MouseEventKind::ScrollUp, "Sidebar", "sidebar:up");
+        bindings.add_scoped(MouseEventKind::ScrollUp, "FileList", "files:up");
+
+        let action = |name: &str| Some(Action::AppAction(name.to_string()));
+        let hovered = ["Sidebar".to_string(), "FileList".to_string()];
+
+        assert_eq!(bindings.get(MouseEventKind::ScrollUp, &hovered).cloned(), action("files:up"));
+        assert_eq!(
+            bindings.get(MouseEventKind::ScrollUp, &hovered[..1]).cloned(),
+            action("sidebar:up")
+        );
+        assert_eq!(bindings.get(MouseEventKind::ScrollUp, &[]).cloned(), action("app:scroll_up"));
+        assert_eq!(
+            bindings.get(MouseEventKind::ScrollDown, &hovered).cloned(),
+            action("app:scroll_down")
+        );
+        assert_eq!(bindings.get(MouseEventKind::Moved, &hovered), None);
+    }
+
+    #[test]
+    fn test_local_mouse_event_is_relative_to_the_area() {
+        let mouse = |column, row| MouseEvent {
+            kind: MouseEventKind::Down(crossterm::event::MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        let area = Rect::new(10, 5, 4, 2);
+
+        let local = LocalMouseEvent::new(mouse(10, 5), area);
+        assert_eq!((local.column, local.row), (0, 0));
+        assert_eq!(local.position(), Some(Position::new(0, 0)));
+        assert_eq!(local.absolute, mouse(10, 5));
+
+        assert_eq!(LocalMouseEvent::new(mouse(13, 6), area).position(), Some(Position::new(3, 1)));
+        let outside = LocalMouseEvent::new(mouse(2, 6), area);
+        assert_eq!((outside.column, outside.row), (-8, 1));
+        assert!(!outside.is_inside());
+        assert!(!LocalMouseEvent::new(mouse(14, 5), area).is_inside());
+    }
+}
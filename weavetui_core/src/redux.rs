@@ -4,9 +4,10 @@
 //! - Central store for application state
 //! - Pure reducer functions for state transitions
 //! - Action dispatching and state subscriptions
-//! - Middleware support for cross-cutting concerns
+//! - Middleware support for cross-cutting concerns, with prebuilt ones in [`middleware`]
+//! - Optional bounded history for time-travel debugging
 
-use std::{fmt, sync::{Arc, RwLock}};
+use std::{fmt, sync::{atomic::{AtomicBool, Ordering}, Arc, RwLock}};
 use tokio::sync::broadcast;
 use crate::event::Action;
 
@@ -37,7 +38,63 @@ struct StoreInner<S: AppState, A: Clone + Send + Sync + 'static> {
     state: RwLock<S>,
     reducer: Reducer<S, A>,
     subscribers: RwLock<Vec<broadcast::Sender<S>>>,
+    slice_subscribers: RwLock<Vec<Box<dyn SliceSubscriber<S>>>>,
     middleware: RwLock<Vec<Middleware<S, A>>>,
+    history: RwLock<History<S, A>>,
+    /// Hold notifications until `flush_notifications` instead of sending them on dispatch
+    coalesce: AtomicBool,
+    /// Whether the state changed since the last flush, while coalescing
+    notify_pending: AtomicBool,
+}
+
+/// A subscriber only interested in part of the state
+trait SliceSubscriber<S>: Send + Sync {
+    /// Send the selected slice of `state` if it changed since the last notification
+    fn notify(&mut self, state: &S);
+
+    /// Whether every receiver has been dropped
+    fn is_closed(&self) -> bool;
+}
+
+struct SliceSubscription<T, F> {
+    selector: F,
+    last: T,
+    tx: broadcast::Sender<T>,
+}
+
+impl<S, T, F> SliceSubscriber<S> for SliceSubscription<T, F>
+where
+    T: PartialEq + Clone + Send + Sync + 'static,
+    F: Fn(&S) -> T + Send + Sync,
+{
+    fn notify(&mut self, state: &S) {
+        let slice = (self.selector)(state);
+        if slice != self.last {
+            self.last = slice.clone();
+            let _ = self.tx.send(slice);
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.tx.receiver_count() == 0
+    }
+}
+
+/// A recorded step in the store's history
+#[derive(Clone, Debug)]
+pub struct HistoryEntry<S, A> {
+    /// The dispatched action, or `None` for the state history started from
+    pub action: Option<A>,
+    /// The state after the action was applied
+    pub state: S,
+}
+
+/// Recorded states with the position of the current one; disabled while `limit` is 0
+#[derive(Debug)]
+struct History<S, A> {
+    entries: Vec<HistoryEntry<S, A>>,
+    cursor: usize,
+    limit: usize,
 }
 
 impl<S: AppState, A: Clone + Send + Sync + 'static> fmt::Debug for StoreInner<S, A> {
@@ -46,7 +103,11 @@ impl<S: AppState, A: Clone + Send + Sync + 'static> fmt::Debug for StoreInner<S,
             .field("state", &"<RwLock>")
             .field("reducer", &"<fn>")
             .field("subscribers", &"<RwLock<Vec<Sender>>>")
+            .field("slice_subscribers", &"<RwLock<Vec<SliceSubscriber>>>")
             .field("middleware", &"<RwLock<Vec<Middleware>>>")
+            .field("history", &"<RwLock<History>>")
+            .field("coalesce", &self.coalesce)
+            .field("notify_pending", &self.notify_pending)
             .finish()
     }
 }
@@ -59,39 +120,111 @@ impl<S: AppState, A: Clone + Send + Sync + 'static> Store<S, A> {
                 state: RwLock::new(initial_state),
                 reducer,
                 subscribers: RwLock::new(Vec::new()),
+                slice_subscribers: RwLock::new(Vec::new()),
                 middleware: RwLock::new(Vec::new()),
+                history: RwLock::new(History {
+                    entries: Vec::new(),
+                    cursor: 0,
+                    limit: 0,
+                }),
+                coalesce: AtomicBool::new(false),
+                notify_pending: AtomicBool::new(false),
             }),
         }
     }
 
+    /// Record up to `limit` states for undo/redo and time-travel (chainable).
+    ///
+    /// The current state becomes the first entry. Once the limit is reached the oldest
+    /// entries are dropped. A limit of 0 disables history.
+    pub fn with_history(self, limit: usize) -> Self {
+        {
+            let mut history = self.inner.history.write().unwrap();
+            history.limit = limit;
+            history.cursor = 0;
+            history.entries.clear();
+            if limit > 0 {
+                history.entries.push(HistoryEntry {
+                    action: None,
+                    state: self.get_state(),
+                });
+            }
+        }
+        self
+    }
+
+    /// Hold subscriber notifications until [`Store::flush_notifications`] (chainable).
+    ///
+    /// While coalescing, dispatching only marks the state as changed, and the next flush
+    /// sends subscribers the state as it is then. Register the store with
+    /// [`App::with_store`](crate::app::App::with_store) to flush once per frame, so many
+    /// actions handled in one batch cost connected components a single update before the
+    /// next render. The catch is latency: subscribers don't see a change until the next
+    /// frame, and never see the states in between.
+    pub fn with_coalesced_notify(self, coalesce: bool) -> Self {
+        self.inner.coalesce.store(coalesce, Ordering::Relaxed);
+        self
+    }
+
+    /// Notify subscribers of the current state if it changed since the last flush.
+    ///
+    /// Does nothing unless notifications are coalesced, see [`Store::with_coalesced_notify`].
+    pub fn flush_notifications(&self) {
+        if self.inner.notify_pending.swap(false, Ordering::AcqRel) {
+            self.send_to_subscribers(self.get_state());
+        }
+    }
+
     /// Get current state (immutable)
     pub fn get_state(&self) -> S {
         self.inner.state.read().unwrap().clone()
     }
 
+    /// Read the current state in place, returning what `f` extracts from it.
+    ///
+    /// Unlike [`Store::get_state`] this doesn't clone the whole state, which matters for large
+    /// states read often, e.g. in `draw`. The store stays locked while `f` runs, so `f` must
+    /// not call back into the store (dispatching or reading state), or it will deadlock.
+    pub fn with_state<R>(&self, f: impl FnOnce(&S) -> R) -> R {
+        f(&self.inner.state.read().unwrap())
+    }
+
     /// Dispatch an action to update state
     pub fn dispatch(&self, action: &A) {
-        // Run middleware first
-        let current_state = self.get_state();
-        let middleware = self.inner.middleware.read().unwrap();
+        self.dispatch_all(std::slice::from_ref(action));
+    }
 
-        for middleware_fn in middleware.iter() {
-            if !middleware_fn(&current_state, action, self) {
-                return; // Middleware blocked the action
+    /// Dispatch several actions in order as one update, e.g. resetting then seeding the state.
+    ///
+    /// Middleware runs for each action, seeing the state left by the previous ones, and can
+    /// block any of them without stopping the rest. The state is written once at the end and
+    /// subscribers are notified once, so they see only the final state, never the ones in
+    /// between. Each applied action still gets its own history entry. Nothing is notified if
+    /// middleware blocked every action.
+    ///
+    /// The state stays locked for the whole batch, so a dispatch from another thread waits
+    /// for it instead of being overwritten. Middleware must therefore not read the state
+    /// through the store or dispatch, or it will deadlock; it gets the state as an argument.
+    pub fn dispatch_all(&self, actions: &[A]) {
+        let changed = {
+            let mut state = self.inner.state.write().unwrap();
+            let middleware = self.inner.middleware.read().unwrap();
+            let mut applied = false;
+            for action in actions {
+                if !middleware.iter().all(|middleware_fn| middleware_fn(&state, action, self)) {
+                    continue; // Middleware blocked the action
+                }
+
+                *state = (self.inner.reducer)(&state, action);
+                self.record(action, &state);
+                applied = true;
             }
-        }
-
-        // Apply reducer to get new state
-        let new_state = (self.inner.reducer)(&current_state, action);
+            applied.then(|| state.clone())
+        };
 
-        // Update state
-        {
-            let mut state = self.inner.state.write().unwrap();
-            *state = new_state.clone();
+        if let Some(state) = changed {
+            self.notify_subscribers(state);
         }
-
-        // Notify subscribers
-        self.notify_subscribers(new_state);
     }
 
     /// Subscribe to state changes
@@ -106,13 +239,117 @@ impl<S: AppState, A: Clone + Send + Sync + 'static> Store<S, A> {
         rx
     }
 
+    /// Subscribe to one slice of the state, picked out by `selector`.
+    ///
+    /// Unlike [`Store::subscribe`], the receiver only gets a value when the selected slice
+    /// changes, so a component that cares about one field isn't woken up by unrelated
+    /// updates. Changes are detected by comparing against the last selected value with
+    /// `PartialEq`; the first comparison is against the slice of the current state.
+    pub fn subscribe_to<T, F>(&self, selector: F) -> broadcast::Receiver<T>
+    where
+        T: PartialEq + Clone + Send + Sync + 'static,
+        F: Fn(&S) -> T + Send + Sync + 'static,
+    {
+        let (tx, rx) = broadcast::channel(100);
+        let last = selector(&self.inner.state.read().unwrap());
+
+        self.inner
+            .slice_subscribers
+            .write()
+            .unwrap()
+            .push(Box::new(SliceSubscription { selector, last, tx }));
+
+        rx
+    }
+
+    /// Subscribe to state changes, skipping states equal to the last one sent.
+    ///
+    /// [`Store::subscribe`] gets the state after every applied action, even one that left
+    /// it as it was. This receiver only gets a state when it differs, by `PartialEq`, from
+    /// the last state sent to it, or from the current state for the first one. The last
+    /// state is kept per call, so receivers made with `resubscribe` share it. This is
+    /// [`Store::subscribe_to`] with the whole state as the slice.
+    pub fn subscribe_distinct(&self) -> broadcast::Receiver<S>
+    where
+        S: PartialEq,
+    {
+        self.subscribe_to(S::clone)
+    }
+
     /// Add middleware to the store
     pub fn add_middleware(&self, middleware: Middleware<S, A>) {
         let mut middleware_vec = self.inner.middleware.write().unwrap();
         middleware_vec.push(middleware);
     }
 
+    /// Get the recorded history, oldest first (empty unless enabled with [`Store::with_history`])
+    pub fn history(&self) -> Vec<HistoryEntry<S, A>> {
+        self.inner.history.read().unwrap().entries.clone()
+    }
+
+    /// Get the index of the current state in [`Store::history`]
+    pub fn history_index(&self) -> usize {
+        self.inner.history.read().unwrap().cursor
+    }
+
+    /// Restore the state recorded at `index` in the history and notify subscribers.
+    ///
+    /// Dispatching after a jump discards the entries after `index`.
+    /// Returns `false` if there's no entry at `index`.
+    pub fn jump_to(&self, index: usize) -> bool {
+        let state = {
+            let mut history = self.inner.history.write().unwrap();
+            let Some(entry) = history.entries.get(index) else {
+                return false;
+            };
+            let state = entry.state.clone();
+            history.cursor = index;
+            state
+        };
+
+        *self.inner.state.write().unwrap() = state.clone();
+        self.notify_subscribers(state);
+        true
+    }
+
+    /// Step back to the previous recorded state, returning `false` at the oldest one
+    pub fn undo(&self) -> bool {
+        let cursor = self.history_index();
+        cursor > 0 && self.jump_to(cursor - 1)
+    }
+
+    /// Step forward to the next recorded state, returning `false` at the newest one
+    pub fn redo(&self) -> bool {
+        self.jump_to(self.history_index() + 1)
+    }
+
+    fn record(&self, action: &A, state: &S) {
+        let mut history = self.inner.history.write().unwrap();
+        if history.limit == 0 {
+            return;
+        }
+
+        let cursor = history.cursor;
+        history.entries.truncate(cursor + 1);
+        history.entries.push(HistoryEntry {
+            action: Some(action.clone()),
+            state: state.clone(),
+        });
+
+        let overflow = history.entries.len().saturating_sub(history.limit);
+        history.entries.drain(..overflow);
+        history.cursor = history.entries.len() - 1;
+    }
+
     fn notify_subscribers(&self, state: S) {
+        if self.inner.coalesce.load(Ordering::Relaxed) {
+            self.inner.notify_pending.store(true, Ordering::Release);
+        } else {
+            self.send_to_subscribers(state);
+        }
+    }
+
+    fn send_to_subscribers(&self, state: S) {
         let mut subscribers = self.inner.subscribers.write().unwrap();
 
         // Keep only active subscribers
@@ -122,6 +359,12 @@ impl<S: AppState, A: Clone + Send + Sync + 'static> Store<S, A> {
         for tx in subscribers.iter() {
             let _ = tx.send(state.clone());
         }
+
+        let mut slice_subscribers = self.inner.slice_subscribers.write().unwrap();
+        slice_subscribers.retain(|subscriber| !subscriber.is_closed());
+        for subscriber in slice_subscribers.iter_mut() {
+            subscriber.notify(&state);
+        }
     }
 }
 
@@ -180,6 +423,191 @@ pub type ActionStore<S> = Store<S, Action>;
 /// Type alias for Action-based store connection
 pub type ActionStoreConnection<S> = StoreConnection<S, Action>;
 
+/// Somewhere to save the state of a store, for [`middleware::persist`].
+///
+/// Implemented for closures taking the state, e.g. one writing it to a file. Loading the
+/// state back is up to the app, usually before creating the store with it.
+pub trait StorePersistence<S>: Send + Sync + 'static {
+    /// Save `state`, replacing what was saved before
+    fn save(&self, state: &S) -> anyhow::Result<()>;
+}
+
+impl<S, F> StorePersistence<S> for F
+where
+    F: Fn(&S) -> anyhow::Result<()> + Send + Sync + 'static,
+{
+    fn save(&self, state: &S) -> anyhow::Result<()> {
+        self(state)
+    }
+}
+
+/// Prebuilt middleware for [`Store::add_middleware`]
+pub mod middleware {
+    use std::{
+        fmt,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use tokio::time::Instant;
+
+    use super::{AppState, Middleware, StorePersistence};
+
+    /// Log every action with the state before and after it as `tracing` debug events.
+    ///
+    /// Nothing is printed unless the app installs a `tracing` subscriber, e.g. one writing
+    /// to a file, so the log never draws over the UI. Use [`logger_with`] to send the lines
+    /// elsewhere.
+    pub fn logger<S, A>(
+        format: impl Fn(&A, &S, &S) -> String + Send + Sync + 'static,
+    ) -> Middleware<S, A>
+    where
+        S: AppState,
+        A: Clone + Send + Sync + 'static,
+    {
+        logger_with(format, |line| tracing::debug!(target: "weavetui::store", "{}", line))
+    }
+
+    /// Log every action with the state before and after it, passing each line to `write`.
+    ///
+    /// The after state is worked out by running the reducer on a copy of the state, so each
+    /// logged action is reduced twice, and formatting a large state on every dispatch adds
+    /// up too. Keep loggers out of release builds or log a small part of the state. The
+    /// middleware always lets the action through; add it last so that actions blocked by
+    /// other middleware aren't logged.
+    pub fn logger_with<S, A>(
+        format: impl Fn(&A, &S, &S) -> String + Send + Sync + 'static,
+        write: impl Fn(String) + Send + Sync + 'static,
+    ) -> Middleware<S, A>
+    where
+        S: AppState,
+        A: Clone + Send + Sync + 'static,
+    {
+        Box::new(move |state, action, store| {
+            let after = (store.inner.reducer)(state, action);
+            write(format(action, state, &after));
+            true
+        })
+    }
+
+    /// Save the state in the background some time after it changes, without blocking
+    /// `dispatch` on the write.
+    ///
+    /// Each action the middleware lets through schedules a save of the state it leads to,
+    /// worked out by running the reducer on a copy like [`logger_with`]. Saves are debounced:
+    /// the save runs once no action came in for `debounce`, and actions arriving meanwhile
+    /// push it back and are saved together, so at most one save is pending at a time. Saves
+    /// run one after the other on tokio's blocking threads. Outside a tokio runtime the state
+    /// is saved right away instead.
+    ///
+    /// Failed saves are passed to `on_error`, e.g. to send an `Action::Notify` warning through
+    /// the app's action channel, since printing would draw over the UI.
+    ///
+    /// A pending save is lost when the runtime shuts down first, e.g. when the app quits
+    /// within `debounce` of the last action, so save the final state yourself on exit if it
+    /// matters. Add this middleware last, so actions blocked by other middleware aren't saved.
+    pub fn persist<S, A>(
+        p: impl StorePersistence<S>,
+        debounce: Duration,
+        on_error: impl Fn(anyhow::Error) + Send + Sync + 'static,
+    ) -> Middleware<S, A>
+    where
+        S: AppState,
+        A: Clone + Send + Sync + 'static,
+    {
+        let saver = Arc::new(Saver {
+            persistence: p,
+            on_error,
+        });
+        let pending = Arc::new(Mutex::new(PendingSave::<S> {
+            state: None,
+            changed_at: Instant::now(),
+            scheduled: false,
+        }));
+
+        Box::new(move |state, action, store| {
+            let after = (store.inner.reducer)(state, action);
+            let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+                saver.save(&after);
+                return true;
+            };
+
+            let mut save_state = pending.lock().unwrap();
+            save_state.state = Some(after);
+            save_state.changed_at = Instant::now();
+            if !save_state.scheduled {
+                save_state.scheduled = true;
+                runtime.spawn(run_saves(saver.clone(), pending.clone(), debounce));
+            }
+            true
+        })
+    }
+
+    /// Where [`persist`] saves to, and what it does when saving fails
+    struct Saver<P, E> {
+        persistence: P,
+        on_error: E,
+    }
+
+    impl<P, E> Saver<P, E> {
+        fn save<S>(&self, state: &S)
+        where
+            P: StorePersistence<S>,
+            E: Fn(anyhow::Error),
+        {
+            if let Err(err) = self.persistence.save(state) {
+                (self.on_error)(err);
+            }
+        }
+    }
+
+    /// The newest state waiting to be saved by [`persist`]
+    struct PendingSave<S> {
+        state: Option<S>,
+        changed_at: Instant,
+        /// Whether a task is waiting to save `state`
+        scheduled: bool,
+    }
+
+    /// Save pending states once they stop changing for `debounce`, until none are left
+    async fn run_saves<S, P, E>(
+        saver: Arc<Saver<P, E>>,
+        pending: Arc<Mutex<PendingSave<S>>>,
+        debounce: Duration,
+    ) where
+        S: AppState,
+        P: StorePersistence<S>,
+        E: Fn(anyhow::Error) + Send + Sync + 'static,
+    {
+        loop {
+            let deadline = pending.lock().unwrap().changed_at + debounce;
+            tokio::time::sleep_until(deadline).await;
+
+            let state = {
+                let mut save_state = pending.lock().unwrap();
+                if save_state.changed_at + debounce > Instant::now() {
+                    continue; // Changed again while sleeping
+                }
+                match save_state.state.take() {
+                    Some(state) => state,
+                    None => {
+                        save_state.scheduled = false;
+                        return;
+                    }
+                }
+            };
+
+            let saver = saver.clone();
+            let _ = tokio::task::spawn_blocking(move || saver.save(&state)).await;
+        }
+    }
+
+    /// Format an action and both states with `Debug`, for [`logger`]
+    pub fn debug_format<S: fmt::Debug, A: fmt::Debug>(action: &A, before: &S, after: &S) -> String {
+        format!("{:?}: {:?} -> {:?}", action, before, after)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -239,4 +667,282 @@ mod tests {
 
         assert_eq!(store.get_state().counter, 1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_with_state_reads_the_current_state() {
+        let store = Store::new(
+            TestState {
+                counter: 0,
+                message: "Hello".to_string(),
+            },
+            test_reducer,
+        );
+        assert_eq!(store.with_state(|state| state.message.len()), 5);
+
+        store.dispatch(&TestAction::Increment);
+        assert_eq!(store.with_state(|state| state.counter), 1);
+    }
+
+    #[test]
+    fn test_history_undo_redo_and_limit() {
+        let initial_state = TestState {
+            counter: 0,
+            message: "Hello".to_string(),
+        };
+
+        let store = Store::new(initial_state, test_reducer).with_history(3);
+        let mut rx = store.subscribe();
+        store.dispatch(&TestAction::Increment);
+        store.dispatch(&TestAction::Increment);
+        store.dispatch(&TestAction::Increment);
+
+        // The initial entry was evicted to stay within the limit
+        let counters = store.history().iter().map(|e| e.state.counter).collect::<Vec<_>>();
+        assert_eq!(counters, vec![1, 2, 3]);
+
+        assert!(store.undo());
+        assert!(store.undo());
+        assert!(!store.undo());
+        assert_eq!(store.get_state().counter, 1);
+
+        assert!(store.redo());
+        assert_eq!(store.get_state().counter, 2);
+
+        // Jumps notify subscribers like dispatches do
+        let mut last = None;
+        while let Ok(state) = rx.try_recv() {
+            last = Some(state.counter);
+        }
+        assert_eq!(last, Some(2));
+
+        // Dispatching after going back discards the redo branch
+        store.dispatch(&TestAction::Decrement);
+        let counters = store.history().iter().map(|e| e.state.counter).collect::<Vec<_>>();
+        assert_eq!(counters, vec![1, 2, 1]);
+        assert!(!store.redo());
+    }
+
+    #[test]
+    fn test_slice_subscribers_only_see_changes_to_their_slice() {
+        let initial_state = TestState {
+            counter: 0,
+            message: "Hello".to_string(),
+        };
+
+        let store = Store::new(initial_state, test_reducer);
+        let mut counter_rx = store.subscribe_to(|state: &TestState| state.counter);
+        let mut message_rx = store.subscribe_to(|state: &TestState| state.message.clone());
+
+        store.dispatch(&TestAction::Increment);
+        store.dispatch(&TestAction::SetMessage("Hello".to_string()));
+        store.dispatch(&TestAction::SetMessage("World".to_string()));
+        store.dispatch(&TestAction::Increment);
+
+        assert_eq!(counter_rx.try_recv().ok(), Some(1));
+        assert_eq!(counter_rx.try_recv().ok(), Some(2));
+        assert!(counter_rx.try_recv().is_err());
+
+        // Setting the same message again isn't a change
+        assert_eq!(message_rx.try_recv().ok(), Some("World".to_string()));
+        assert!(message_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_distinct_subscribers_skip_unchanged_states() {
+        let store = Store::new(
+            TestState {
+                counter: 0,
+                message: "Hello".to_string(),
+            },
+            test_reducer,
+        );
+        let mut all_rx = store.subscribe();
+        let mut distinct_rx = store.subscribe_distinct();
+
+        store.dispatch(&TestAction::SetMessage("Hello".to_string()));
+        store.dispatch(&TestAction::Increment);
+        store.dispatch(&TestAction::Decrement);
+        store.dispatch(&TestAction::Decrement);
+
+        let counters = |rx: &mut broadcast::Receiver<TestState>| {
+            std::iter::from_fn(|| rx.try_recv().ok()).map(|state| state.counter).collect::<Vec<_>>()
+        };
+        assert_eq!(counters(&mut all_rx), [0, 1, 0, -1]);
+        // Only the first dispatch left the state as it was
+        assert_eq!(counters(&mut distinct_rx), [1, 0, -1]);
+    }
+
+    #[test]
+    fn test_logger_middleware_sees_both_states() {
+        let store = Store::new(
+            TestState {
+                counter: 0,
+                message: "Hello".to_string(),
+            },
+            test_reducer,
+        );
+        let lines = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = lines.clone();
+        store.add_middleware(middleware::logger_with(
+            |action: &TestAction, before: &TestState, after: &TestState| {
+                format!("{:?} {} -> {}", action, before.counter, after.counter)
+            },
+            move |line| sink.lock().unwrap().push(line),
+        ));
+
+        store.dispatch(&TestAction::Increment);
+        store.dispatch(&TestAction::Decrement);
+        assert_eq!(store.get_state().counter, 0);
+        assert_eq!(*lines.lock().unwrap(), ["Increment 0 -> 1", "Decrement 1 -> 0"]);
+
+        let line = middleware::debug_format(&TestAction::Increment, &1, &2);
+        assert_eq!(line, "Increment: 1 -> 2");
+    }
+
+    #[test]
+    fn test_dispatch_all_notifies_once_with_the_final_state() {
+        let store = Store::new(
+            TestState {
+                counter: 0,
+                message: "Hello".to_string(),
+            },
+            test_reducer,
+        );
+        let mut rx = store.subscribe();
+        // Middleware still sees each action, with the state left by the previous one
+        store.add_middleware(Box::new(|state: &TestState, action: &TestAction, _store| {
+            !matches!(action, TestAction::Decrement) || state.counter > 1
+        }));
+
+        store.dispatch_all(&[
+            TestAction::Decrement,
+            TestAction::Increment,
+            TestAction::Increment,
+            TestAction::Decrement,
+            TestAction::SetMessage("Seeded".to_string()),
+        ]);
+
+        let state = rx.try_recv().unwrap();
+        assert_eq!((state.counter, state.message.as_str()), (1, "Seeded"));
+        assert!(rx.try_recv().is_err());
+        assert_eq!(store.get_state(), state);
+    }
+
+    #[test]
+    fn test_concurrent_dispatches_are_not_lost() {
+        let store = Store::new(
+            TestState {
+                counter: 0,
+                message: "Hello".to_string(),
+            },
+            test_reducer,
+        );
+        // Give other threads a chance to dispatch in the middle of a batch
+        store.add_middleware(Box::new(|_: &TestState, _: &TestAction, _store| {
+            std::thread::yield_now();
+            true
+        }));
+
+        let threads = (0..4)
+            .map(|_| {
+                let store = store.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        store.dispatch_all(&[TestAction::Increment, TestAction::Increment]);
+                        store.dispatch(&TestAction::Increment);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(store.get_state().counter, 4 * 100 * 3);
+    }
+
+    #[test]
+    fn test_coalesced_notifications_wait_for_a_flush() {
+        let store = Store::new(
+            TestState {
+                counter: 0,
+                message: "Hello".to_string(),
+            },
+            test_reducer,
+        )
+        .with_coalesced_notify(true);
+        let mut rx = store.subscribe();
+
+        store.flush_notifications();
+        assert!(rx.try_recv().is_err());
+
+        store.dispatch(&TestAction::Increment);
+        store.dispatch(&TestAction::Increment);
+        assert!(rx.try_recv().is_err());
+        assert_eq!(store.get_state().counter, 2);
+
+        store.flush_notifications();
+        assert_eq!(rx.try_recv().unwrap().counter, 2);
+        assert!(rx.try_recv().is_err());
+
+        // Nothing changed since the last flush
+        store.flush_notifications();
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_persist_passes_failed_saves_to_the_error_callback() {
+        let store = Store::new(
+            TestState {
+                counter: 0,
+                message: "Hello".to_string(),
+            },
+            test_reducer,
+        );
+        let errors = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = errors.clone();
+        store.add_middleware(middleware::persist(
+            |_: &TestState| Err(anyhow::anyhow!("disk full")),
+            std::time::Duration::from_millis(20),
+            move |err| sink.lock().unwrap().push(err.to_string()),
+        ));
+
+        // Outside a runtime the state is saved right away
+        store.dispatch(&TestAction::Increment);
+        assert_eq!(*errors.lock().unwrap(), ["disk full"]);
+        assert_eq!(store.get_state().counter, 1);
+    }
+
+    #[tokio::test]
+    async fn test_persist_saves_the_last_state_once_changes_settle() {
+        let store = Store::new(
+            TestState {
+                counter: 0,
+                message: "Hello".to_string(),
+            },
+            test_reducer,
+        );
+        let saved = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = saved.clone();
+        store.add_middleware(middleware::persist(
+            move |state: &TestState| {
+                sink.lock().unwrap().push(state.counter);
+                Ok(())
+            },
+            std::time::Duration::from_millis(20),
+            |err| panic!("{}", err),
+        ));
+
+        for _ in 0..3 {
+            store.dispatch(&TestAction::Increment);
+        }
+        assert!(saved.lock().unwrap().is_empty());
+
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert_eq!(*saved.lock().unwrap(), [3]);
+
+        store.dispatch(&TestAction::Decrement);
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert_eq!(*saved.lock().unwrap(), [3, 2]);
+    }
+}
@@ -0,0 +1,125 @@
+//! Seedable random numbers shared by the app and its components.
+
+use rand::{
+    rngs::SmallRng,
+    seq::{IndexedRandom, SliceRandom},
+    Rng as _, RngCore, SeedableRng,
+};
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    ops::Range,
+    sync::{Arc, Mutex, MutexGuard},
+    time::SystemTime,
+};
+
+/// A small, fast pseudo-random number generator, wrapping `rand`'s `SmallRng`.
+///
+/// Seeded with [`Rng::seed_from_u64`], it produces the same numbers on every run, which
+/// makes randomized visuals like animations or shuffles reproducible in tests. It's not
+/// cryptographically secure: don't use it for secrets, tokens or anything an attacker
+/// shouldn't guess.
+#[derive(Clone, Debug)]
+pub struct Rng(SmallRng);
+
+impl Rng {
+    /// Create a generator that always produces the same numbers for the same seed
+    pub fn seed_from_u64(seed: u64) -> Self {
+        Self(SmallRng::seed_from_u64(seed))
+    }
+
+    /// Create a generator seeded differently on each run
+    pub fn from_entropy() -> Self {
+        let mut hasher = RandomState::new().build_hasher();
+        if let Ok(elapsed) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+            hasher.write_u128(elapsed.as_nanos());
+        }
+        Self::seed_from_u64(hasher.finish())
+    }
+
+    /// Get the next random `u64`
+    pub fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    /// Get a random number from 0 (inclusive) to 1 (exclusive)
+    pub fn next_f64(&mut self) -> f64 {
+        self.0.random()
+    }
+
+    /// Get a random number in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    pub fn gen_range(&mut self, range: Range<usize>) -> usize {
+        assert!(!range.is_empty(), "cannot pick from an empty range {:?}", range);
+        self.0.random_range(range)
+    }
+
+    /// Return `true` with probability `p`, from 0 to 1
+    pub fn gen_bool(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    /// Pick a random item, or `None` if `items` is empty
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        items.choose(&mut self.0)
+    }
+
+    /// Shuffle `items` in place
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        items.shuffle(&mut self.0);
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::from_entropy()
+    }
+}
+
+/// The app's random number generator, shared with every component.
+///
+/// Cloning is cheap and every clone draws from the same generator, so with a fixed seed the
+/// numbers depend only on the order components ask for them.
+#[derive(Clone, Debug, Default)]
+pub struct SharedRng(Arc<Mutex<Rng>>);
+
+impl SharedRng {
+    /// Lock the generator to draw numbers from it
+    pub fn lock(&self) -> MutexGuard<'_, Rng> {
+        self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Restart the generator from `seed`, for every clone
+    pub fn reseed(&self, seed: u64) {
+        *self.lock() = Rng::seed_from_u64(seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_numbers() {
+        let mut a = Rng::seed_from_u64(42);
+        let mut b = Rng::seed_from_u64(42);
+        let numbers = (0..8).map(|_| a.next_u64()).collect::<Vec<_>>();
+        assert_eq!(numbers, (0..8).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(Rng::seed_from_u64(43).next_u64(), numbers[0]);
+
+        for _ in 0..1000 {
+            assert!((5..8).contains(&a.gen_range(5..8)));
+            assert!((0.0..1.0).contains(&a.next_f64()));
+        }
+
+        let mut items = (0..20).collect::<Vec<_>>();
+        a.shuffle(&mut items);
+        let mut sorted = items.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+        assert_eq!(a.choose::<u8>(&[]), None);
+    }
+}
@@ -1,7 +1,7 @@
 //! Theme management for the `weavetui` framework.
 
-use ratatui::style::{Color, Style};
-use std::collections::HashMap;
+use ratatui::style::{Color, Modifier, Style};
+use std::{collections::HashMap, sync::Arc};
 
 #[derive(Debug, Default, Clone)]
 pub struct Theme {
@@ -26,6 +26,11 @@ impl Theme {
         self
     }
 
+    /// Add a style described by a [`StyleSpec`] to this theme (chainable)
+    pub fn add_style_spec(self, name: &str, spec: StyleSpec) -> Self {
+        self.add_style(name, spec.into())
+    }
+
     /// Add a color to this theme (chainable)
     pub fn add_color(mut self, name: &str, color: Color) -> Self {
         self.colors.insert(name.to_string(), color);
@@ -41,11 +46,495 @@ impl Theme {
     pub fn get_color(&self, key: &str) -> Color {
         self.colors.get(key).cloned().unwrap_or(Color::Reset)
     }
+
+    /// Get the color of a semantic role, falling back as described on [`ColorRole`]
+    pub fn role(&self, role: ColorRole) -> Color {
+        if let Some(color) = self.colors.get(role.key()) {
+            return *color;
+        }
+        match role {
+            ColorRole::Secondary => self.role(ColorRole::Primary),
+            ColorRole::Accent => self.role(ColorRole::Secondary),
+            ColorRole::Primary => Color::Blue,
+            ColorRole::Success => Color::Green,
+            ColorRole::Warning => Color::Yellow,
+            ColorRole::Error => Color::Red,
+            ColorRole::Info => Color::Cyan,
+            ColorRole::Muted => Color::DarkGray,
+            ColorRole::Selection => self.role(ColorRole::Accent),
+            ColorRole::Focus => self.role(ColorRole::Primary),
+            ColorRole::Background | ColorRole::Foreground => Color::Reset,
+        }
+    }
+
+    /// Get the main color of the UI, e.g. for focused borders
+    pub fn primary(&self) -> Color {
+        self.role(ColorRole::Primary)
+    }
+
+    /// Get the color for less prominent elements
+    pub fn secondary(&self) -> Color {
+        self.role(ColorRole::Secondary)
+    }
+
+    /// Get the color for completed or healthy states
+    pub fn success(&self) -> Color {
+        self.role(ColorRole::Success)
+    }
+
+    /// Get the color for states that need attention
+    pub fn warning(&self) -> Color {
+        self.role(ColorRole::Warning)
+    }
+
+    /// Get the color for failures
+    pub fn error(&self) -> Color {
+        self.role(ColorRole::Error)
+    }
+
+    /// Get the background color
+    pub fn background(&self) -> Color {
+        self.role(ColorRole::Background)
+    }
+
+    /// Get the text color
+    pub fn foreground(&self) -> Color {
+        self.role(ColorRole::Foreground)
+    }
+
+    /// Get the color for highlights that stand out from `primary`
+    pub fn accent(&self) -> Color {
+        self.role(ColorRole::Accent)
+    }
+
+    /// Get the color for neutral notices
+    pub fn info(&self) -> Color {
+        self.role(ColorRole::Info)
+    }
+
+    /// Get the color for disabled or de-emphasized elements
+    pub fn muted(&self) -> Color {
+        self.role(ColorRole::Muted)
+    }
+
+    /// Get the background color of selected items
+    pub fn selection(&self) -> Color {
+        self.role(ColorRole::Selection)
+    }
+
+    /// Get the background color of focused inputs
+    pub fn focus(&self) -> Color {
+        self.role(ColorRole::Focus)
+    }
+
+    /// Fold several named styles together, later keys overriding earlier ones where set.
+    ///
+    /// Missing keys are skipped, so `["list.item", "list.item.selected"]` yields the base
+    /// item style when no selected style is defined.
+    pub fn compose_style(&self, keys: &[&str]) -> Style {
+        keys.iter()
+            .filter_map(|key| self.styles.get(*key))
+            .fold(Style::default(), |style, next| style.patch(*next))
+    }
+}
+
+/// A semantic color role, so built-in components and apps can share colors with any theme
+/// that sets them.
+///
+/// Roles are stored as colors under their [`ColorRole::key`], e.g. `primary` in a theme
+/// file's `[colors]` table. A theme that doesn't set a role falls back: `secondary` to
+/// `primary`, `accent` to `secondary`, `primary` to blue, `success` to green, `warning` to
+/// yellow, `error` to red, `info` to cyan, `muted` to dark gray, `selection` to `accent`,
+/// `focus` to `primary`, and `background` and `foreground` to the terminal's own colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorRole {
+    Primary,
+    Secondary,
+    Success,
+    Warning,
+    Error,
+    Background,
+    Foreground,
+    Accent,
+    Info,
+    Muted,
+    Selection,
+    Focus,
+}
+
+impl ColorRole {
+    /// Every role, in declaration order
+    pub const ALL: [ColorRole; 12] = [
+        ColorRole::Primary,
+        ColorRole::Secondary,
+        ColorRole::Success,
+        ColorRole::Warning,
+        ColorRole::Error,
+        ColorRole::Background,
+        ColorRole::Foreground,
+        ColorRole::Accent,
+        ColorRole::Info,
+        ColorRole::Muted,
+        ColorRole::Selection,
+        ColorRole::Focus,
+    ];
+
+    /// Get the color key the role is stored under
+    pub fn key(self) -> &'static str {
+        match self {
+            ColorRole::Primary => "primary",
+            ColorRole::Secondary => "secondary",
+            ColorRole::Success => "success",
+            ColorRole::Warning => "warning",
+            ColorRole::Error => "error",
+            ColorRole::Background => "background",
+            ColorRole::Foreground => "foreground",
+            ColorRole::Accent => "accent",
+            ColorRole::Info => "info",
+            ColorRole::Muted => "muted",
+            ColorRole::Selection => "selection",
+            ColorRole::Focus => "focus",
+        }
+    }
+}
+
+/// A style as written in theme definitions: optional colors plus modifiers to add and remove.
+///
+/// Removed modifiers survive [`Style::patch`], so a spec for a state like `selected` can turn
+/// off modifiers of the base style it's composed onto.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StyleSpec {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub add_modifiers: Modifier,
+    pub sub_modifiers: Modifier,
+}
+
+impl StyleSpec {
+    /// Parse modifier names separated by `|` or `,`, like `"bold | italic | underlined"`.
+    ///
+    /// Names ignore case and accept `-` for `_` (`slow-blink`). An empty string is no modifiers.
+    pub fn parse_modifiers(raw: &str) -> anyhow::Result<Modifier> {
+        raw.split(['|', ','])
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .try_fold(Modifier::empty(), |modifiers, name| {
+                let modifier = Modifier::from_name(&name.to_ascii_uppercase().replace('-', "_"))
+                    .ok_or_else(|| anyhow::anyhow!("unknown modifier `{}`", name))?;
+                Ok(modifiers | modifier)
+            })
+    }
+}
+
+impl From<StyleSpec> for Style {
+    fn from(spec: StyleSpec) -> Self {
+        let mut style = Style::default()
+            .add_modifier(spec.add_modifiers)
+            .remove_modifier(spec.sub_modifiers);
+        style.fg = spec.fg;
+        style.bg = spec.bg;
+        style
+    }
+}
+
+#[cfg(feature = "theme-files")]
+impl Theme {
+    /// Parse a theme from TOML.
+    ///
+    /// ```toml
+    /// name = "dark"
+    ///
+    /// [colors]
+    /// primary = "#5f87ff"
+    ///
+    /// [styles]
+    /// "menu.item.selected" = { fg = "black", bg = "light-blue", modifiers = ["bold"] }
+    /// "menu.item.disabled" = { modifiers = "dim | italic", sub_modifiers = "bold" }
+    /// ```
+    ///
+    /// Styles are read as a [`StyleSpec`]: `modifiers` are added and `sub_modifiers` removed,
+    /// each given as an array of names or a `|` separated string.
+    /// Colors accept names (`red`, `light-blue`), hex (`#rrggbb`) and indexed (`42`) values.
+    /// A missing `name` leaves the theme unnamed; [`Theme::from_file`] falls back to the file stem.
+    pub fn from_toml_str(source: &str) -> anyhow::Result<Self> {
+        let table = source.parse::<toml::Table>()?;
+        let mut theme = Theme::new(table.get("name").and_then(|v| v.as_str()).unwrap_or_default());
+
+        if let Some(colors) = table.get("colors") {
+            let colors = colors
+                .as_table()
+                .ok_or_else(|| anyhow::anyhow!("`colors` must be a table"))?;
+            for (key, value) in colors {
+                theme.colors.insert(key.clone(), parse_color_value(key, value)?);
+            }
+        }
+
+        if let Some(styles) = table.get("styles") {
+            let styles = styles
+                .as_table()
+                .ok_or_else(|| anyhow::anyhow!("`styles` must be a table"))?;
+            for (key, value) in styles {
+                theme.styles.insert(key.clone(), parse_style(key, value)?);
+            }
+        }
+
+        Ok(theme)
+    }
+
+    /// Load a theme from a TOML file (see [`Theme::from_toml_str`] for the format)
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+        let mut theme = Self::from_toml_str(&source)
+            .map_err(|err| anyhow::anyhow!("{}: {}", path.display(), err))?;
+
+        if theme.name.is_empty() {
+            theme.name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+        }
+
+        Ok(theme)
+    }
+}
+
+#[cfg(feature = "theme-files")]
+fn parse_color_value(key: &str, value: &toml::Value) -> anyhow::Result<Color> {
+    let raw = value
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("color for `{}` must be a string: {}", key, value))?;
+    parse_color(raw).map_err(|err| anyhow::anyhow!("invalid color for `{}`: {}", key, err))
+}
+
+#[cfg(feature = "theme-files")]
+fn parse_style(key: &str, value: &toml::Value) -> anyhow::Result<Style> {
+    let table = value
+        .as_table()
+        .ok_or_else(|| anyhow::anyhow!("style `{}` must be a table", key))?;
+
+    let mut spec = StyleSpec::default();
+    for (field, value) in table {
+        match field.as_str() {
+            "fg" => spec.fg = Some(parse_color_value(key, value)?),
+            "bg" => spec.bg = Some(parse_color_value(key, value)?),
+            "modifiers" => spec.add_modifiers = parse_modifiers_value(key, field, value)?,
+            "sub_modifiers" => spec.sub_modifiers = parse_modifiers_value(key, field, value)?,
+            other => anyhow::bail!("unknown field `{}` in style `{}`", other, key),
+        }
+    }
+
+    Ok(spec.into())
+}
+
+/// Parse modifiers given as an array of names or a `|` separated string
+#[cfg(feature = "theme-files")]
+fn parse_modifiers_value(key: &str, field: &str, value: &toml::Value) -> anyhow::Result<Modifier> {
+    let invalid = |err| anyhow::anyhow!("invalid `{}` for `{}`: {}", field, key, err);
+
+    match value {
+        toml::Value::String(raw) => StyleSpec::parse_modifiers(raw).map_err(invalid),
+        toml::Value::Array(names) => names.iter().try_fold(Modifier::empty(), |modifiers, name| {
+            let name = name
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("`{}` of `{}` must be strings", field, key))?;
+            Ok(modifiers | StyleSpec::parse_modifiers(name).map_err(invalid)?)
+        }),
+        _ => anyhow::bail!("`{}` of `{}` must be an array or a string", field, key),
+    }
+}
+
+/// Watches a theme file and reloads it once edits settle.
+///
+/// The file's directory is watched rather than the file itself, so editors that save by
+/// replacing the file are picked up too.
+#[cfg(feature = "watch")]
+pub struct ThemeWatcher {
+    path: std::path::PathBuf,
+    changes: std::sync::mpsc::Receiver<()>,
+    last_change: Option<std::time::Instant>,
+    _watcher: notify::RecommendedWatcher,
+}
+
+#[cfg(feature = "watch")]
+impl ThemeWatcher {
+    /// Start watching a theme file
+    pub fn new(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        use notify::Watcher;
+
+        let path = path.as_ref().to_path_buf();
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("{} is not a file", path.display()))?
+            .to_owned();
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => std::path::PathBuf::from("."),
+        };
+
+        let (tx, changes) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            // Reading the file on reload shows up as access events, which mustn't retrigger it
+            if let Ok(event) = res {
+                let ours = event
+                    .paths
+                    .iter()
+                    .any(|changed| changed.file_name() == Some(file_name.as_os_str()));
+                if ours && !event.kind.is_access() {
+                    let _ = tx.send(());
+                }
+            }
+        })?;
+        watcher.watch(&dir, notify::RecursiveMode::NonRecursive)?;
+
+        Ok(Self {
+            path,
+            changes,
+            last_change: None,
+            _watcher: watcher,
+        })
+    }
+
+    /// Get the reloaded theme once the file has been quiet for `debounce` after a change.
+    ///
+    /// Returns `None` while there's nothing new to load.
+    pub fn poll(&mut self, debounce: std::time::Duration) -> Option<anyhow::Result<Theme>> {
+        if self.changes.try_iter().count() > 0 {
+            self.last_change = Some(std::time::Instant::now());
+        }
+
+        let settled = self.last_change?.elapsed() >= debounce;
+        if !settled {
+            return None;
+        }
+        self.last_change = None;
+        Some(Theme::from_file(&self.path))
+    }
+}
+
+#[cfg(feature = "watch")]
+impl std::fmt::Debug for ThemeWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ThemeWatcher")
+            .field("path", &self.path)
+            .field("last_change", &self.last_change)
+            .finish()
+    }
+}
+
+/// Named colors with the canonical spelling used by [`color_to_string`]
+const NAMED_COLORS: [(&str, Color); 17] = [
+    ("reset", Color::Reset),
+    ("black", Color::Black),
+    ("red", Color::Red),
+    ("green", Color::Green),
+    ("yellow", Color::Yellow),
+    ("blue", Color::Blue),
+    ("magenta", Color::Magenta),
+    ("cyan", Color::Cyan),
+    ("gray", Color::Gray),
+    ("dark_gray", Color::DarkGray),
+    ("light_red", Color::LightRed),
+    ("light_green", Color::LightGreen),
+    ("light_yellow", Color::LightYellow),
+    ("light_blue", Color::LightBlue),
+    ("light_magenta", Color::LightMagenta),
+    ("light_cyan", Color::LightCyan),
+    ("white", Color::White),
+];
+
+/// Parse a color from a string.
+///
+/// Accepts `#rrggbb` and `#rgb` hex, `rgb(r, g, b)`, a palette index from `0` to `255`,
+/// or a named color such as `red` or `light-blue` (case, `-`, `_` and spaces are ignored,
+/// and `grey` is accepted for `gray`).
+pub fn parse_color(raw: &str) -> anyhow::Result<Color> {
+    let value = raw.trim();
+
+    if let Some(hex) = value.strip_prefix('#') {
+        return parse_hex_color(hex).ok_or_else(|| {
+            anyhow::anyhow!("invalid hex color `{}`: expected #rgb or #rrggbb", raw)
+        });
+    }
+
+    if let Some(args) = value
+        .strip_prefix("rgb(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let parts = args.split(',').map(str::trim).collect::<Vec<_>>();
+        if parts.len() != 3 {
+            anyhow::bail!("invalid color `{}`: rgb() takes three components", raw);
+        }
+        let mut channels = [0u8; 3];
+        for (channel, part) in channels.iter_mut().zip(parts) {
+            *channel = part.parse().map_err(|_| {
+                anyhow::anyhow!("invalid color `{}`: `{}` is not a number from 0 to 255", raw, part)
+            })?;
+        }
+        return Ok(Color::Rgb(channels[0], channels[1], channels[2]));
+    }
+
+    if value.chars().all(|c| c.is_ascii_digit()) && !value.is_empty() {
+        return value
+            .parse()
+            .map(Color::Indexed)
+            .map_err(|_| anyhow::anyhow!("color index `{}` is out of range 0-255", raw));
+    }
+
+    let name = value
+        .chars()
+        .filter(|c| !matches!(c, '-' | '_' | ' '))
+        .collect::<String>()
+        .to_ascii_lowercase()
+        .replace("grey", "gray");
+    NAMED_COLORS
+        .iter()
+        .find(|(known, _)| known.replace('_', "") == name)
+        .map(|(_, color)| *color)
+        .ok_or_else(|| anyhow::anyhow!("unknown color `{}`", raw))
+}
+
+fn parse_hex_color(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    match hex.len() {
+        6 => Some(Color::Rgb(channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?)),
+        // Each digit is doubled, so `#f80` is `#ff8800`
+        3 => Some(Color::Rgb(
+            channel(&hex[0..1])? * 0x11,
+            channel(&hex[1..2])? * 0x11,
+            channel(&hex[2..3])? * 0x11,
+        )),
+        _ => None,
+    }
+}
+
+/// Format a color in a form [`parse_color`] reads back to the same color.
+///
+/// RGB colors become `#rrggbb`, indexed colors their number and named colors their
+/// snake_case name.
+pub fn color_to_string(color: Color) -> String {
+    match color {
+        Color::Rgb(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        Color::Indexed(index) => index.to_string(),
+        named => NAMED_COLORS
+            .iter()
+            .find(|(_, color)| *color == named)
+            .map(|(name, _)| name.to_string())
+            .unwrap_or_default(),
+    }
 }
 
+/// The themes an app can switch between, and which one is active.
+///
+/// Clones share the themes until one of them adds a theme, so handing a copy to every
+/// component is cheap.
 #[derive(Debug, Default, Clone)]
 pub struct ThemeManager {
-    themes: HashMap<String, Theme>,
+    themes: Arc<HashMap<String, Theme>>,
     active_theme_name: Option<String>,
 }
 
@@ -57,7 +546,7 @@ impl ThemeManager {
 
     /// Add a theme to the manager
     pub fn add_theme(&mut self, theme: Theme) {
-        self.themes.insert(theme.name.clone(), theme);
+        Arc::make_mut(&mut self.themes).insert(theme.name.clone(), theme);
     }
 
     /// Set which theme is currently active
@@ -89,8 +578,293 @@ impl ThemeManager {
             .unwrap_or(Color::Reset)
     }
 
+    /// Get the style marking a state like selection or focus, for built-in components to
+    /// compose their own state styles onto.
+    ///
+    /// When the current theme sets the role's color it's used as the background; otherwise
+    /// the style is reversed video, which shows in any terminal.
+    pub fn highlight(&self, role: ColorRole) -> Style {
+        match self
+            .get_active_theme()
+            .and_then(|theme| theme.colors.get(role.key()))
+        {
+            Some(color) => Style::default().bg(*color),
+            None => Style::default().add_modifier(Modifier::REVERSED),
+        }
+    }
+
+    /// Get the color of a semantic role from the current theme, or the role's fallback
+    /// color when no theme is active (see [`ColorRole`])
+    pub fn get_current_role(&self, role: ColorRole) -> Color {
+        match self.get_active_theme() {
+            Some(theme) => theme.role(role),
+            None => Theme::default().role(role),
+        }
+    }
+
+    /// Compose named styles from the current theme (see [`Theme::compose_style`])
+    pub fn compose_style(&self, keys: &[&str]) -> Style {
+        self.get_active_theme()
+            .map(|theme| theme.compose_style(keys))
+            .unwrap_or_default()
+    }
+
+    /// Get every named style of the active theme, e.g. to export it or to look styles up
+    /// once instead of per cell.
+    ///
+    /// Each entry is the final style [`ThemeManager::get_current_style`] returns for its
+    /// key, so the map can stand in for the theme. Keys missing from it resolve to the
+    /// default style. Empty when no theme is active.
+    pub fn resolve(&self) -> HashMap<String, Style> {
+        self.get_active_theme()
+            .map(|theme| {
+                theme
+                    .styles
+                    .keys()
+                    .map(|key| (key.clone(), self.get_current_style(key)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Check if there's an active theme set
     pub fn has_active_theme(&self) -> bool {
         self.active_theme_name.is_some()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compose_style_patches_in_order() {
+        let theme = Theme::new("test")
+            .add_style("item", Style::default().fg(Color::White).bg(Color::Black))
+            .add_style(
+                "item.selected",
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            );
+
+        let style = theme.compose_style(&["item", "missing", "item.selected"]);
+        assert_eq!(style.fg, Some(Color::Yellow));
+        assert_eq!(style.bg, Some(Color::Black));
+        assert!(style.add_modifier.contains(Modifier::BOLD));
+
+        assert_eq!(theme.compose_style(&["missing"]), Style::default());
+    }
+
+    #[test]
+    fn test_theme_manager_clones_share_themes_until_changed() {
+        let mut manager = ThemeManager::new();
+        manager.add_theme(Theme::new("dark").add_style("text", Style::default().fg(Color::White)));
+        manager.set_active_theme("dark");
+
+        let mut copy = manager.clone();
+        assert!(Arc::ptr_eq(&manager.themes, &copy.themes));
+
+        copy.add_theme(Theme::new("dark").add_style("text", Style::default().fg(Color::Black)));
+        assert!(!Arc::ptr_eq(&manager.themes, &copy.themes));
+        assert_eq!(manager.get_current_style("text").fg, Some(Color::White));
+        assert_eq!(copy.get_current_style("text").fg, Some(Color::Black));
+    }
+
+    #[test]
+    fn test_color_roles_fall_back() {
+        let theme = Theme::new("roles")
+            .add_color("primary", Color::Magenta)
+            .add_color("error", Color::LightRed);
+        assert_eq!(theme.primary(), Color::Magenta);
+        assert_eq!(theme.secondary(), Color::Magenta);
+        assert_eq!(theme.accent(), Color::Magenta);
+        assert_eq!(theme.error(), Color::LightRed);
+        assert_eq!(theme.success(), Color::Green);
+        assert_eq!(theme.background(), Color::Reset);
+        assert_eq!(theme.muted(), Color::DarkGray);
+
+        let theme = theme.add_color("secondary", Color::Cyan);
+        assert_eq!(theme.accent(), Color::Cyan);
+
+        let mut manager = ThemeManager::new();
+        assert_eq!(manager.get_current_role(ColorRole::Warning), Color::Yellow);
+        manager.add_theme(theme);
+        manager.set_active_theme("roles");
+        assert_eq!(manager.get_current_role(ColorRole::Secondary), Color::Cyan);
+        assert_eq!(ColorRole::ALL.map(ColorRole::key)[7], "accent");
+
+        // Highlights are reversed until the theme sets the role's color
+        let reversed = Style::default().add_modifier(Modifier::REVERSED);
+        assert_eq!(manager.highlight(ColorRole::Selection), reversed);
+        manager.add_theme(Theme::new("roles").add_color("selection", Color::DarkGray));
+        assert_eq!(manager.highlight(ColorRole::Selection), Style::default().bg(Color::DarkGray));
+        assert_eq!(manager.highlight(ColorRole::Focus), reversed);
+    }
+
+    #[test]
+    fn test_resolve_flattens_the_active_theme() {
+        let mut manager = ThemeManager::new();
+        assert!(manager.resolve().is_empty());
+
+        let light = Theme::new("light").add_style("title", Style::default().fg(Color::Black));
+        manager.add_theme(light);
+        manager.add_theme(
+            Theme::new("dark")
+                .add_style("title", Style::default().fg(Color::White))
+                .add_style("border", Style::default().fg(Color::DarkGray)),
+        );
+        manager.set_active_theme("dark");
+
+        let resolved = manager.resolve();
+        assert_eq!(resolved.len(), 2);
+        for (key, style) in &resolved {
+            assert_eq!(*style, manager.get_current_style(key));
+        }
+        assert_eq!(resolved["title"].fg, Some(Color::White));
+    }
+
+    #[test]
+    fn test_style_spec_modifiers() {
+        assert_eq!(
+            StyleSpec::parse_modifiers("bold | Italic, slow-blink").unwrap(),
+            Modifier::BOLD | Modifier::ITALIC | Modifier::SLOW_BLINK
+        );
+        assert_eq!(StyleSpec::parse_modifiers(" ").unwrap(), Modifier::empty());
+        assert!(StyleSpec::parse_modifiers("bold | shiny").is_err());
+
+        // Removed modifiers carry through composition
+        let theme = Theme::new("test")
+            .add_style("item", Style::default().add_modifier(Modifier::BOLD | Modifier::DIM))
+            .add_style_spec(
+                "item.selected",
+                StyleSpec {
+                    fg: Some(Color::Yellow),
+                    add_modifiers: Modifier::REVERSED,
+                    sub_modifiers: Modifier::DIM,
+                    ..Default::default()
+                },
+            );
+        let style = theme.compose_style(&["item", "item.selected"]);
+        assert_eq!(style.fg, Some(Color::Yellow));
+        assert_eq!(style.add_modifier, Modifier::BOLD | Modifier::REVERSED);
+        assert!(style.sub_modifier.contains(Modifier::DIM));
+    }
+
+    #[test]
+    fn test_parse_color_formats() {
+        let cases = [
+            ("#5f87ff", Color::Rgb(0x5f, 0x87, 0xff)),
+            ("#5F87FF", Color::Rgb(0x5f, 0x87, 0xff)),
+            ("#f80", Color::Rgb(0xff, 0x88, 0x00)),
+            ("rgb(95, 135, 255)", Color::Rgb(95, 135, 255)),
+            ("rgb(0,0,0)", Color::Rgb(0, 0, 0)),
+            ("0", Color::Indexed(0)),
+            ("255", Color::Indexed(255)),
+            ("red", Color::Red),
+            ("Light-Blue", Color::LightBlue),
+            ("dark_grey", Color::DarkGray),
+            ("light magenta", Color::LightMagenta),
+            ("reset", Color::Reset),
+            ("  white ", Color::White),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(parse_color(raw).unwrap(), expected, "parsing `{}`", raw);
+        }
+
+        let errors = [
+            ("#12", "expected #rgb or #rrggbb"),
+            ("#gg0000", "expected #rgb or #rrggbb"),
+            ("rgb(1, 2)", "three components"),
+            ("rgb(1, 2, 300)", "`300` is not a number from 0 to 255"),
+            ("256", "out of range 0-255"),
+            ("purple", "unknown color `purple`"),
+            ("", "unknown color"),
+        ];
+        for (raw, message) in errors {
+            let err = parse_color(raw).unwrap_err().to_string();
+            assert!(err.contains(message), "`{}` gave `{}`", raw, err);
+        }
+    }
+
+    #[test]
+    fn test_color_to_string_round_trips() {
+        assert_eq!(color_to_string(Color::Rgb(0x5f, 0x87, 0xff)), "#5f87ff");
+        assert_eq!(color_to_string(Color::Indexed(12)), "12");
+        assert_eq!(color_to_string(Color::DarkGray), "dark_gray");
+
+        let colors = NAMED_COLORS
+            .iter()
+            .map(|(_, color)| *color)
+            .chain([Color::Rgb(1, 2, 3), Color::Indexed(200)]);
+        for color in colors {
+            assert_eq!(parse_color(&color_to_string(color)).unwrap(), color);
+        }
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn test_theme_watcher_reloads_after_edits_settle() {
+        use std::time::{Duration, Instant};
+
+        let dir = std::env::temp_dir().join(format!("weavetui-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("live.toml");
+        std::fs::write(&file, "[colors]\nprimary = \"red\"\n").unwrap();
+
+        let mut watcher = ThemeWatcher::new(&file).unwrap();
+        assert!(watcher.poll(Duration::ZERO).is_none());
+
+        std::fs::write(&file, "[colors]\nprimary = \"blue\"\n").unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let theme = loop {
+            if let Some(result) = watcher.poll(Duration::from_millis(50)) {
+                break result.unwrap();
+            }
+            assert!(Instant::now() < deadline, "no reload after editing the theme");
+            std::thread::sleep(Duration::from_millis(10));
+        };
+
+        assert_eq!(theme.name, "live");
+        assert_eq!(theme.get_color("primary"), Color::Blue);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "theme-files")]
+    #[test]
+    fn test_theme_from_toml() {
+        let theme = Theme::from_toml_str(
+            r##"
+            name = "dark"
+
+            [colors]
+            primary = "#5f87ff"
+            accent = "light-red"
+
+            [styles]
+            "menu.item.selected" = { fg = "black", bg = "12", modifiers = ["bold", "italic"] }
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(theme.name, "dark");
+        assert_eq!(theme.get_color("primary"), Color::Rgb(0x5f, 0x87, 0xff));
+        assert_eq!(theme.get_color("accent"), Color::LightRed);
+        assert_eq!(
+            theme.get_style("menu.item.selected"),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Indexed(12))
+                .add_modifier(Modifier::BOLD | Modifier::ITALIC)
+        );
+
+        assert!(Theme::from_toml_str("[styles]\nitem = { fg = \"nope\" }").is_err());
+        assert!(Theme::from_toml_str("[styles]\nitem = { size = 3 }").is_err());
+
+        let theme = Theme::from_toml_str(
+            "[styles]\nitem = { modifiers = \"dim | italic\", sub_modifiers = [\"bold\"] }",
+        )
+        .unwrap();
+        let style = theme.get_style("item");
+        assert_eq!(style.add_modifier, Modifier::DIM | Modifier::ITALIC);
+        assert_eq!(style.sub_modifier, Modifier::BOLD);
+    }
+}
@@ -6,28 +6,72 @@ use {
         cursor,
         event::{
             DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
-            Event as CrosstermEvent, KeyEventKind,
+            Event as CrosstermEvent, KeyEventKind, KeyboardEnhancementFlags,
+            PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
         },
         terminal::{EnterAlternateScreen, LeaveAlternateScreen},
     },
-    futures_util::{FutureExt, StreamExt},
-    ratatui::backend::CrosstermBackend as Backend,
+    futures_util::{FutureExt, Stream, StreamExt},
+    ratatui::{backend::CrosstermBackend as Backend, TerminalOptions, Viewport},
     std::{
+        io::Write,
         ops::{Deref, DerefMut},
         time::Duration,
     },
     tokio::{
         sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
         task::JoinHandle,
+        time::{Interval, MissedTickBehavior},
     },
     tokio_util::sync::CancellationToken,
 };
 
-pub type IO = std::io::Stdout;
+/// The stream the TUI is drawn on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderTarget {
+    #[default]
+    Stdout,
+    /// Draw on stderr, leaving stdout free to be piped, e.g. `app | grep foo`
+    Stderr,
+}
+
+/// Writer for a [`RenderTarget`]
+#[derive(Debug)]
+pub enum IO {
+    Stdout(std::io::Stdout),
+    Stderr(std::io::Stderr),
+}
+
+impl Write for IO {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            IO::Stdout(out) => out.write(buf),
+            IO::Stderr(err) => err.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            IO::Stdout(out) => out.flush(),
+            IO::Stderr(err) => err.flush(),
+        }
+    }
+}
+
+/// Returns a handle to the stream the TUI is drawn on.
+fn io(target: RenderTarget) -> IO {
+    match target {
+        RenderTarget::Stdout => IO::Stdout(std::io::stdout()),
+        RenderTarget::Stderr => IO::Stderr(std::io::stderr()),
+    }
+}
 
-/// Returns a handle to the standard output.
-fn io() -> IO {
-    std::io::stdout()
+/// The viewport for an inline region `inline_height` rows tall, or the full screen
+fn viewport(inline_height: Option<u16>) -> Viewport {
+    match inline_height {
+        Some(height) => Viewport::Inline(height),
+        None => Viewport::Fullscreen,
+    }
 }
 
 /// TUI wrapper around ratatui terminal
@@ -41,19 +85,54 @@ pub struct Tui {
     pub tick_rate: f64,
     pub mouse: bool,
     pub paste: bool,
+    /// Ask the terminal for the kitty keyboard protocol, if it supports it
+    pub keyboard_enhancement: bool,
+    /// Whether the terminal supports the kitty keyboard protocol, once asked
+    keyboard_enhancement_supported: Option<bool>,
+    /// Keep input typed while suspended instead of discarding it on resume
+    pub keep_events_on_resume: bool,
+    /// Height of the inline viewport, or `None` when drawing on the alternate screen
+    pub inline_height: Option<u16>,
+    /// What the tick and render timers do after falling behind, e.g. during a slow frame
+    pub missed_tick_behavior: MissedTickBehavior,
+    /// The stream the terminal is drawn on and set up through
+    render_target: RenderTarget,
+    /// Whether the terminal is currently set up by `enter` or `resume`
+    entered: bool,
 }
 
 impl Tui {
     /// Create a new TUI instance
     pub fn new() -> anyhow::Result<Self> {
+        Self::with_viewport(Viewport::Fullscreen, RenderTarget::Stdout)
+    }
+
+    /// Create a TUI drawing a region `viewport_height` rows tall below the cursor.
+    ///
+    /// The alternate screen is left alone, so the terminal's scrollback stays intact and the
+    /// last frame remains visible after exit.
+    pub fn inline(viewport_height: u16) -> anyhow::Result<Self> {
+        Self::with_viewport(viewport(Some(viewport_height)), RenderTarget::Stdout)
+    }
+
+    fn with_viewport(viewport: Viewport, render_target: RenderTarget) -> anyhow::Result<Self> {
         let tick_rate = 4.0;
         let frame_rate = 60.0;
-        let terminal = ratatui::Terminal::new(Backend::new(io())).map_err(anyhow::Error::from)?;
+        let inline_height = match viewport {
+            Viewport::Inline(height) => Some(height),
+            _ => None,
+        };
+        let terminal = ratatui::Terminal::with_options(
+            Backend::new(io(render_target)),
+            TerminalOptions { viewport },
+        )
+        .map_err(anyhow::Error::from)?;
         let (event_tx, event_rx) = mpsc::unbounded_channel();
         let cancellation_token = CancellationToken::new();
         let task = tokio::task::spawn(async {});
         let mouse = false;
         let paste = false;
+        let keep_events_on_resume = false;
         Ok(Self {
             terminal,
             task,
@@ -64,6 +143,13 @@ impl Tui {
             tick_rate,
             mouse,
             paste,
+            keyboard_enhancement: false,
+            keyboard_enhancement_supported: None,
+            keep_events_on_resume,
+            inline_height,
+            missed_tick_behavior: MissedTickBehavior::Skip,
+            render_target,
+            entered: false,
         })
     }
 
@@ -91,6 +177,99 @@ impl Tui {
         self
     }
 
+    /// Enable the kitty keyboard protocol on terminals that support it.
+    ///
+    /// Keys the legacy encoding can't tell apart, like ctrl-i and tab or ctrl-enter and
+    /// enter, then arrive as distinct key events. Held keys still repeat as presses, and
+    /// releases aren't reported. Support is checked once, the first time
+    /// the TUI is entered; terminals without it, and UIs drawn on stderr, where the check
+    /// can't be answered, keep the legacy encoding. The flags are popped again on exit.
+    pub fn keyboard_enhancement(mut self, enhance: bool) -> Self {
+        self.keyboard_enhancement = enhance;
+        self
+    }
+
+    /// Set what the tick and render timers do after falling behind.
+    ///
+    /// The default, `MissedTickBehavior::Skip`, drops the missed ticks and waits for the next
+    /// one on schedule, so a hitch isn't followed by a burst of catch-up ticks. `Burst`
+    /// delivers every missed tick right away and `Delay` restarts the schedule from the
+    /// late tick.
+    pub fn missed_tick_behavior(mut self, behavior: MissedTickBehavior) -> Self {
+        self.missed_tick_behavior = behavior;
+        self
+    }
+
+    /// Draw on `target` instead of stdout.
+    ///
+    /// With `RenderTarget::Stderr`, the TUI is drawn and the terminal set up through stderr,
+    /// so stdout can be piped or used for logging while the app runs. Must be called before
+    /// the TUI is entered, as it recreates the terminal.
+    pub fn render_to(self, target: RenderTarget) -> anyhow::Result<Self> {
+        if target == self.render_target {
+            return Ok(self);
+        }
+        let terminal = ratatui::Terminal::with_options(
+            Backend::new(io(target)),
+            TerminalOptions { viewport: viewport(self.inline_height) },
+        )
+        .map_err(anyhow::Error::from)?;
+        let mut tui = self;
+        tui.terminal = terminal;
+        tui.render_target = target;
+        Ok(tui)
+    }
+
+    /// Get the stream the TUI is drawn on
+    pub fn render_target(&self) -> RenderTarget {
+        self.render_target
+    }
+
+    /// Keep input typed while suspended instead of discarding it on resume
+    pub fn keep_events_on_resume(mut self, keep: bool) -> Self {
+        self.keep_events_on_resume = keep;
+        self
+    }
+
+    /// Whether the TUI draws inline instead of on the alternate screen
+    pub fn is_inline(&self) -> bool {
+        self.inline_height.is_some()
+    }
+
+    /// Get the size of the terminal as `(width, height)`, e.g. before anything is drawn
+    pub fn size(&self) -> anyhow::Result<(u16, u16)> {
+        let size = self.terminal.size()?;
+        Ok((size.width, size.height))
+    }
+
+    /// Whether the terminal is set up for the TUI, i.e. entered and not exited since
+    pub fn is_entered(&self) -> bool {
+        self.entered
+    }
+
+    fn modes(&self) -> TerminalModes {
+        TerminalModes {
+            mouse: self.mouse,
+            paste: self.paste,
+            keyboard_enhancement: self.keyboard_enhancement
+                && self.keyboard_enhancement_supported == Some(true),
+            inline: self.is_inline(),
+            target: self.render_target,
+        }
+    }
+
+    /// Ask the terminal whether it supports the kitty keyboard protocol, if enabled and not
+    /// asked yet. Must run while the event loop is stopped, as the answer is read as input.
+    fn check_keyboard_enhancement(&mut self) {
+        if !self.keyboard_enhancement || self.keyboard_enhancement_supported.is_some() {
+            return;
+        }
+        let supported = keyboard_enhancement_supported(self.render_target, || {
+            crossterm::terminal::supports_keyboard_enhancement().unwrap_or(false)
+        });
+        self.keyboard_enhancement_supported = Some(supported);
+    }
+
     /// Start the event loop
     pub fn start(&mut self) {
         let tick_delay = std::time::Duration::from_secs_f64(1.0 / self.tick_rate);
@@ -99,64 +278,14 @@ impl Tui {
         self.cancellation_token = CancellationToken::new();
         let _cancellation_token = self.cancellation_token.clone();
         let _event_tx = self.event_tx.clone();
-        self.task = tokio::spawn(async move {
-            let mut reader = crossterm::event::EventStream::new();
-            let mut tick_interval = tokio::time::interval(tick_delay);
-            let mut render_interval = tokio::time::interval(render_delay);
-            _event_tx
-                .send(Event::Init)
-                .expect("Failed to send Init event");
-            loop {
-                let tick_delay = tick_interval.tick();
-                let render_delay = render_interval.tick();
-                let crossterm_event = reader.next().fuse();
-                tokio::select! {
-                    _ = _cancellation_token.cancelled() => {
-                        println!("Tui task cancelled");
-                        break;
-                    }
-                    maybe_event = crossterm_event => {
-                        match maybe_event {
-                        Some(Ok(evt)) => {
-                            match evt {
-                                CrosstermEvent::Key(key) => {
-                                    if key.kind == KeyEventKind::Press {
-                                        _event_tx.send(Event::Key(key)).expect("Failed to send Key event");
-                                    }
-                                },
-                                CrosstermEvent::Mouse(mouse) => {
-                                    _event_tx.send(Event::Mouse(mouse)).expect("Failed to send Mouse event");
-                                },
-                                CrosstermEvent::Resize(x, y) => {
-                                    _event_tx.send(Event::Resize(x, y)).expect("Failed to send Resize event");
-                                },
-                                CrosstermEvent::FocusLost => {
-                                    _event_tx.send(Event::FocusLost).expect("Failed to send FocusLost event");
-                                },
-                                CrosstermEvent::FocusGained => {
-                                    _event_tx.send(Event::FocusGained).expect("Failed to send FocusGained event");
-                                },
-                                CrosstermEvent::Paste(s) => {
-                                    _event_tx.send(Event::Paste(s)).expect("Failed to send Paste event");
-                                },
-
-                            }
-                        }
-                        Some(Err(_)) => {
-                            _event_tx.send(Event::Error).expect("Failed to send Error event");
-                        }
-                        None => {},
-                        }
-                    },
-                    _ = tick_delay => {
-                        _event_tx.send(Event::Tick).expect("Failed to send Tick event");
-                    },
-                    _ = render_delay => {
-                        _event_tx.send(Event::Render).expect("Failed to send Render event");
-                    },
-                }
-            }
-        });
+        let missed_tick_behavior = self.missed_tick_behavior;
+        self.task = tokio::spawn(forward_events(
+            crossterm::event::EventStream::new(),
+            _event_tx,
+            _cancellation_token,
+            interval(tick_delay, missed_tick_behavior),
+            interval(render_delay, missed_tick_behavior),
+        ));
     }
 
     /// Stop the event loop
@@ -179,36 +308,31 @@ impl Tui {
         Ok(())
     }
 
-    /// Switch to fullscreen mode
+    /// Switch to fullscreen mode and start the event loop.
+    ///
+    /// Does nothing if the TUI is already entered.
     pub fn enter(&mut self) -> anyhow::Result<()> {
-        crossterm::terminal::enable_raw_mode().map_err(anyhow::Error::from)?;
-        crossterm::execute!(io(), EnterAlternateScreen, cursor::Hide)
-            .map_err(anyhow::Error::from)?;
-        if self.mouse {
-            crossterm::execute!(io(), EnableMouseCapture).map_err(anyhow::Error::from)?;
-        }
-        if self.paste {
-            crossterm::execute!(io(), EnableBracketedPaste).map_err(anyhow::Error::from)?;
+        self.check_keyboard_enhancement();
+        let modes = self.modes();
+        if transition(&mut self.entered, true, || modes.enable())? {
+            self.start();
         }
-        self.start();
         Ok(())
     }
 
-    /// Return to normal terminal mode
+    /// Stop the event loop and return to normal terminal mode.
+    ///
+    /// The terminal is only restored if it was entered, so calling this again (e.g. from
+    /// `Drop` after an explicit exit) only makes sure the event loop is stopped.
     pub fn exit(&mut self) -> anyhow::Result<()> {
         self.stop()?;
-        if crossterm::terminal::is_raw_mode_enabled().map_err(anyhow::Error::from)? {
+        if self.entered {
             self.flush().map_err(anyhow::Error::from)?;
-            if self.paste {
-                crossterm::execute!(io(), DisableBracketedPaste).map_err(anyhow::Error::from)?;
-            }
-            if self.mouse {
-                crossterm::execute!(io(), DisableMouseCapture).map_err(anyhow::Error::from)?;
-            }
-            crossterm::execute!(io(), LeaveAlternateScreen, cursor::Show)
-                .map_err(anyhow::Error::from)?;
-            crossterm::terminal::disable_raw_mode().map_err(anyhow::Error::from)?;
         }
+
+        let modes = self.modes();
+        let bottom = self.terminal.get_frame().area().bottom();
+        transition(&mut self.entered, false, || modes.disable(bottom))?;
         Ok(())
     }
 
@@ -222,15 +346,237 @@ impl Tui {
         self.exit()
     }
 
-    /// Return to fullscreen mode after suspend
+    /// Return to fullscreen mode after suspend.
+    ///
+    /// Input typed while suspended is discarded unless `keep_events_on_resume` is set.
     pub fn resume(&mut self) -> anyhow::Result<()> {
-        self.enter()
+        self.check_keyboard_enhancement();
+        let modes = self.modes();
+        if !transition(&mut self.entered, true, || modes.enable())? {
+            return Ok(());
+        }
+        if !self.keep_events_on_resume {
+            self.flush_pending_events()?;
+        }
+        self.start();
+        Ok(())
+    }
+
+    /// Discard queued key, mouse and paste events, returning how many were dropped.
+    ///
+    /// Drains both the terminal's input buffer and events not yet taken with [`Tui::next`].
+    /// Other events, like resizes, are kept. Call this while the event loop is stopped,
+    /// otherwise it races with the loop reading the terminal.
+    pub fn flush_pending_events(&mut self) -> anyhow::Result<usize> {
+        let mut dropped = 0;
+        while crossterm::event::poll(Duration::ZERO)? {
+            match crossterm::event::read()? {
+                CrosstermEvent::Resize(x, y) => {
+                    let _ = self.event_tx.send(Event::Resize(x, y));
+                }
+                _ => dropped += 1,
+            }
+        }
+
+        Ok(dropped + discard_queued_input(&mut self.event_rx, &self.event_tx))
     }
 
     /// Get the next event from the queue
     pub async fn next(&mut self) -> Option<Event> {
         self.event_rx.recv().await
     }
+
+    /// Get the next event if one is already queued, without waiting
+    pub fn try_next(&mut self) -> Option<Event> {
+        self.event_rx.try_recv().ok()
+    }
+}
+
+/// Terminal modes switched on when entering the TUI and back off when exiting
+#[derive(Clone, Copy, Debug)]
+struct TerminalModes {
+    mouse: bool,
+    paste: bool,
+    keyboard_enhancement: bool,
+    inline: bool,
+    target: RenderTarget,
+}
+
+impl TerminalModes {
+    /// Enable raw mode, the alternate screen and the configured input modes
+    fn enable(self) -> anyhow::Result<()> {
+        crossterm::terminal::enable_raw_mode().map_err(anyhow::Error::from)?;
+        self.write_enable(&mut io(self.target)).map_err(anyhow::Error::from)?;
+        Ok(())
+    }
+
+    /// Undo `enable`; an inline viewport leaves the cursor on row `bottom`, below it.
+    ///
+    /// Every step is tried even if an earlier one fails, so a partly enabled terminal is
+    /// restored as far as possible; the first error is returned.
+    fn disable(self, bottom: u16) -> anyhow::Result<()> {
+        let written = self.write_disable(&mut io(self.target), bottom);
+        let raw = crossterm::terminal::disable_raw_mode();
+        written.and(raw).map_err(anyhow::Error::from)
+    }
+
+    /// Write the escape sequences switching on the screen and input modes
+    fn write_enable(self, out: &mut impl Write) -> std::io::Result<()> {
+        if self.inline {
+            crossterm::execute!(out, cursor::Hide)?;
+        } else {
+            crossterm::execute!(out, EnterAlternateScreen, cursor::Hide)?;
+        }
+        if self.mouse {
+            crossterm::execute!(out, EnableMouseCapture)?;
+        }
+        if self.paste {
+            crossterm::execute!(out, EnableBracketedPaste)?;
+        }
+        if self.keyboard_enhancement {
+            // Only presses are forwarded, so reporting repeats and releases would turn held
+            // keys into a single press
+            let flags = KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES;
+            crossterm::execute!(out, PushKeyboardEnhancementFlags(flags))?;
+        }
+        Ok(())
+    }
+
+    /// Write the escape sequences switching the modes of `write_enable` back off, trying
+    /// every one and returning the first error
+    fn write_disable(self, out: &mut impl Write, bottom: u16) -> std::io::Result<()> {
+        let mut result = Ok(());
+        let mut step = |written: std::io::Result<()>| {
+            if result.is_ok() {
+                result = written;
+            }
+        };
+        if self.keyboard_enhancement {
+            step(crossterm::execute!(out, PopKeyboardEnhancementFlags));
+        }
+        if self.paste {
+            step(crossterm::execute!(out, DisableBracketedPaste));
+        }
+        if self.mouse {
+            step(crossterm::execute!(out, DisableMouseCapture));
+        }
+        if self.inline {
+            // Keep the shell prompt from overwriting the last frame
+            step(crossterm::execute!(out, cursor::MoveTo(0, bottom), cursor::Show));
+            step(writeln!(out));
+        } else {
+            step(crossterm::execute!(out, LeaveAlternateScreen, cursor::Show));
+        }
+        result
+    }
+}
+
+/// Whether the kitty keyboard protocol can be used when drawing on `target`, asking the
+/// terminal with `query` if it can be answered
+fn keyboard_enhancement_supported(target: RenderTarget, query: impl FnOnce() -> bool) -> bool {
+    // The query is written to stdout, which may be piped when drawing on stderr
+    target == RenderTarget::Stdout && query()
+}
+
+/// Run `switch` to move `entered` to `target`, unless it's already there.
+///
+/// Returns whether it switched. `entered` moves before `switch` runs, so a partly failed
+/// enter is still undone by the next exit, and a failed exit isn't repeated on drop.
+fn transition(
+    entered: &mut bool,
+    target: bool,
+    switch: impl FnOnce() -> anyhow::Result<()>,
+) -> anyhow::Result<bool> {
+    if *entered == target {
+        return Ok(false);
+    }
+
+    *entered = target;
+    switch()?;
+    Ok(true)
+}
+
+/// Drop queued input events, re-queueing the rest in order. Returns how many were dropped.
+fn discard_queued_input(rx: &mut UnboundedReceiver<Event>, tx: &UnboundedSender<Event>) -> usize {
+    let mut queued = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        queued.push(event);
+    }
+
+    let before = queued.len();
+    queued.retain(|event| !matches!(event, Event::Key(_) | Event::Mouse(_) | Event::Paste(_)));
+    let dropped = before - queued.len();
+
+    for event in queued {
+        let _ = tx.send(event);
+    }
+    dropped
+}
+
+/// Send terminal input, ticks and render requests to the app until cancelled.
+///
+/// Stops when the app's receiver is gone, and asks the app to quit when the input stream ends.
+async fn forward_events(
+    mut reader: impl Stream<Item = std::io::Result<CrosstermEvent>> + Unpin,
+    event_tx: UnboundedSender<Event>,
+    cancellation_token: CancellationToken,
+    mut tick_interval: Interval,
+    mut render_interval: Interval,
+) {
+    let started = std::time::Instant::now();
+    let mut tick_count = 0;
+    if event_tx.send(Event::Init).is_err() {
+        return;
+    }
+    loop {
+        let tick_delay = tick_interval.tick();
+        let render_delay = render_interval.tick();
+        let crossterm_event = reader.next().fuse();
+        let event = tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                break;
+            }
+            maybe_event = crossterm_event => {
+                match maybe_event {
+                    Some(Ok(evt)) => match evt {
+                        CrosstermEvent::Key(key) if key.kind == KeyEventKind::Press => {
+                            Event::Key(key)
+                        }
+                        CrosstermEvent::Key(_) => continue,
+                        CrosstermEvent::Mouse(mouse) => Event::Mouse(mouse),
+                        CrosstermEvent::Resize(x, y) => Event::Resize(x, y),
+                        CrosstermEvent::FocusLost => Event::FocusLost,
+                        CrosstermEvent::FocusGained => Event::FocusGained,
+                        CrosstermEvent::Paste(s) => Event::Paste(s),
+                    },
+                    Some(Err(_)) => Event::Error,
+                    // The input stream has ended and would keep yielding nothing, so
+                    // ask the app to quit instead of polling it in a tight loop
+                    None => {
+                        let _ = event_tx.send(Event::Quit);
+                        break;
+                    }
+                }
+            },
+            _ = tick_delay => {
+                tick_count += 1;
+                Event::Tick { count: tick_count, elapsed: started.elapsed() }
+            },
+            _ = render_delay => Event::Render,
+        };
+
+        // The receiver is gone, e.g. the `Tui` was dropped during teardown
+        if event_tx.send(event).is_err() {
+            break;
+        }
+    }
+}
+
+/// Create a timer firing every `period`, starting now
+fn interval(period: Duration, missed_tick_behavior: MissedTickBehavior) -> Interval {
+    let mut interval = tokio::time::interval(period);
+    interval.set_missed_tick_behavior(missed_tick_behavior);
+    interval
 }
 
 impl Deref for Tui {
@@ -252,3 +598,211 @@ impl Drop for Tui {
         self.exit().expect("Failed to exit Tui cleanly during drop");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyEvent};
+
+    #[tokio::test]
+    async fn test_event_forwarding_stops_when_either_end_closes() {
+        let timer = || interval(Duration::from_secs(3600), MissedTickBehavior::Skip);
+
+        // The input stream ending asks the app to quit
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let input = futures_util::stream::iter([Ok(CrosstermEvent::FocusLost)]);
+        forward_events(input, tx, CancellationToken::new(), timer(), timer()).await;
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        assert!(matches!(events.first(), Some(Event::Init)));
+        assert!(events.iter().any(|event| matches!(event, Event::FocusLost)));
+        assert!(matches!(events.last(), Some(Event::Quit)));
+
+        // The app going away stops forwarding even while input is still open
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let input = futures_util::stream::pending();
+        let token = CancellationToken::new();
+        let task = tokio::spawn(forward_events(input, tx, token, timer(), timer()));
+        assert!(matches!(rx.recv().await, Some(Event::Init)));
+        drop(rx);
+        task.await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_missed_ticks_are_skipped_unless_asked_to_burst() {
+        // Count the ticks that are ready right after the timer falls three periods behind
+        async fn ticks_after_a_hitch(behavior: MissedTickBehavior) -> usize {
+            let period = Duration::from_millis(50);
+            let mut timer = interval(period, behavior);
+            timer.tick().await;
+            tokio::time::advance(period * 3 + period / 2).await;
+
+            let mut ready = 0;
+            while tokio::time::timeout(Duration::from_millis(10), timer.tick())
+                .await
+                .is_ok()
+            {
+                ready += 1;
+            }
+            ready
+        }
+
+        let default = crate::app::AppConfig::default().missed_tick_behavior;
+        assert_eq!(ticks_after_a_hitch(default).await, 1);
+        assert_eq!(ticks_after_a_hitch(MissedTickBehavior::Burst).await, 3);
+    }
+
+    #[test]
+    fn test_queued_input_is_dropped_on_resume() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        tx.send(Event::Key(KeyEvent::from(KeyCode::Char('q')))).unwrap();
+        tx.send(Event::Resize(80, 24)).unwrap();
+        tx.send(Event::Paste("stale".to_string())).unwrap();
+        tx.send(Event::Render).unwrap();
+
+        assert_eq!(discard_queued_input(&mut rx, &tx), 2);
+
+        assert!(matches!(rx.try_recv(), Ok(Event::Resize(80, 24))));
+        assert!(matches!(rx.try_recv(), Ok(Event::Render)));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_repeated_enter_and_exit_switch_modes_once() {
+        let mut entered = false;
+        let mut switches = 0;
+        let mut switch = |target| {
+            transition(&mut entered, target, || {
+                switches += 1;
+                Ok(())
+            })
+            .unwrap()
+        };
+
+        assert!(switch(true));
+        assert!(!switch(true));
+        assert!(switch(false));
+        assert!(!switch(false));
+        assert!(switch(true));
+        assert_eq!(switches, 3);
+
+        // A failed enter still counts as entered, so exiting undoes whatever it enabled
+        let mut entered = false;
+        assert!(transition(&mut entered, true, || anyhow::bail!("no tty")).is_err());
+        assert!(entered);
+        let mut restored = false;
+        assert!(transition(&mut entered, false, || {
+            restored = true;
+            Ok(())
+        })
+        .unwrap());
+        assert!(restored);
+    }
+
+    #[test]
+    fn test_disable_tries_every_step() {
+        struct Failing(Vec<u8>);
+        impl Write for Failing {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.extend_from_slice(buf);
+                Err(std::io::Error::other("closed"))
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let modes = TerminalModes {
+            mouse: true,
+            paste: true,
+            keyboard_enhancement: true,
+            inline: false,
+            target: RenderTarget::Stdout,
+        };
+        let mut out = Failing(Vec::new());
+        assert!(modes.write_disable(&mut out, 0).is_err());
+
+        // Each step still wrote, and the alternate screen was left last
+        let written = String::from_utf8(out.0).unwrap();
+        assert!(written.starts_with("\x1b[<1u"), "{written:?}");
+        assert!(written.contains("\x1b[?2004l"), "{written:?}");
+        assert!(written.contains("\x1b[?1049l"), "{written:?}");
+    }
+
+    #[test]
+    fn test_keyboard_flags_are_pushed_and_popped() {
+        let modes = |keyboard_enhancement| TerminalModes {
+            mouse: false,
+            paste: false,
+            keyboard_enhancement,
+            inline: true,
+            target: RenderTarget::Stdout,
+        };
+        let written = |modes: TerminalModes| {
+            let mut enable = Vec::new();
+            modes.write_enable(&mut enable).unwrap();
+            let mut disable = Vec::new();
+            modes.write_disable(&mut disable, 0).unwrap();
+            (String::from_utf8(enable).unwrap(), String::from_utf8(disable).unwrap())
+        };
+
+        // Only disambiguation, so key repeats keep arriving as presses
+        let (enable, disable) = written(modes(true));
+        assert!(enable.ends_with("\x1b[>1u"), "{enable:?}");
+        assert!(disable.starts_with("\x1b[<1u"), "{disable:?}");
+
+        let (enable, disable) = written(modes(false));
+        assert!(!enable.contains("u") && !disable.contains("\x1b[<"), "{enable:?} {disable:?}");
+    }
+
+    #[test]
+    fn test_inline_viewport_stays_off_the_alternate_screen() {
+        let options = TerminalOptions { viewport: viewport(Some(3)) };
+        let mut terminal =
+            ratatui::Terminal::with_options(ratatui::backend::TestBackend::new(20, 10), options)
+                .unwrap();
+        let mut area = Default::default();
+        terminal.draw(|f| area = f.area()).unwrap();
+        assert_eq!((area.width, area.height), (20, 3));
+        assert!(matches!(viewport(None), Viewport::Fullscreen));
+
+        let modes = TerminalModes {
+            mouse: false,
+            paste: false,
+            keyboard_enhancement: false,
+            inline: true,
+            target: RenderTarget::Stdout,
+        };
+        let mut enable = Vec::new();
+        modes.write_enable(&mut enable).unwrap();
+        let mut disable = Vec::new();
+        modes.write_disable(&mut disable, 3).unwrap();
+        let (enable, disable) =
+            (String::from_utf8(enable).unwrap(), String::from_utf8(disable).unwrap());
+        assert!(!enable.contains("\x1b[?1049"), "{enable:?}");
+        assert!(!disable.contains("\x1b[?1049"), "{disable:?}");
+
+        // The cursor is left below the last frame, on the row after the viewport
+        assert!(disable.starts_with("\x1b[4;1H"), "{disable:?}");
+    }
+
+    #[test]
+    fn test_stderr_target_writes_to_stderr() {
+        assert!(matches!(io(RenderTarget::Stdout), IO::Stdout(_)));
+        let mut out = io(RenderTarget::Stderr);
+        assert!(matches!(out, IO::Stderr(_)));
+        assert_eq!(out.write(b"").unwrap(), 0);
+        out.flush().unwrap();
+    }
+
+    #[test]
+    fn test_keyboard_enhancement_is_only_queried_on_stdout() {
+        assert!(keyboard_enhancement_supported(RenderTarget::Stdout, || true));
+        assert!(!keyboard_enhancement_supported(RenderTarget::Stdout, || false));
+        assert!(!keyboard_enhancement_supported(RenderTarget::Stderr, || {
+            panic!("the terminal can't answer on stderr")
+        }));
+    }
+}
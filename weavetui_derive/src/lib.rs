@@ -8,6 +8,49 @@ use syn::{
 };
 mod args;
 
+/// Lets an app-defined action type be used in keybindings, e.g.
+/// `kb!["<s>" => MyAction::Save]`.
+///
+/// Generates `From<MyAction>` for both `ActionKind` and `Action`, wrapping the value in
+/// `Action::Custom`. Components receive it in `update` and get it back with
+/// `action.as_custom::<MyAction>()`. The type must be `Debug + PartialEq + Send + Sync + 'static`.
+///
+/// ```
+/// use weavetui_core::{event::Action, keyboard::KeyBindings, kb};
+/// use weavetui_derive::IntoAction;
+///
+/// #[derive(Debug, PartialEq, IntoAction)]
+/// enum EditorAction {
+///     Save,
+/// }
+///
+/// let keybindings = KeyBindings::new(kb!["<ctrl-s>" => EditorAction::Save]);
+/// let action = keybindings.0.values().next().unwrap();
+/// assert_eq!(action.as_custom::<EditorAction>(), Some(&EditorAction::Save));
+/// assert_eq!(*action, Action::from(EditorAction::Save));
+/// ```
+#[proc_macro_derive(IntoAction)]
+pub fn into_action(item: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(item as syn::DeriveInput);
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::core::convert::From<#name #ty_generics> for weavetui_core::event::Action #where_clause {
+            fn from(action: #name #ty_generics) -> Self {
+                weavetui_core::event::Action::custom(action)
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#name #ty_generics> for weavetui_core::event::ActionKind #where_clause {
+            fn from(action: #name #ty_generics) -> Self {
+                weavetui_core::event::ActionKind::Full(weavetui_core::event::Action::custom(action))
+            }
+        }
+    }
+    .into()
+}
+
 #[proc_macro_attribute]
 pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
     let mut ast = parse_macro_input!(item as ItemStruct);
@@ -17,6 +60,8 @@ pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     let mut children_entries: Option<Punctuated<args::ChildEntry, syn::token::Comma>> = None;
     let mut default_component_impl = false;
+    let mut container_component_impl = false;
+    let mut serde_impl = false;
 
     // Redux-specific attributes
     let mut state_type: Option<Type> = None;
@@ -64,6 +109,10 @@ pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
                         let ident: Ident = input.parse()?;
                         if ident == "default" {
                             default_component_impl = true;
+                        } else if ident == "container" {
+                            container_component_impl = true;
+                        } else if ident == "serde" {
+                            serde_impl = true;
                         } else {
                             return Err(lookahead.error());
                         }
@@ -79,6 +128,10 @@ pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
         .expect("Failed to parse attribute");
     }
 
+    if default_component_impl && container_component_impl {
+        panic!("#[component] accepts either `default` or `container`, not both");
+    }
+
     // Validate Redux attributes if this is a Redux component
     if is_redux_component {
         if state_type.is_none() {
@@ -344,6 +397,46 @@ pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     };
 
+    // Snapshot every user field, skipping the context and Redux plumbing
+    let serde_methods = if serde_impl {
+        let mut fields = Vec::new();
+        if let Fields::Named(FieldsNamed { named, .. }) = &ast.fields {
+            for field in named.iter() {
+                let field_name = field.ident.as_ref().unwrap();
+                if field_name == "_ctx"
+                    || (is_redux_component
+                        && (field_name == "store_connection" || field_name == "current_state"))
+                {
+                    continue;
+                }
+                fields.push(field_name.clone());
+            }
+        }
+        let keys = fields.iter().map(|field| field.to_string()).collect::<Vec<_>>();
+
+        quote! {
+            fn snapshot(&self) -> Option<weavetui_core::serde_json::Value> {
+                let mut map = weavetui_core::serde_json::Map::new();
+                #(
+                    map.insert(#keys.to_string(), weavetui_core::serde_json::to_value(&self.#fields).ok()?);
+                )*
+                Some(weavetui_core::serde_json::Value::Object(map))
+            }
+
+            fn restore(&mut self, value: &weavetui_core::serde_json::Value) {
+                #(
+                    if let Some(field) = value.get(#keys) {
+                        if let Ok(field) = weavetui_core::serde_json::from_value(field.clone()) {
+                            self.#fields = field;
+                        }
+                    }
+                )*
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     let component_impl = if default_component_impl {
         quote! {
             impl weavetui_core::Component for #name {
@@ -361,6 +454,23 @@ pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
                 }
             }
         }
+    } else if container_component_impl {
+        // Containers only arrange children, which `handle_draw` renders after `draw`
+        quote! {
+            impl weavetui_core::Component for #name {
+                fn draw(&mut self, _f: &mut ratatui::Frame<'_>, _area: ratatui::layout::Rect) {}
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let container_method = if container_component_impl {
+        quote! {
+            fn is_container(&self) -> bool {
+                true
+            }
+        }
     } else {
         quote! {}
     };
@@ -374,7 +484,10 @@ pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
 
         impl weavetui_core::ComponentAccessor for #name {
             fn name(&self) -> String {
-                stringify!(#name).to_string()
+                self._ctx
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| stringify!(#name).to_string())
             }
 
             fn area(&self) -> Option<ratatui::layout::Rect> {
@@ -394,14 +507,16 @@ pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
                 (self as &mut dyn weavetui_core::Component).on_active_changed(active);
             }
 
-            fn register_action_handler(&mut self, tx: tokio::sync::mpsc::UnboundedSender<weavetui_core::event::Action>) {
-                self._ctx.action_tx = Some(tx);
+            fn is_focused(&self) -> bool {
+                self._ctx.focused
             }
 
-            fn send(&self, action: &str) {
-                if let Some(tx) = &self._ctx.action_tx {
-                    let _ = tx.send(weavetui_core::event::Action::AppAction(action.to_string()));
-                }
+            fn set_focused(&mut self, focused: bool) {
+                self._ctx.focused = focused;
+            }
+
+            fn register_action_handler(&mut self, tx: tokio::sync::mpsc::UnboundedSender<weavetui_core::event::Action>) {
+                self._ctx.action_tx = Some(tx);
             }
 
             fn send_action(&self, action: weavetui_core::event::Action) {
@@ -412,6 +527,14 @@ pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
 
 
 
+            fn get_context(&self) -> &weavetui_core::ComponentContext {
+                &self._ctx
+            }
+
+            fn get_context_mut(&mut self) -> &mut weavetui_core::ComponentContext {
+                &mut self._ctx
+            }
+
             fn get_children(&mut self) -> &mut weavetui_core::Children {
                 &mut self._ctx.children
             }
@@ -421,8 +544,12 @@ pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
             }
 
             fn set_theme_manager(&mut self, theme_manager: weavetui_core::theme::ThemeManager) {
-                self._ctx.theme_manager = theme_manager.clone();
+                self._ctx.theme_manager = theme_manager;
             }
+
+            #container_method
+
+            #serde_methods
         }
 
 